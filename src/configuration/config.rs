@@ -1,17 +1,76 @@
 use crate::data_providers::command::Cmd;
 use crate::data_providers::coverage_parser::CoverageParser;
+use crate::data_providers::lcov_parser::LcovCoverageParser;
+use crate::data_providers::llvm_cov_parser::LlvmCovParser;
+use crate::entities::coverage::{FileMinimum, MinimumCoverage, RegressionThreshold};
+use crate::entities::coverage_backend::CoverageBackend;
+use crate::entities::coverage_report::{ReportFormat, ReportLocation};
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::coverage_trigger::CoverageTrigger;
+use crate::entities::custom_stage::{CustomStage, PipelineDef};
+use crate::entities::fmt::FmtMode;
+use crate::entities::hook::Hook;
 use crate::entities::ignored_path::IgnoredPath;
-use crate::result::{CfgErr, CoverageParseErr};
-use crate::use_cases::output_parser::Parser;
+use crate::entities::notification::NotifyPolicy;
+use crate::entities::stage::Stage;
+use crate::result::{CfgErr, CoverageParseErr, HookErr, PipelineErr};
+use crate::use_cases::bus::BusEvent;
 
 use derive_builder::Builder;
+use glob::Pattern;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const COVERAGE_BACKEND_VAR: &str = "CHESTER_COVERAGE_BACKEND";
+const COVERAGE_REPORT_VAR: &str = "CHESTER_COVERAGE_REPORT";
+const COVERAGE_TRIGGER_VAR: &str = "CHESTER_COVERAGE_TRIGGER";
+const COVERAGE_REGRESSION_VAR: &str = "CHESTER_COVERAGE_REGRESSION";
+const COVERAGE_MIN_VAR: &str = "CHESTER_COVERAGE_MIN";
+const COVERAGE_MIN_FILES_VAR: &str = "CHESTER_COVERAGE_MIN_FILES";
+const DIFF_BASE_VAR: &str = "CHESTER_DIFF_BASE";
+const NOTIFY_VAR: &str = "CHESTER_NOTIFY";
+const HOOKS_VAR: &str = "CHESTER_HOOKS";
+const HOOK_TIMEOUT_VAR: &str = "CHESTER_HOOK_TIMEOUT";
+const PIPELINE_VAR: &str = "CHESTER_PIPELINE";
+const MAX_PARALLEL_STAGES_VAR: &str = "CHESTER_MAX_PARALLEL_STAGES";
+const CONTINUE_ON_FAILURE_VAR: &str = "CHESTER_CONTINUE_ON_FAILURE";
+/// Stands for the check failed on warnings only in `CHESTER_CONTINUE_ON_FAILURE`.
+const WARNINGS: &str = "warnings";
+const FMT_VAR: &str = "CHESTER_FMT";
+const ONE_SHOT_VAR: &str = "CHESTER_ONE_SHOT";
 
 pub fn cfg() -> Result<Config, CfgErr> {
+    let coverage_backend = coverage_backend()?;
+    let diff_base = diff_base();
     Ok(ConfigBuilder::default()
         .tests_cmd(tests_cmd())
         .list_tests_cmd(list_tests_cmd())
         .check_cmd(check_cmd())
-        .coverage_cmd(coverage_cmd())
+        .fmt_cmd(fmt_cmd())
+        .fmt_mode(fmt_mode()?)
+        .coverage_backend(coverage_backend)
+        .coverage_cmd(coverage_cmd(coverage_backend))
+        .coverage_report(coverage_report(coverage_backend)?)
+        .coverage_trigger(coverage_trigger()?)
+        .coverage_regression(coverage_regression()?)
+        .coverage_minimum(coverage_minimum()?)
+        .head_cmd(head_cmd())
+        .diff_cmd(diff_cmd(&diff_base))
+        .diff_base(diff_base)
+        .status_cmd(status_cmd())
+        .toplevel_cmd(toplevel_cmd())
+        .history_path(history_path()?)
+        .notify_policy(notify_policy()?)
+        .notify_cmd(notify_cmd())
+        .hooks(hooks()?)
+        .hook_timeout(hook_timeout()?)
+        .custom_stages(custom_stages()?)
+        .max_parallel_stages(max_parallel_stages()?)
+        .continue_on_failure(continue_on_failure()?)
+        .continue_on_warnings(continue_on_warnings())
+        .one_shot(one_shot())
         .ignored_paths(vec![IgnoredPath::new("target")?, IgnoredPath::new(".git")?])
         .build()?)
 }
@@ -25,24 +84,316 @@ fn list_tests_cmd() -> Cmd {
 }
 
 fn check_cmd() -> Cmd {
-    Cmd::new("cargo", &["check"])
+    Cmd::new("cargo", &["check", "--message-format=json"])
+}
+
+fn fmt_cmd() -> Cmd {
+    Cmd::new("cargo", &["fmt", "--check", "--message-format", "short"])
+}
+
+fn fmt_mode() -> Result<FmtMode, CfgErr> {
+    match env::var(FMT_VAR) {
+        Ok(mode) => Ok(mode.parse()?),
+        Err(_) => Ok(FmtMode::default()),
+    }
+}
+
+fn coverage_backend() -> Result<CoverageBackend, CfgErr> {
+    match env::var(COVERAGE_BACKEND_VAR) {
+        Ok(backend) => Ok(backend.parse()?),
+        Err(_) => Ok(CoverageBackend::default()),
+    }
+}
+
+fn coverage_report(backend: CoverageBackend) -> Result<Option<ReportLocation>, CfgErr> {
+    if let Ok(path) = env::var(COVERAGE_REPORT_VAR) {
+        let Some(format) = ReportFormat::from_path(&path) else {
+            return Err(CfgErr::CoverageReport(path));
+        };
+        return Ok(Some(ReportLocation::new(path, format)));
+    }
+    Ok(match backend {
+        CoverageBackend::Tarpaulin => Some(ReportLocation::new(
+            PathBuf::from("tarpaulin-target").join("lcov.info"),
+            ReportFormat::Lcov,
+        )),
+        CoverageBackend::LlvmCov => Some(ReportLocation::new(
+            PathBuf::from("target").join("llvm-cov").join("lcov.info"),
+            ReportFormat::Lcov,
+        )),
+        CoverageBackend::Grcov => Some(ReportLocation::new(
+            PathBuf::from("target").join("grcov").join("lcov.info"),
+            ReportFormat::Lcov,
+        )),
+    })
+}
+
+fn coverage_trigger() -> Result<CoverageTrigger, CfgErr> {
+    match env::var(COVERAGE_TRIGGER_VAR) {
+        Ok(trigger) => Ok(trigger.parse()?),
+        Err(_) => Ok(CoverageTrigger::default()),
+    }
+}
+
+fn coverage_regression() -> Result<Option<RegressionThreshold>, CfgErr> {
+    match env::var(COVERAGE_REGRESSION_VAR).as_deref() {
+        Ok("off") => Ok(None),
+        Ok(threshold) => Ok(Some(threshold.parse()?)),
+        Err(_) => Ok(Some(RegressionThreshold::default())),
+    }
+}
+
+fn coverage_minimum() -> Result<MinimumCoverage, CfgErr> {
+    let global = match env::var(COVERAGE_MIN_VAR) {
+        Ok(min) => Some(percent(&min)?),
+        Err(_) => None,
+    };
+    let files = match env::var(COVERAGE_MIN_FILES_VAR) {
+        Ok(mins) => file_minimums(&mins)?,
+        Err(_) => Vec::new(),
+    };
+    Ok(MinimumCoverage { global, files })
 }
 
-fn coverage_cmd() -> Cmd<f32, CoverageParseErr> {
-    Cmd::with_parser(
-        "cargo",
+/// Parses comma separated `<glob>=<percent>` pairs, eg.: `src/core/**=90,src/*.rs=50`.
+fn file_minimums(mins: &str) -> Result<Vec<FileMinimum>, CfgErr> {
+    mins.split(',')
+        .filter(|min| !min.trim().is_empty())
+        .map(|min| {
+            let Some((pattern, required)) = min.rsplit_once('=') else {
+                return Err(CfgErr::CoverageMinimum(min.to_string()));
+            };
+            let pattern = Pattern::new(pattern.trim())
+                .map_err(|_| CfgErr::CoverageMinimum(min.to_string()))?;
+            Ok(FileMinimum::new(pattern, percent(required)?))
+        })
+        .collect()
+}
+
+fn percent(val: &str) -> Result<f32, CfgErr> {
+    match val.trim().parse() {
+        Ok(val) if (0.0..=100.0).contains(&val) => Ok(val),
+        _ => Err(CfgErr::CoverageMinimum(val.to_string())),
+    }
+}
+
+fn head_cmd() -> Cmd {
+    Cmd::new("git", &["rev-parse", "HEAD"])
+}
+
+fn diff_base() -> String {
+    env::var(DIFF_BASE_VAR).unwrap_or("HEAD".to_string())
+}
+
+fn diff_cmd(base: &str) -> Cmd {
+    Cmd::new(
+        "git",
         &[
-            "tarpaulin",
-            "--skip-clean",
-            "--target-dir",
-            "./tarpaulin-target",
+            "diff",
+            "--relative",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            base,
         ],
-        coverage_parser(),
     )
 }
 
-fn coverage_parser() -> Parser<f32, CoverageParseErr> {
-    CoverageParser::make()
+fn status_cmd() -> Cmd {
+    Cmd::new(
+        "git",
+        &["status", "--porcelain", "-z", "--untracked-files=all"],
+    )
+}
+
+fn toplevel_cmd() -> Cmd {
+    Cmd::new("git", &["rev-parse", "--show-toplevel"])
+}
+
+fn history_path() -> Result<PathBuf, CfgErr> {
+    let state_dir = match dirs::state_dir() {
+        Some(dir) => dir,
+        None => dirs::home_dir()
+            .ok_or(CfgErr::HistoryPath)?
+            .join(".local")
+            .join("state"),
+    };
+    Ok(state_dir.join("chester").join("history.jsonl"))
+}
+
+fn notify_policy() -> Result<NotifyPolicy, CfgErr> {
+    match env::var(NOTIFY_VAR) {
+        Ok(policy) => Ok(policy.parse()?),
+        Err(_) => Ok(NotifyPolicy::default()),
+    }
+}
+
+/// Calls `Notify` method of the freedesktop notifications service. Summary, body, actions, hints
+/// and timeout are appended by the notifier.
+fn notify_cmd() -> Cmd {
+    Cmd::new(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.Notifications",
+            "--object-path",
+            "/org/freedesktop/Notifications",
+            "--method",
+            "org.freedesktop.Notifications.Notify",
+            "chester",
+            "0",
+            "",
+        ],
+    )
+}
+
+fn hooks() -> Result<Vec<Hook>, CfgErr> {
+    match env::var(HOOKS_VAR) {
+        Ok(hooks) => parse_hooks(&hooks),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Parses semicolon separated `<event>=<action>` hooks, eg.:
+/// `tests-failed=./notify.sh;coverage-passed=http://localhost:9000/hook`.
+fn parse_hooks(hooks: &str) -> Result<Vec<Hook>, CfgErr> {
+    hooks
+        .split(';')
+        .filter(|hook| !hook.trim().is_empty())
+        .map(|hook| {
+            let hook: Hook = hook.parse()?;
+            if !BusEvent::is_name(&hook.event) {
+                return Err(HookErr::UnknownEvent(hook.event).into());
+            }
+            Ok(hook)
+        })
+        .collect()
+}
+
+fn hook_timeout() -> Result<Duration, CfgErr> {
+    match env::var(HOOK_TIMEOUT_VAR) {
+        Ok(secs) => match secs.trim().parse() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => Err(CfgErr::HookTimeout(secs)),
+        },
+        Err(_) => Ok(Duration::from_secs(10)),
+    }
+}
+
+fn custom_stages() -> Result<Vec<CustomStage>, CfgErr> {
+    match env::var(PIPELINE_VAR) {
+        Ok(path) => Ok(pipeline(
+            &fs::read_to_string(path).map_err(PipelineErr::from)?,
+        )?),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn pipeline(json: &str) -> Result<Vec<CustomStage>, PipelineErr> {
+    let pipeline: PipelineDef = serde_json::from_str(json)?;
+    pipeline.validate()?;
+    Ok(pipeline.stages)
+}
+
+fn max_parallel_stages() -> Result<usize, CfgErr> {
+    match env::var(MAX_PARALLEL_STAGES_VAR) {
+        Ok(max) => match max.trim().parse() {
+            Ok(max) if max > 0 => Ok(max),
+            _ => Err(CfgErr::MaxParallelStages(max)),
+        },
+        Err(_) => Ok(2),
+    }
+}
+
+fn continue_on_failure() -> Result<Vec<Stage>, CfgErr> {
+    match env::var(CONTINUE_ON_FAILURE_VAR) {
+        Ok(stages) => parse_stages(&stages),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn continue_on_warnings() -> bool {
+    env::var(CONTINUE_ON_FAILURE_VAR).is_ok_and(|stages| has_warnings(&stages))
+}
+
+fn one_shot() -> bool {
+    env::var(ONE_SHOT_VAR).is_ok_and(|val| !matches!(val.as_str(), "" | "0" | "false"))
+}
+
+/// Parses comma separated names of the stages, eg.: `check,tests`, skipping `warnings`. Failed
+/// index refresh doesn't stop the pipeline, so it's rejected.
+fn parse_stages(stages: &str) -> Result<Vec<Stage>, CfgErr> {
+    stages
+        .split(',')
+        .map(str::trim)
+        .filter(|stage| !stage.is_empty() && *stage != WARNINGS)
+        .map(|stage| match stage.parse()? {
+            Stage::Index => Err(CfgErr::ContinueAfterIndex),
+            stage => Ok(stage),
+        })
+        .collect()
+}
+
+/// Stages continuing on failure contain `warnings`, eg.: `warnings,tests`.
+fn has_warnings(stages: &str) -> bool {
+    stages
+        .split(',')
+        .map(str::trim)
+        .any(|stage| stage == WARNINGS)
+}
+
+fn coverage_cmd(backend: CoverageBackend) -> Cmd<CoverageSummary, CoverageParseErr> {
+    match backend {
+        CoverageBackend::Tarpaulin => Cmd::with_parser(
+            "cargo",
+            &[
+                "tarpaulin",
+                "--skip-clean",
+                "--target-dir",
+                "./tarpaulin-target",
+                "--out",
+                "Stdout",
+                "--out",
+                "Lcov",
+                "--output-dir",
+                "./tarpaulin-target",
+            ],
+            CoverageParser::make(),
+        ),
+        // NOTE: the summary doesn't have per-file data, so the tests are run once writing the lcov
+        // report and the summary is printed from the collected profiles without running them again.
+        CoverageBackend::LlvmCov => Cmd::with_parser(
+            "sh",
+            &[
+                "-c",
+                "mkdir -p target/llvm-cov \
+                 && cargo llvm-cov --lcov --output-path target/llvm-cov/lcov.info \
+                 && cargo llvm-cov report --json --summary-only",
+            ],
+            LlvmCovParser::make(),
+        ),
+        // NOTE: grcov only reads the profiles, so the tests are built with instrumentation and
+        // run first. They're built in a separate target directory to not invalidate the regular
+        // build, profiles of the previous run are removed to not mix them with the current ones.
+        CoverageBackend::Grcov => Cmd::with_parser(
+            "sh",
+            &[
+                "-c",
+                "rm -rf target/grcov/profraw \
+                 && LLVM_PROFILE_FILE=\"$PWD/target/grcov/profraw/%p-%m.profraw\" \
+                    cargo test --target-dir target/grcov \
+                 && grcov target/grcov/profraw --binary-path target/grcov/debug/ \
+                    --source-dir . --output-type lcov --ignore-not-existing \
+                    --output-path target/grcov/lcov.info \
+                 && cat target/grcov/lcov.info",
+            ],
+            LcovCoverageParser::make(),
+        )
+        .with_env("RUSTFLAGS", "-Cinstrument-coverage")
+        .with_env("CARGO_INCREMENTAL", "0"),
+    }
 }
 
 #[derive(Debug, Default, Clone, Builder)]
@@ -52,7 +403,43 @@ pub struct Config {
     pub tests_cmd: Cmd,
     pub list_tests_cmd: Cmd,
     pub check_cmd: Cmd,
-    pub coverage_cmd: Cmd<f32, CoverageParseErr>,
+    /// Prints unformatted files, one per line.
+    pub fmt_cmd: Cmd,
+    pub fmt_mode: FmtMode,
+    pub coverage_backend: CoverageBackend,
+    pub coverage_cmd: Cmd<CoverageSummary, CoverageParseErr>,
+    /// Report written by `coverage_cmd`. When set, coverage is read from it instead of the output.
+    pub coverage_report: Option<ReportLocation>,
+    pub coverage_trigger: CoverageTrigger,
+    pub coverage_regression: Option<RegressionThreshold>,
+    pub coverage_minimum: MinimumCoverage,
+    pub head_cmd: Cmd,
+    /// Commit (or branch) the changed lines are compared to. Used to build `diff_cmd`.
+    pub diff_base: String,
+    pub diff_cmd: Cmd,
+    /// Lists changed and untracked files. Used to fingerprint the source tree.
+    pub status_cmd: Cmd,
+    /// Prints the root of the repository, paths listed by `status_cmd` are relative to it.
+    pub toplevel_cmd: Cmd,
+    pub history_path: PathBuf,
+    pub notify_policy: NotifyPolicy,
+    /// Sends desktop notification. Used by the D-Bus notifier.
+    pub notify_cmd: Cmd,
+    pub hooks: Vec<Hook>,
+    /// Hook which doesn't finish in this time is stopped.
+    pub hook_timeout: Duration,
+    /// Stages defined by the user in the pipeline file, run by the custom stages shell.
+    pub custom_stages: Vec<CustomStage>,
+    /// How many custom stages can run at the same time.
+    pub max_parallel_stages: usize,
+    /// Built-in stages whose failure doesn't stop the later stages, eg. the tests are run even
+    /// when the check failed.
+    pub continue_on_failure: Vec<Stage>,
+    /// Check which failed only because of the warnings denied as errors doesn't stop the later
+    /// stages, unlike the check which failed on errors.
+    pub continue_on_warnings: bool,
+    /// Runs the pipeline once and exits, instead of watching the repository and serving the API.
+    pub one_shot: bool,
 }
 
 #[cfg(test)]
@@ -63,4 +450,85 @@ mod test {
     fn cfg_works() {
         assert!(cfg().is_ok());
     }
+
+    #[test]
+    fn file_minimums_are_parsed() -> anyhow::Result<()> {
+        // when
+        let mins = file_minimums("src/core/**=90, src/*.rs=50.5")?;
+
+        // then
+        assert_eq!(
+            mins,
+            vec![
+                FileMinimum::new(Pattern::new("src/core/**")?, 90.0),
+                FileMinimum::new(Pattern::new("src/*.rs")?, 50.5),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_are_parsed() -> anyhow::Result<()> {
+        // when
+        let hooks = parse_hooks("tests-failed=./notify.sh; coverage-passed=http://localhost/")?;
+
+        // then
+        assert_eq!(
+            hooks,
+            vec![
+                "tests-failed=./notify.sh".parse()?,
+                "coverage-passed=http://localhost/".parse()?,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_of_unknown_events_are_rejected() {
+        assert!(parse_hooks("tests-exploded=./notify.sh").is_err());
+        assert!(parse_hooks("tests-failed").is_err());
+    }
+
+    #[test]
+    fn stages_continuing_on_failure_are_parsed() -> anyhow::Result<()> {
+        // when
+        let stages = parse_stages("check, tests")?;
+
+        // then
+        assert_eq!(stages, vec![Stage::Check, Stage::Tests]);
+        assert!(parse_stages("check,lint").is_err());
+        assert!(parse_stages("tests,index").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn warnings_continuing_on_failure_are_parsed() -> anyhow::Result<()> {
+        // when
+        let stages = parse_stages("warnings, tests")?;
+
+        // then
+        assert_eq!(stages, vec![Stage::Tests]);
+        assert!(has_warnings("warnings, tests"));
+        assert!(!has_warnings("check,tests"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_pipeline_is_rejected() {
+        assert!(pipeline(r#"{"stages": [{"name": "fmt", "cmd": ["cargo", "fmt"]}]}"#).is_ok());
+        assert!(pipeline(r#"{"stages": [{"name": "fmt"}]}"#).is_err());
+        assert!(pipeline(r#"{"stages": [{"name": "tests", "cmd": ["true"]}]}"#).is_err());
+    }
+
+    #[test]
+    fn invalid_file_minimums_are_rejected() {
+        assert!(file_minimums("src/*.rs").is_err());
+        assert!(file_minimums("src/*.rs=abc").is_err());
+        assert!(file_minimums("src/*.rs=101").is_err());
+        assert!(file_minimums("[=50").is_err());
+    }
 }
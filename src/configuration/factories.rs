@@ -3,42 +3,95 @@ use crate::data_providers::bus::LocalBus;
 use crate::data_providers::change_watcher::FsChangeWatcher;
 use crate::data_providers::check_runner::DefaultCheckRunner;
 use crate::data_providers::coverage_runner::DefaultCoverageRunner;
+use crate::data_providers::fmt_runner::DefaultFmtRunner;
+use crate::data_providers::history::JsonLinesHistory;
+use crate::data_providers::hook_runner::DefaultHookRunner;
+use crate::data_providers::notifier::DbusNotifier;
+use crate::data_providers::quality_analyzer::SynQualityAnalyzer;
+use crate::data_providers::stage_runner::DefaultStageRunner;
 use crate::data_providers::state::InMemoryState;
 use crate::data_providers::test_runner::DefaultTestRunner;
 use crate::data_providers::tests_index::DefaultTestsIndex;
+use crate::data_providers::vcs::GitVcs;
 use crate::entities::repo_root::RepoRoot;
 use crate::result::{BusErr, SetupErr};
 use crate::use_cases::bus::{EventBus, EventPublisher};
 use crate::use_cases::change_watcher::ChangeWatcher;
 use crate::use_cases::check_runner::CheckRunner;
 use crate::use_cases::coverage_runner::CoverageRunner;
+use crate::use_cases::fmt_runner::FmtRunner;
+use crate::use_cases::history::{History, HistoryQuery};
+use crate::use_cases::hook_runner::HookRunner;
+use crate::use_cases::notifier::Notifier;
+use crate::use_cases::quality_analyzer::QualityAnalyzer;
+use crate::use_cases::stage_runner::StageRunner;
 use crate::use_cases::state::{State, StateReader};
 use crate::use_cases::test_runner::TestRunner;
 use crate::use_cases::tests_index::TestsIndex;
+use crate::use_cases::vcs::VersionControl;
 
+use std::env;
 use std::sync::Arc;
+use tracing::debug;
 
 pub struct Runtime {
+    pub cfg: Config,
     pub bus: EventBus,
-    pub change_watcher: ChangeWatcher,
+    /// There is nothing to watch in one-shot mode.
+    pub change_watcher: Option<ChangeWatcher>,
     pub tests_index: TestsIndex,
     pub test_runner: TestRunner,
     pub check_runner: CheckRunner,
+    pub fmt_runner: FmtRunner,
     pub coverage_runner: CoverageRunner,
+    pub stage_runner: StageRunner,
+    pub quality_analyzer: QualityAnalyzer,
+    pub history: History,
+    pub vcs: VersionControl,
+    pub notifier: Notifier,
+    pub hook_runner: HookRunner,
     pub state: State,
 }
 
 impl Runtime {
+    /// One-shot run checks the repository it's started in, otherwise the repository of the last
+    /// run is watched.
     pub fn new(cfg: Config) -> Result<Self, SetupErr> {
+        let repo_root = if cfg.one_shot {
+            Some(RepoRoot::new(env::current_dir()?))
+        } else {
+            None
+        };
+        Self::with_repo_root(cfg, repo_root)
+    }
+
+    /// Restores the state of the given repository, or of the repository of the last run when
+    /// none is given.
+    pub fn with_repo_root(cfg: Config, repo_root: Option<RepoRoot>) -> Result<Self, SetupErr> {
         let bus = event_bus()?;
+        let history = history(cfg.clone());
         let state = state(bus.publisher());
+        restore_state(&state, &history, repo_root)?;
+        let change_watcher = if cfg.one_shot {
+            None
+        } else {
+            Some(change_watcher(state.reader().repo_root()?, cfg.clone())?)
+        };
         Ok(Self {
+            cfg: cfg.clone(),
             bus,
-            change_watcher: change_watcher(state.reader().repo_root()?, cfg.clone())?,
+            change_watcher,
             tests_index: tests_index(cfg.clone(), state.reader()),
             test_runner: test_runner(cfg.clone()),
             check_runner: check_runner(cfg.clone()),
-            coverage_runner: coverage_runner(cfg),
+            fmt_runner: fmt_runner(cfg.clone()),
+            coverage_runner: coverage_runner(cfg.clone()),
+            stage_runner: stage_runner(),
+            quality_analyzer: quality_analyzer(cfg.clone()),
+            history,
+            notifier: notifier(cfg.clone()),
+            hook_runner: hook_runner(cfg.clone()),
+            vcs: vcs(cfg),
             state,
         })
     }
@@ -64,20 +117,166 @@ fn check_runner(cfg: Config) -> CheckRunner {
     DefaultCheckRunner::make(cfg)
 }
 
+fn fmt_runner(cfg: Config) -> FmtRunner {
+    DefaultFmtRunner::make(cfg)
+}
+
 fn coverage_runner(cfg: Config) -> CoverageRunner {
     DefaultCoverageRunner::make(cfg)
 }
 
+fn stage_runner() -> StageRunner {
+    DefaultStageRunner::make()
+}
+
+fn quality_analyzer(cfg: Config) -> QualityAnalyzer {
+    SynQualityAnalyzer::make(cfg)
+}
+
+fn history(cfg: Config) -> History {
+    JsonLinesHistory::make(cfg)
+}
+
+fn notifier(cfg: Config) -> Notifier {
+    DbusNotifier::make(cfg)
+}
+
+fn hook_runner(cfg: Config) -> HookRunner {
+    DefaultHookRunner::make(cfg)
+}
+
+fn vcs(cfg: Config) -> VersionControl {
+    GitVcs::make(cfg)
+}
+
 pub fn state(publ: EventPublisher) -> State {
     InMemoryState::make(publ)
 }
 
+/// Restores the last known state from the history.
+///
+/// The repo root is taken from the most recent run, unless it's given. Each stage state is taken
+/// from the most recent run of that repo in which the stage was executed.
+fn restore_state(
+    state: &State,
+    history: &History,
+    repo_root: Option<RepoRoot>,
+) -> Result<(), SetupErr> {
+    let runs = history.runs(&HistoryQuery::all())?;
+    let Some(repo_root) = repo_root.or_else(|| runs.first().map(|run| run.repo_root.clone()))
+    else {
+        debug!("no runs in history, nothing to restore");
+        return Ok(());
+    };
+
+    debug!("restoring state of {repo_root}");
+    let repo_runs: Vec<_> = runs.iter().filter(|r| r.repo_root == repo_root).collect();
+    let sw = state.writer();
+    if let Some(check) = repo_runs.iter().find_map(|r| r.check.clone()) {
+        sw.check(check)?;
+    }
+    if let Some(tests) = repo_runs.iter().find_map(|r| r.tests.clone()) {
+        sw.tests(tests)?;
+    }
+    if let Some(coverage) = repo_runs.iter().find_map(|r| r.coverage.clone()) {
+        sw.coverage(coverage)?;
+    }
+    sw.repo_root(repo_root)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    use crate::entities::check::CheckState;
+    use crate::entities::coverage::CoverageState;
+    use crate::entities::pipeline_run::{PipelineRun, Trigger};
+    use crate::entities::run_id::RunId;
+    use crate::entities::tests::TestsState;
+    use crate::testingtools::history::working;
+
+    use anyhow::Result;
+
     #[test]
     fn creating_runtime_works() {
         assert!(Runtime::new(Config::default()).is_ok());
     }
+
+    #[test]
+    fn last_known_state_is_restored_from_history() -> Result<()> {
+        // given
+        let bus = event_bus()?;
+        let state = state(bus.publisher());
+        let mut newest_run = run("/repo");
+        newest_run.check = Some(CheckState::Success);
+        newest_run.tests = Some(TestsState::Failure);
+        let mut older_run = run("/repo");
+        older_run.coverage = Some(CoverageState::Success(50.0));
+        let mut other_repo_run = run("/other-repo");
+        other_repo_run.coverage = Some(CoverageState::Success(10.0));
+        let history = working(vec![newest_run, other_repo_run, older_run]);
+
+        // when
+        restore_state(&state, &history, None)?;
+
+        // then
+        let sr = state.reader();
+        assert_eq!(sr.repo_root()?, RepoRoot::new("/repo"));
+        assert_eq!(sr.check()?, CheckState::Success);
+        assert_eq!(sr.tests()?, TestsState::Failure);
+        assert_eq!(sr.coverage()?, CoverageState::Success(50.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn state_of_given_repo_is_restored_from_history() -> Result<()> {
+        // given
+        let bus = event_bus()?;
+        let state = state(bus.publisher());
+        let mut newest_run = run("/repo");
+        newest_run.coverage = Some(CoverageState::Success(50.0));
+        let mut other_repo_run = run("/other-repo");
+        other_repo_run.coverage = Some(CoverageState::Success(10.0));
+        let history = working(vec![newest_run, other_repo_run]);
+
+        // when
+        restore_state(&state, &history, Some(RepoRoot::new("/other-repo")))?;
+
+        // then
+        let sr = state.reader();
+        assert_eq!(sr.repo_root()?, RepoRoot::new("/other-repo"));
+        assert_eq!(sr.coverage()?, CoverageState::Success(10.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_is_restored_from_empty_history() -> Result<()> {
+        // given
+        let bus = event_bus()?;
+        let state = state(bus.publisher());
+        let history = working(Vec::new());
+
+        // when
+        restore_state(&state, &history, None)?;
+
+        // then
+        let sr = state.reader();
+        assert_eq!(sr.repo_root()?, RepoRoot::default());
+        assert_eq!(sr.check()?, CheckState::Pending);
+
+        Ok(())
+    }
+
+    fn run(repo_root: &str) -> PipelineRun {
+        PipelineRun::new(
+            RunId::new(),
+            Trigger::Change,
+            RepoRoot::new(repo_root),
+            None,
+        )
+    }
 }
@@ -1,5 +1,6 @@
 use crate::configuration::config::Config;
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::watcher::WatchedEvents;
 use crate::result::WatcherErr;
 use crate::use_cases::change_watcher::{ChangeWatcher, Watcher};
 
@@ -21,7 +22,7 @@ type Dbcr = Debouncer<RecommendedWatcher>;
 /// [`FsChangeWatcher::make`] fn.
 ///
 /// Initially, it watches for changes in directory pointed by `repo_root` passed as an argument to
-/// [`FsChangeWatcher::make`] fn. See [`FsChangeWatcher::wait_for_change`] for details.
+/// [`FsChangeWatcher::make`] fn. See [`FsChangeWatcher::wait_for_events`] for details.
 pub struct FsChangeWatcher {
     rx: RefCell<Rx>,
     watcher: RefCell<Dbcr>,
@@ -50,24 +51,22 @@ impl FsChangeWatcher {
         Ok(())
     }
 
+    /// Returns how many of the events are in the ignored paths.
     #[instrument(level = "trace", skip(self, events))]
-    fn is_ignored(&self, events: &[DebouncedEvent]) -> bool {
-        if self.cfg.ignored_paths.is_empty() {
-            return false;
-        }
-
+    fn ignored_count(&self, events: &[DebouncedEvent]) -> usize {
         let ignored_paths = &self.cfg.ignored_paths;
-        for ev in events {
-            let event_path = &ev.path;
-            if ignored_paths.iter().any(|p| p.matched_by(event_path)) {
-                trace!("ignored path: {event_path:?}");
-                continue;
-            }
-            trace!("change detected: {event_path:?}");
-            return false;
-        }
-
-        true
+        events
+            .iter()
+            .filter(|ev| {
+                let event_path = &ev.path;
+                if ignored_paths.iter().any(|p| p.matched_by(event_path)) {
+                    trace!("ignored path: {event_path:?}");
+                    return true;
+                }
+                trace!("change detected: {event_path:?}");
+                false
+            })
+            .count()
     }
 }
 
@@ -86,11 +85,14 @@ impl Watcher for FsChangeWatcher {
     /// If the passed root is different than the one passed when creating
     /// `FsChangeWatcher`, then the filesystem watcher is reattached.
     ///
-    /// Not every file change breaks the waiting loop. Some files can be ignored by setting
-    /// [`Config::ignored_paths`] in a configuration passed as a second argument to
-    /// [`FsChangeWatcher::make`].
+    /// It returns as soon as the debounced events are received, even if all of them are in the
+    /// ignored paths, so they are counted without waiting for the next change. Some files can be
+    /// ignored by setting [`Config::ignored_paths`] in a configuration passed as a second
+    /// argument to [`FsChangeWatcher::make`], see [`WatchedEvents::has_change`].
+    ///
+    /// Returns the number of the received events and of the ignored ones.
     #[instrument(level = "trace", skip(self))]
-    fn wait_for_change(&self, passed_root: RepoRoot) -> Result<(), WatcherErr> {
+    fn wait_for_events(&self, passed_root: RepoRoot) -> Result<WatchedEvents, WatcherErr> {
         if *self.repo_root.borrow() != passed_root {
             self.reattach_watcher(passed_root)?;
         }
@@ -98,7 +100,12 @@ impl Watcher for FsChangeWatcher {
         let rx = self.rx.borrow();
         loop {
             match rx.recv() {
-                Ok(Ok(events)) if !self.is_ignored(&events) => return Ok(()),
+                Ok(Ok(events)) => {
+                    return Ok(WatchedEvents {
+                        received: events.len() as u64,
+                        ignored: self.ignored_count(&events) as u64,
+                    });
+                }
                 _ => trace!("no valid change detected"),
             }
         }
@@ -154,6 +161,28 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn events_in_ignored_paths_are_counted() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = ConfigBuilder::default()
+            .ignored_paths(vec![IgnoredPath::new("target")?])
+            .build()?;
+        let watcher = FsChangeWatcher::make(shim.repo_root(), cfg)?;
+        mk_file(shim.repo_file("target"))?;
+
+        // when
+        let watched = watcher.wait_for_events(shim.repo_root())?;
+
+        // then
+        assert!(watched.ignored >= 1);
+        assert_eq!(watched.received, watched.ignored);
+        assert!(!watched.has_change());
+
+        Ok(())
+    }
+
     #[test]
     fn change_in_ignored_dir_is_not_detected() -> Result<()> {
         // given
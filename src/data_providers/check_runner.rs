@@ -3,6 +3,7 @@ use crate::entities::repo_root::RepoRoot;
 use crate::result::CheckErr;
 use crate::use_cases::check_runner::{CRunner, CheckRunStatus, CheckRunner};
 
+use serde::Deserialize;
 use tracing::{debug, instrument};
 
 /// It runs the command for a check stage. Command is passed in via `Config::check_cmd`.
@@ -26,16 +27,30 @@ impl CRunner for DefaultCheckRunner {
     /// - there is an error while executing `check_cmd` command
     /// - the command succeeds, but there are issues with the code
     ///   (`check_cmd` exits with non-zero status code).
+    ///
+    /// When the command prints JSON messages (`--message-format=json`) and all the errors are
+    /// lints denied as errors, it returns `CheckRunStatus::Warnings` instead of the failure.
     #[instrument(skip(self))]
     fn run(&self, repo_root: RepoRoot) -> Result<CheckRunStatus, CheckErr> {
         debug!("running check in {repo_root}");
-        let Ok(status) = self.cfg.check_cmd.status(&repo_root) else {
+        let mut errors = CompilerErrors::default();
+        let Ok((status, _)) = self
+            .cfg
+            .check_cmd
+            .status_and_find_in_stdout(&repo_root, |line| {
+                errors.read(line);
+                None::<()>
+            })
+        else {
             debug!("command failed");
             return Ok(CheckRunStatus::Failure);
         };
 
         if !status.success() {
             debug!("check failed with: {status}");
+            if errors.warnings_only() {
+                return Ok(CheckRunStatus::Warnings);
+            }
             return Ok(CheckRunStatus::Failure);
         }
 
@@ -44,6 +59,60 @@ impl CRunner for DefaultCheckRunner {
     }
 }
 
+/// Errors reported by the compiler in the JSON messages of cargo.
+#[derive(Debug, Default)]
+struct CompilerErrors {
+    lints: usize,
+    other: usize,
+}
+
+impl CompilerErrors {
+    fn read(&mut self, line: &str) {
+        let Ok(CargoMessage {
+            message: Some(diagnostic),
+        }) = serde_json::from_str(line)
+        else {
+            return;
+        };
+        // NOTE: Summary of the errors is reported as an error too.
+        if diagnostic.level != "error" || diagnostic.message.starts_with("aborting due to") {
+            return;
+        }
+        match diagnostic.code {
+            Some(code) if !is_error_code(&code.code) => self.lints += 1,
+            _ => self.other += 1,
+        }
+    }
+
+    /// There are only the lints denied as errors, eg. `unused_variables` or `clippy::unwrap_used`.
+    fn warnings_only(&self) -> bool {
+        self.lints > 0 && self.other == 0
+    }
+}
+
+/// Error of the compiler has a code like `E0308`, while lint has its name as a code.
+fn is_error_code(code: &str) -> bool {
+    code.strip_prefix('E')
+        .is_some_and(|num| !num.is_empty() && num.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    message: Option<Diagnostic>,
+}
+
+#[derive(Deserialize)]
+struct Diagnostic {
+    level: String,
+    message: String,
+    code: Option<DiagnosticCode>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticCode {
+    code: String,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -102,6 +171,53 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn when_check_fails_on_denied_warnings_only_then_warnings_status_is_returned() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        run_cmd!(
+            cd $tmpdir_path;
+            cargo new test_project;
+            echo "#![deny(unused_variables)]\nfn main() { let x = 1; }" > $tmpdir_path/test_project/src/main.rs
+        )?;
+        let cfg = ConfigBuilder::default()
+            .check_cmd(Cmd::new("cargo", &["check", "--message-format=json"]))
+            .build()?;
+        let runner = DefaultCheckRunner::make(cfg);
+        let project_path = tmpdir_path.join("test_project");
+        let root = RepoRoot::new(project_path);
+
+        // when
+        let res = runner.run(root)?;
+
+        // then
+        assert_eq!(res, CheckRunStatus::Warnings);
+
+        Ok(())
+    }
+
+    #[test]
+    fn errors_of_the_compiler_are_not_warnings() {
+        // given
+        init_tracing();
+        let mut errors = CompilerErrors::default();
+
+        // when
+        errors.read(r#"{"reason":"compiler-message","message":{"level":"error","message":"unused variable: `x`","code":{"code":"unused_variables"}}}"#);
+        errors.read(r#"{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","code":null}}"#);
+
+        // then
+        assert!(errors.warnings_only());
+
+        // when
+        errors.read(r#"{"reason":"compiler-message","message":{"level":"error","message":"mismatched types","code":{"code":"E0308"}}}"#);
+
+        // then
+        assert!(!errors.warnings_only());
+    }
+
     #[test]
     fn when_check_succeed_then_success_status_is_returned() -> Result<()> {
         // given
@@ -0,0 +1,207 @@
+use crate::entities::coverage_report::{CoverageReport, Metric};
+use crate::entities::file_coverage::FileCoverage;
+use crate::result::ReportParseErr;
+use crate::use_cases::output_parser::OutputParser;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+use tracing::{error, instrument, trace};
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(coverage|class|line)\b([^>]*)>").unwrap());
+static ATTR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap());
+
+/// Parses the coverage report in the Cobertura XML format.
+///
+/// Only `coverage`, `class` and `line` elements are taken into account, the rest of the report is
+/// ignored. See [`CoberturaParser::parse`] for details.
+pub struct CoberturaParser;
+
+impl OutputParser for CoberturaParser {
+    type Output = CoverageReport;
+    type Error = ReportParseErr;
+
+    /// Parses the XML report and returns coverage of every file mentioned in the report.
+    ///
+    /// Lines coverage is calculated from the `line` elements. Branches coverage is read from
+    /// `branches-covered` and `branches-valid` attributes of the `coverage` element.
+    /// Cobertura does not report functions coverage.
+    ///
+    /// It can fail when:
+    /// - there is no `coverage` element
+    /// - `line` element is not inside of a `class`
+    /// - any of the numeric attributes is not a number
+    #[instrument(skip(self, output))]
+    fn parse(&self, output: String) -> Result<Self::Output, Self::Error> {
+        let mut branches = None;
+        let mut coverage_found = false;
+        let mut files: Vec<FileCoverage> = Vec::new();
+        let mut curr_file: Option<usize> = None;
+        for tag in TAG_RE.captures_iter(&output) {
+            let attrs = attributes(&tag[2]);
+            match &tag[1] {
+                "coverage" => {
+                    coverage_found = true;
+                    branches = branches_metric(&attrs)?;
+                }
+                "class" => {
+                    let Some(path) = attrs.get("filename") else {
+                        trace!("class without filename");
+                        curr_file = None;
+                        continue;
+                    };
+                    curr_file = Some(file_idx(&mut files, path));
+                }
+                _ => {
+                    let Some(idx) = curr_file else {
+                        error!("line outside of a class");
+                        return Err(ReportParseErr::NoSourceFile);
+                    };
+                    let line_no = number::<u32>(&attrs, "number")?;
+                    let hits = number::<u64>(&attrs, "hits")?;
+                    // NOTE: the same line can be reported in a class and in a method of the class
+                    let line_hits = files[idx].lines.entry(line_no).or_default();
+                    *line_hits = (*line_hits).max(hits);
+                }
+            }
+        }
+
+        if !coverage_found {
+            error!("no coverage element in the report");
+            return Err(ReportParseErr::InvalidReport("no coverage element".into()));
+        }
+
+        let lines = Metric::new(
+            files.iter().map(FileCoverage::covered).sum(),
+            files.iter().map(FileCoverage::total).sum(),
+        );
+        Ok(CoverageReport {
+            lines,
+            branches,
+            functions: None,
+            files,
+        })
+    }
+}
+
+fn attributes(tag: &str) -> BTreeMap<&str, &str> {
+    ATTR_RE
+        .captures_iter(tag)
+        .filter_map(|c| Some((c.get(1)?.as_str(), c.get(2)?.as_str())))
+        .collect()
+}
+
+fn branches_metric(attrs: &BTreeMap<&str, &str>) -> Result<Option<Metric>, ReportParseErr> {
+    if !attrs.contains_key("branches-valid") {
+        return Ok(None);
+    }
+    let total = number::<u64>(attrs, "branches-valid")?;
+    let covered = number::<u64>(attrs, "branches-covered")?;
+    Ok(Some(Metric::new(covered, total)))
+}
+
+fn file_idx(files: &mut Vec<FileCoverage>, path: &str) -> usize {
+    if let Some(idx) = files.iter().position(|file| file.path == path) {
+        return idx;
+    }
+    files.push(FileCoverage::new(path, BTreeMap::new()));
+    files.len() - 1
+}
+
+fn number<T: std::str::FromStr>(
+    attrs: &BTreeMap<&str, &str>,
+    name: &str,
+) -> Result<T, ReportParseErr> {
+    let value = attrs.get(name).copied().unwrap_or_default();
+    value.parse::<T>().map_err(|_| {
+        error!("invalid {name}: '{value}'");
+        ReportParseErr::InvalidEntry(format!("{name}=\"{value}\""))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn with_correct_report_it_returns_coverage_of_files() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = CoberturaParser;
+        let report = r#"<?xml version="1.0"?>
+            <coverage lines-covered="3" lines-valid="4" line-rate="0.75" branches-covered="1"
+                      branches-valid="2" branch-rate="0.5" version="1.9">
+              <packages><package name="src"><classes>
+                <class name="lib" filename="src/lib.rs" line-rate="0.5">
+                  <methods><method name="main"><lines><line number="1" hits="2"/></lines></method></methods>
+                  <lines><line number="1" hits="2"/><line number="2" hits="0"/></lines>
+                </class>
+                <class name="main" filename="src/main.rs" line-rate="1">
+                  <lines><line number="3" hits="1" branch="false"/><line number="4" hits="7"/></lines>
+                </class>
+              </classes></package></packages>
+            </coverage>"#;
+
+        // when
+        let res = parser.parse(report.to_string())?;
+
+        // then
+        assert_eq!(
+            res.files,
+            vec![
+                FileCoverage::new("src/lib.rs", BTreeMap::from([(1, 2), (2, 0)])),
+                FileCoverage::new("src/main.rs", BTreeMap::from([(3, 1), (4, 7)])),
+            ]
+        );
+        assert_eq!(res.lines, Metric::new(3, 4));
+        assert_eq!(res.branches, Some(Metric::new(1, 2)));
+        assert_eq!(res.functions, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_when_there_is_no_coverage_element() {
+        // given
+        init_tracing();
+        let parser = CoberturaParser;
+
+        // when
+        let res = parser.parse("<report></report>".to_string());
+
+        // then
+        assert!(matches!(res, Err(ReportParseErr::InvalidReport(_))));
+    }
+
+    #[test]
+    fn it_fails_when_line_is_outside_of_class() {
+        // given
+        init_tracing();
+        let parser = CoberturaParser;
+
+        // when
+        let res = parser.parse(r#"<coverage><line number="1" hits="1"/></coverage>"#.to_string());
+
+        // then
+        assert!(matches!(res, Err(ReportParseErr::NoSourceFile)));
+    }
+
+    #[test]
+    fn it_fails_when_hits_are_not_a_number() {
+        // given
+        init_tracing();
+        let parser = CoberturaParser;
+        let report =
+            r#"<coverage><class filename="a.rs"><line number="1" hits="x"/></class></coverage>"#;
+
+        // when
+        let res = parser.parse(report.to_string());
+
+        // then
+        assert!(matches!(res, Err(ReportParseErr::InvalidEntry(_))));
+    }
+}
@@ -2,6 +2,7 @@ use crate::result::CmdErr;
 use crate::use_cases::output_parser::Parser;
 
 use debug_ignore::DebugIgnore;
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::process::{Command, ExitStatus, Stdio};
 
@@ -9,6 +10,7 @@ use std::process::{Command, ExitStatus, Stdio};
 pub struct Cmd<T = (), E = ()> {
     tool: String,
     args: Vec<String>,
+    envs: Vec<(String, String)>,
     output_parser: DebugIgnore<Option<Parser<T, E>>>,
 }
 
@@ -19,6 +21,7 @@ impl<T, E> Cmd<T, E> {
         Self {
             tool,
             args,
+            envs: Vec::new(),
             output_parser: None.into(),
         }
     }
@@ -29,27 +32,109 @@ impl<T, E> Cmd<T, E> {
         Self {
             tool,
             args,
+            envs: Vec::new(),
             output_parser: Some(parser).into(),
         }
     }
 
     pub fn stdout<P: AsRef<Path>>(&self, working_dir: P) -> Result<String, CmdErr> {
-        let output = Command::new(&self.tool)
-            .args(&self.args)
-            .current_dir(working_dir)
-            .output()?;
+        let output = self.command(working_dir).output()?;
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Returns standard output of the command or `None` if the command exited with non-zero
+    /// status code.
+    pub fn successful_stdout<P: AsRef<Path>>(
+        &self,
+        working_dir: P,
+    ) -> Result<Option<String>, CmdErr> {
+        let output = self.command(working_dir).output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+    }
+
     pub fn status<P: AsRef<Path>>(&self, working_dir: P) -> Result<ExitStatus, CmdErr> {
-        Ok(Command::new(&self.tool)
-            .args(&self.args)
-            .current_dir(working_dir)
+        Ok(self
+            .command(working_dir)
             .stderr(Stdio::null()) // TODO: Move it to separate log file?
             .status()?)
     }
 
+    /// Returns exit status of the command together with its standard output.
+    pub fn status_and_stdout<P: AsRef<Path>>(
+        &self,
+        working_dir: P,
+    ) -> Result<(ExitStatus, String), CmdErr> {
+        let output = self.command(working_dir).output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        Ok((output.status, stdout))
+    }
+
+    /// Returns exit status of the command together with the first line of its standard output
+    /// for which `find` returns a value.
+    ///
+    /// Output is read line by line while the command runs, so it's not kept in memory.
+    pub fn status_and_find_in_stdout<P, R, F>(
+        &self,
+        working_dir: P,
+        mut find: F,
+    ) -> Result<(ExitStatus, Option<R>), CmdErr>
+    where
+        P: AsRef<Path>,
+        F: FnMut(&str) -> Option<R>,
+    {
+        let mut child = self
+            .command(working_dir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut found = None;
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).split(b'\n') {
+                let line = line?;
+                if found.is_none() {
+                    found = find(&String::from_utf8_lossy(&line));
+                }
+            }
+        }
+        Ok((child.wait()?, found))
+    }
+
+    /// Returns exit status of the command together with its standard output followed by its
+    /// standard error.
+    pub fn status_and_output<P: AsRef<Path>>(
+        &self,
+        working_dir: P,
+    ) -> Result<(ExitStatus, String), CmdErr> {
+        let output = self.command(working_dir).output()?;
+        let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+        combined.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok((output.status, combined))
+    }
+
+    /// Appends arguments to the command.
+    pub fn with_args(mut self, args: &[&str]) -> Self {
+        self.args.extend(args.iter().map(ToString::to_string));
+        self
+    }
+
+    /// Sets environment variable of the command.
+    pub fn with_env<K: Into<String>, V: Into<String>>(mut self, key: K, val: V) -> Self {
+        self.envs.push((key.into(), val.into()));
+        self
+    }
+
     pub fn parser(&self) -> Option<&Parser<T, E>> {
         self.output_parser.0.as_ref()
     }
+
+    fn command<P: AsRef<Path>>(&self, working_dir: P) -> Command {
+        let mut cmd = Command::new(&self.tool);
+        cmd.args(&self.args)
+            .envs(self.envs.iter().map(|(key, val)| (key, val)))
+            .current_dir(working_dir);
+        cmd
+    }
 }
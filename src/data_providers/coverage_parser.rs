@@ -1,5 +1,6 @@
 use std::sync::{Arc, Mutex};
 
+use crate::entities::coverage_summary::CoverageSummary;
 use crate::result::CoverageParseErr;
 use crate::use_cases::output_parser::{OutputParser, Parser};
 
@@ -16,16 +17,17 @@ static COVERAGE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d+.\d{2})% covera
 pub struct CoverageParser;
 
 impl CoverageParser {
-    pub fn make() -> Parser<f32, CoverageParseErr> {
+    pub fn make() -> Parser<CoverageSummary, CoverageParseErr> {
         Arc::new(Mutex::new(Self))
     }
 }
 
 impl OutputParser for CoverageParser {
-    type Output = f32;
+    type Output = CoverageSummary;
     type Error = CoverageParseErr;
 
-    /// Parses the string output and returns f32 value of code coverage.
+    /// Parses the string output and returns lines coverage. Tarpaulin does not report other
+    /// metrics in the output.
     ///
     /// It can fail in few ways:
     /// - there was no last line in the output (line containing code coverage info)
@@ -57,7 +59,7 @@ impl OutputParser for CoverageParser {
             )));
         }
 
-        Ok(coverage)
+        Ok(CoverageSummary::new(coverage))
     }
 }
 
@@ -108,7 +110,7 @@ mod test {
         let res = cov_parser.parse(coverage_output)?;
 
         // then
-        assert!((res - 25.05).abs() < f32::EPSILON);
+        assert!((res.lines - 25.05).abs() < f32::EPSILON);
 
         Ok(())
     }
@@ -124,7 +126,7 @@ mod test {
         let res = cov_parser.parse(coverage_output)?;
 
         // then
-        assert!((res - 5.01).abs() < f32::EPSILON);
+        assert!((res.lines - 5.01).abs() < f32::EPSILON);
 
         Ok(())
     }
@@ -140,7 +142,7 @@ mod test {
         let res = cov_parser.parse(coverage_output)?;
 
         // then
-        assert!((res - 5.0).abs() < f32::EPSILON);
+        assert!((res.lines - 5.0).abs() < f32::EPSILON);
 
         Ok(())
     }
@@ -156,7 +158,7 @@ mod test {
         let res = cov_parser.parse(coverage_output)?;
 
         // then
-        assert!((res - 100.0).abs() < f32::EPSILON);
+        assert!((res.lines - 100.0).abs() < f32::EPSILON);
 
         Ok(())
     }
@@ -1,12 +1,24 @@
 use crate::configuration::config::Config;
+use crate::data_providers::cobertura_parser::CoberturaParser;
+use crate::data_providers::lcov_parser::LcovParser;
+use crate::data_providers::tarpaulin_json_parser::TarpaulinJsonParser;
+use crate::entities::coverage_report::{CoverageReport, ReportFormat, ReportLocation};
+use crate::entities::file_coverage::FileCoverage;
 use crate::entities::repo_root::RepoRoot;
 use crate::result::CoverageErr;
 use crate::use_cases::coverage_runner::{CovRunner, CoverageRunStatus, CoverageRunner};
+use crate::use_cases::output_parser::OutputParser;
 
-use tracing::{debug, error, instrument};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use tracing::{debug, error, instrument, trace};
 
 /// It runs the command for a coverage stage. Command is passed in via `Config::coverage_cmd`.
 ///
+/// When `Config::coverage_report` is set, the coverage is read from the report written by the
+/// command. Otherwise, it's parsed from the output of the command.
+///
 /// The execution can fail. See the [`DefaultCoverageRunner::run`] for details.
 #[derive(Debug)]
 pub struct DefaultCoverageRunner {
@@ -17,25 +29,65 @@ impl DefaultCoverageRunner {
     pub fn make(cfg: Config) -> CoverageRunner {
         Box::new(Self { cfg })
     }
+
+    fn report(&self, repo_root: &RepoRoot) -> Option<CoverageReport> {
+        let location = self.cfg.coverage_report.as_ref()?;
+        match read_report(location, repo_root) {
+            Ok(report) => Some(report),
+            Err(e) => {
+                error!("failed to read coverage report: {e:?}");
+                None
+            }
+        }
+    }
+
+    fn remove_stale_report(&self, repo_root: &RepoRoot) {
+        let Some(location) = &self.cfg.coverage_report else {
+            return;
+        };
+        match fs::remove_file(repo_root.as_ref().join(&location.path)) {
+            Ok(()) => trace!("removed previous coverage report"),
+            Err(e) if e.kind() == ErrorKind::NotFound => trace!("no previous coverage report"),
+            Err(e) => error!("failed to remove previous coverage report: {e:?}"),
+        }
+    }
 }
 
 impl CovRunner for DefaultCoverageRunner {
-    /// It executes `coverage_cmd` on a path specified by `repo_root` and parses the output
-    /// to read the code coverage by using output parser from `coverage_cmd`.
+    /// It executes `coverage_cmd` on a path specified by `repo_root` and reads the code coverage.
+    ///
+    /// If the report is configured, the coverage is read from it. The report left by the previous
+    /// run is removed before the command starts, so the outdated report is never used. Coverage of
+    /// every file in the report is returned too, with paths relative to the `repo_root`.
+    /// If the report is not configured or it can't be read, the output of the command is parsed by
+    /// using output parser from `coverage_cmd`.
     ///
     /// It can fail in a few ways:
     /// - there was an error while running the command (for example no binary in PATH)
-    /// - there is no parser in the `coverage_cmd` (parser is required in this case)
+    /// - there is no report and no parser in the `coverage_cmd`
     /// - the parser failed to parse the output produced by the `coverage_cmd`
     #[instrument(skip(self))]
     fn run(&self, repo_root: RepoRoot) -> Result<CoverageRunStatus, CoverageErr> {
-        let repo_root = repo_root.to_string();
         debug!("running coverage in {repo_root}");
-        let Ok(output) = self.cfg.coverage_cmd.stdout(repo_root) else {
+        self.remove_stale_report(&repo_root);
+        let Ok(output) = self.cfg.coverage_cmd.stdout(&repo_root) else {
             error!("command failed");
             return Ok(CoverageRunStatus::Failure);
         };
 
+        if let Some(report) = self.report(&repo_root) {
+            if let Some(summary) = report.summary() {
+                debug!("read coverage of {} files", report.files.len());
+                let files = report
+                    .files
+                    .into_iter()
+                    .map(|file| relative_to(file, repo_root.as_ref()))
+                    .collect();
+                return Ok(CoverageRunStatus::Success(summary, files));
+            }
+            error!("no instrumented lines in the report");
+        }
+
         let Some(parser) = self.cfg.coverage_cmd.parser() else {
             error!("this command requires output parser");
             return Ok(CoverageRunStatus::Failure);
@@ -48,7 +100,27 @@ impl CovRunner for DefaultCoverageRunner {
             return Ok(CoverageRunStatus::Failure);
         };
 
-        Ok(CoverageRunStatus::Success(coverage))
+        Ok(CoverageRunStatus::Success(coverage, Vec::new()))
+    }
+}
+
+fn read_report(
+    location: &ReportLocation,
+    repo_root: &RepoRoot,
+) -> Result<CoverageReport, CoverageErr> {
+    let content = fs::read_to_string(repo_root.as_ref().join(&location.path))?;
+    let report = match location.format {
+        ReportFormat::Lcov => LcovParser.parse(content)?,
+        ReportFormat::Cobertura => CoberturaParser.parse(content)?,
+        ReportFormat::TarpaulinJson => TarpaulinJsonParser.parse(content)?,
+    };
+    Ok(report)
+}
+
+fn relative_to(file: FileCoverage, repo_root: &Path) -> FileCoverage {
+    match Path::new(&file.path).strip_prefix(repo_root) {
+        Ok(path) => FileCoverage::new(path.to_string_lossy(), file.lines),
+        Err(_) => file,
     }
 }
 
@@ -59,9 +131,12 @@ mod test {
     use crate::configuration::config::ConfigBuilder;
     use crate::configuration::tracing::init_tracing;
     use crate::data_providers::command::Cmd;
+    use crate::entities::coverage_summary::CoverageSummary;
     use crate::testingtools::output_parser::{failing, working};
 
     use anyhow::Result;
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
 
     #[test]
     fn it_fails_with_invalid_cmd() -> Result<()> {
@@ -134,7 +209,127 @@ mod test {
         let res = cov_runner.run(repo_root)?;
 
         // then
-        assert_eq!(res, CoverageRunStatus::Success(60.0));
+        assert_eq!(
+            res,
+            CoverageRunStatus::Success(CoverageSummary::new(60.0), Vec::new())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_returns_files_from_report_relative_to_repo_root() -> Result<()> {
+        // given
+        init_tracing();
+        let dir = tempdir()?;
+        let script = format!(
+            "printf 'SF:{}/src/lib.rs\\nDA:1,1\\nDA:2,0\\nend_of_record\\n' > lcov.info",
+            dir.path().display()
+        );
+        let cfg = ConfigBuilder::default()
+            .coverage_cmd(Cmd::new("sh", &["-c", &script]))
+            .coverage_report(Some(ReportLocation::new("lcov.info", ReportFormat::Lcov)))
+            .build()?;
+        let cov_runner = DefaultCoverageRunner::make(cfg);
+
+        // when
+        let res = cov_runner.run(RepoRoot::new(dir.path()))?;
+
+        // then
+        assert_eq!(
+            res,
+            CoverageRunStatus::Success(
+                CoverageSummary::new(50.0),
+                vec![FileCoverage::new(
+                    "src/lib.rs",
+                    BTreeMap::from([(1, 1), (2, 0)])
+                )]
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_when_report_is_missing_and_cmd_does_not_have_parser() -> Result<()> {
+        // given
+        init_tracing();
+        let dir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .coverage_cmd(Cmd::new("ls", &[]))
+            .coverage_report(Some(ReportLocation::new(
+                "no-such-report.info",
+                ReportFormat::Lcov,
+            )))
+            .build()?;
+        let cov_runner = DefaultCoverageRunner::make(cfg);
+
+        // when
+        let res = cov_runner.run(RepoRoot::new(dir.path()))?;
+
+        // then
+        assert_eq!(res, CoverageRunStatus::Failure);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_reads_coverage_from_report_instead_of_output() -> Result<()> {
+        // given
+        init_tracing();
+        let dir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .coverage_cmd(Cmd::with_parser(
+                "sh",
+                &[
+                    "-c",
+                    "printf 'SF:src/lib.rs\\nDA:1,1\\nDA:2,1\\nDA:3,0\\nDA:4,0\\n' > lcov.info",
+                ],
+                failing(),
+            ))
+            .coverage_report(Some(ReportLocation::new("lcov.info", ReportFormat::Lcov)))
+            .build()?;
+        let cov_runner = DefaultCoverageRunner::make(cfg);
+
+        // when
+        let res = cov_runner.run(RepoRoot::new(dir.path()))?;
+
+        // then
+        assert_eq!(
+            res,
+            CoverageRunStatus::Success(
+                CoverageSummary::new(50.0),
+                vec![FileCoverage::new(
+                    "src/lib.rs",
+                    BTreeMap::from([(1, 1), (2, 1), (3, 0), (4, 0)])
+                )]
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_falls_back_to_output_parser_when_report_is_not_written() -> Result<()> {
+        // given
+        init_tracing();
+        let dir = tempdir()?;
+        fs::write(dir.path().join("lcov.info"), "SF:src/lib.rs\nDA:1,1\n")?;
+        let cfg = ConfigBuilder::default()
+            .coverage_cmd(Cmd::with_parser("ls", &[], working(60.0)))
+            .coverage_report(Some(ReportLocation::new("lcov.info", ReportFormat::Lcov)))
+            .build()?;
+        let cov_runner = DefaultCoverageRunner::make(cfg);
+
+        // when
+        let res = cov_runner.run(RepoRoot::new(dir.path()))?;
+
+        // then
+        assert_eq!(
+            res,
+            CoverageRunStatus::Success(CoverageSummary::new(60.0), Vec::new())
+        );
+        assert!(!dir.path().join("lcov.info").exists());
 
         Ok(())
     }
@@ -0,0 +1,225 @@
+use crate::entities::diff_coverage::ChangedLines;
+use crate::result::DiffParseErr;
+use crate::use_cases::output_parser::OutputParser;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeSet;
+use tracing::{error, instrument, trace};
+
+const OLD_COUNT: usize = 1;
+const START: usize = 2;
+const COUNT: usize = 3;
+static HUNK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^@@ -\d+(?:,(\d+))? \+(\d+)(?:,(\d+))? @@").unwrap());
+
+/// Parses the output of `git diff --unified=0` into lines added or modified in every file.
+///
+/// It can fail. See [`DiffParser::parse`] for details.
+pub struct DiffParser;
+
+impl OutputParser for DiffParser {
+    type Output = Vec<ChangedLines>;
+    type Error = DiffParseErr;
+
+    /// Parses the diff and returns changed lines of every file which exists after the change.
+    ///
+    /// Deleted files and files without added lines are skipped. It fails when a hunk is found
+    /// before the file header.
+    #[instrument(skip(self, output))]
+    fn parse(&self, output: String) -> Result<Self::Output, Self::Error> {
+        let mut changes: Vec<ChangedLines> = Vec::new();
+        let mut curr_file: Option<ChangedLines> = None;
+        let mut in_file = false;
+        let mut old_left: u32 = 0;
+        let mut new_left: u32 = 0;
+        for line in output.lines() {
+            // NOTE: lines of a hunk can look like headers (eg.: removed `-- a` is `--- a`), so they
+            // are skipped until all lines announced by the hunk header are read
+            if old_left > 0 || new_left > 0 {
+                match line.chars().next() {
+                    Some('-') => old_left = old_left.saturating_sub(1),
+                    Some('+') => new_left = new_left.saturating_sub(1),
+                    Some('\\') => trace!("no newline at end of file"),
+                    _ => {
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
+                    }
+                }
+                continue;
+            }
+
+            if line.starts_with("diff ") {
+                changes.extend(curr_file.take());
+                in_file = false;
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                changes.extend(curr_file.take());
+                in_file = true;
+                // NOTE: deleted file has no new version
+                curr_file = path
+                    .strip_prefix("b/")
+                    .map(|path| ChangedLines::new(path, BTreeSet::new()));
+            } else if let Some(captures) = HUNK_RE.captures(line) {
+                if !in_file {
+                    error!("hunk without file header: {line}");
+                    return Err(DiffParseErr::NoFileHeader);
+                }
+                // NOTE: all groups contain only digits, count is `1` when omitted
+                let count = |group: usize| -> u32 {
+                    captures
+                        .get(group)
+                        .map_or(1, |count| count.as_str().parse().unwrap_or_default())
+                };
+                old_left = count(OLD_COUNT);
+                new_left = count(COUNT);
+                let Some(file) = curr_file.as_mut() else {
+                    trace!("hunk of deleted file");
+                    continue;
+                };
+                let start: u32 = captures[START].parse().unwrap_or_default();
+                file.lines.extend(start..start + new_left);
+            }
+        }
+        changes.extend(curr_file);
+
+        Ok(changes
+            .into_iter()
+            .filter(|changed| !changed.lines.is_empty())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn empty_diff_has_no_changes() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = DiffParser;
+
+        // when
+        let res = parser.parse(String::new())?;
+
+        // then
+        assert!(res.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn added_and_modified_lines_of_every_file_are_parsed() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = DiffParser;
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2 +2 @@ fn main() {
+-    old();
++    new();
+@@ -10,0 +11,3 @@ fn other() {
++a
++b
++c
+@@ -20,2 +23,0 @@ fn removed() {
+-x
+-y
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,2 @@
++fn a() {}
++fn b() {}
+diff --git a/src/gone.rs b/src/gone.rs
+deleted file mode 100644
+--- a/src/gone.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn a() {}
+-fn b() {}
+";
+
+        // when
+        let res = parser.parse(diff.to_string())?;
+
+        // then
+        assert_eq!(
+            res,
+            vec![
+                ChangedLines::new("src/lib.rs", BTreeSet::from([2, 11, 12, 13])),
+                ChangedLines::new("src/new.rs", BTreeSet::from([1, 2])),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn file_with_only_removed_lines_is_skipped() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = DiffParser;
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -3,2 +2,0 @@\n-x\n-y\n";
+
+        // when
+        let res = parser.parse(diff.to_string())?;
+
+        // then
+        assert!(res.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn lines_of_hunk_looking_like_file_headers_are_not_parsed_as_headers() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = DiffParser;
+        let diff = "\
+diff --git a/a.sql b/a.sql
+--- a/a.sql
++++ b/a.sql
+@@ -1,2 +1,2 @@
+--- old comment
+-x
++++ new comment
++y
+@@ -5 +5 @@
+-z
++w
+";
+
+        // when
+        let res = parser.parse(diff.to_string())?;
+
+        // then
+        assert_eq!(
+            res,
+            vec![ChangedLines::new("a.sql", BTreeSet::from([1, 2, 5]))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_when_hunk_is_before_file_header() {
+        // given
+        init_tracing();
+        let parser = DiffParser;
+
+        // when
+        let res = parser.parse("@@ -1 +1 @@\n-a\n+b\n".to_string());
+
+        // then
+        assert!(matches!(res, Err(DiffParseErr::NoFileHeader)));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::configuration::config::Config;
+use crate::entities::repo_root::RepoRoot;
+use crate::result::FmtErr;
+use crate::use_cases::fmt_runner::{FRunner, FmtRunStatus, FmtRunner};
+
+use std::path::Path;
+use tracing::{debug, instrument};
+
+/// It runs the command checking the formatting. Command is passed in via `Config::fmt_cmd`.
+///
+/// The command is expected to print the paths of unformatted files, one per line, and to exit
+/// with non-zero status code when there are any (like `cargo fmt --check --message-format short`).
+#[derive(Debug)]
+pub struct DefaultFmtRunner {
+    cfg: Config,
+}
+
+impl DefaultFmtRunner {
+    pub fn make(cfg: Config) -> FmtRunner {
+        Box::new(Self { cfg })
+    }
+}
+
+impl FRunner for DefaultFmtRunner {
+    /// Executes the `fmt_cmd` on the path specified by `repo_root`. Paths of unformatted files
+    /// are returned relative to `repo_root`. Command which can't be executed is a failure
+    /// without any files.
+    #[instrument(skip(self))]
+    fn run(&self, repo_root: RepoRoot) -> Result<FmtRunStatus, FmtErr> {
+        debug!("checking formatting in {repo_root}");
+        let Ok((status, stdout)) = self.cfg.fmt_cmd.status_and_stdout(&repo_root) else {
+            debug!("command failed");
+            return Ok(FmtRunStatus::Failure(Vec::new()));
+        };
+
+        if status.success() {
+            debug!("all files are formatted");
+            return Ok(FmtRunStatus::Success);
+        }
+
+        let files: Vec<String> = stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| relative(line, repo_root.as_ref()))
+            .collect();
+        debug!("unformatted files: {files:?}");
+        Ok(FmtRunStatus::Failure(files))
+    }
+}
+
+fn relative(path: &str, repo_root: &Path) -> String {
+    Path::new(path)
+        .strip_prefix(repo_root)
+        .map_or(path.to_string(), |path| path.display().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::data_providers::command::Cmd;
+
+    use anyhow::Result;
+    use cmd_lib::run_cmd;
+    use tempfile::tempdir;
+
+    #[test]
+    fn unformatted_files_are_returned_relative_to_repo_root() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        run_cmd!(
+            cd $tmpdir_path;
+            cargo new test_project;
+            echo "fn  main( ) {}" > $tmpdir_path/test_project/src/main.rs
+        )?;
+        let runner = DefaultFmtRunner::make(cfg()?);
+        let root = RepoRoot::new(tmpdir_path.join("test_project"));
+
+        // when
+        let res = runner.run(root)?;
+
+        // then
+        assert_eq!(res, FmtRunStatus::Failure(vec!["src/main.rs".into()]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_files_are_formatted_then_success_status_is_returned() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        run_cmd!(cd $tmpdir_path ; cargo new test_project)?;
+        let runner = DefaultFmtRunner::make(cfg()?);
+        let root = RepoRoot::new(tmpdir_path.join("test_project"));
+
+        // when
+        let res = runner.run(root)?;
+
+        // then
+        assert_eq!(res, FmtRunStatus::Success);
+
+        Ok(())
+    }
+
+    fn cfg() -> Result<Config> {
+        Ok(ConfigBuilder::default()
+            .fmt_cmd(Cmd::new(
+                "cargo",
+                &["fmt", "--check", "--message-format", "short"],
+            ))
+            .build()?)
+    }
+}
@@ -0,0 +1,219 @@
+use crate::configuration::config::Config;
+use crate::entities::pipeline_run::PipelineRun;
+use crate::result::HistoryErr;
+use crate::use_cases::history::{History, HistoryQuery, RunHistory};
+
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, instrument};
+
+/// Stores pipeline runs in a file, one JSON object per line. The path of the file is passed in via
+/// `Config::history_path`.
+///
+/// Runs are only appended to the file, so the history survives restarts of the daemon.
+#[derive(Debug)]
+pub struct JsonLinesHistory {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonLinesHistory {
+    pub fn make(cfg: Config) -> History {
+        Arc::new(Self {
+            path: cfg.history_path,
+            lock: Mutex::new(()),
+        })
+    }
+}
+
+impl RunHistory for JsonLinesHistory {
+    #[instrument(skip(self))]
+    fn append(&self, run: PipelineRun) -> Result<(), HistoryErr> {
+        let _lock = self.lock.lock().expect("poisoned mutex");
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&run)?)?;
+        Ok(())
+    }
+
+    /// Reads the runs matching the `query`.
+    ///
+    /// Lines which can't be parsed are skipped. Missing history file means there were no runs yet.
+    #[instrument(skip(self))]
+    fn runs(&self, query: &HistoryQuery) -> Result<Vec<PipelineRun>, HistoryErr> {
+        let _lock = self.lock.lock().expect("poisoned mutex");
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                debug!("no history file, there were no runs yet");
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let runs: Vec<PipelineRun> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(run) => Some(run),
+                Err(e) => {
+                    error!("skipping invalid history entry: {e}");
+                    None
+                }
+            })
+            .collect();
+
+        Ok(runs
+            .into_iter()
+            .rev()
+            .filter(|run| query.matches(run))
+            .skip(query.offset)
+            .take(query.limit)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::pipeline_run::Trigger;
+    use crate::entities::repo_root::RepoRoot;
+    use crate::entities::run_id::RunId;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    #[test]
+    fn when_there_is_no_history_file_there_are_no_runs() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .history_path(tmpdir.path().join("history.jsonl"))
+            .build()?;
+        let history = JsonLinesHistory::make(cfg);
+
+        // when
+        let runs = history.runs(&HistoryQuery::all())?;
+
+        // then
+        assert!(runs.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn appended_runs_are_returned_from_the_newest_one() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .history_path(tmpdir.path().join("nested").join("history.jsonl"))
+            .build()?;
+        let history = JsonLinesHistory::make(cfg);
+        let first = run("/first");
+        let second = run("/second");
+
+        // when
+        history.append(first.clone())?;
+        history.append(second.clone())?;
+
+        // then
+        assert_eq!(history.runs(&HistoryQuery::all())?, vec![second, first]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn runs_can_be_filtered_by_repo_root() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .history_path(tmpdir.path().join("history.jsonl"))
+            .build()?;
+        let history = JsonLinesHistory::make(cfg);
+        let first = run("/first");
+        history.append(first.clone())?;
+        history.append(run("/second"))?;
+        let query = HistoryQuery {
+            repo_root: Some(RepoRoot::new("/first")),
+            ..HistoryQuery::all()
+        };
+
+        // when
+        let runs = history.runs(&query)?;
+
+        // then
+        assert_eq!(runs, vec![first]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn runs_can_be_paginated() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .history_path(tmpdir.path().join("history.jsonl"))
+            .build()?;
+        let history = JsonLinesHistory::make(cfg);
+        let second = run("/second");
+        history.append(run("/first"))?;
+        history.append(second.clone())?;
+        history.append(run("/third"))?;
+        let query = HistoryQuery {
+            repo_root: None,
+            offset: 1,
+            limit: 1,
+        };
+
+        // when
+        let runs = history.runs(&query)?;
+
+        // then
+        assert_eq!(runs, vec![second]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_entries_are_skipped() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let path = tmpdir.path().join("history.jsonl");
+        fs::write(&path, "not a json\n")?;
+        let cfg = ConfigBuilder::default().history_path(path).build()?;
+        let history = JsonLinesHistory::make(cfg);
+        let valid = run("/valid");
+
+        // when
+        history.append(valid.clone())?;
+
+        // then
+        assert_eq!(history.runs(&HistoryQuery::all())?, vec![valid]);
+
+        Ok(())
+    }
+
+    fn run(repo_root: &str) -> PipelineRun {
+        PipelineRun::new(
+            RunId::new(),
+            Trigger::Change,
+            RepoRoot::new(repo_root),
+            None,
+        )
+    }
+}
@@ -0,0 +1,322 @@
+use crate::configuration::config::Config;
+use crate::entities::hook::{HookAction, HookPayload};
+use crate::result::HookRunErr;
+use crate::use_cases::hook_runner::{HookRunner, RunHook};
+
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Client, Request};
+use std::io::{ErrorKind, Write};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::{runtime, time};
+use tracing::{debug, instrument};
+
+/// How often the running command is checked for completion.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs hooks with the timeout set in `Config::hook_timeout`.
+///
+/// Commands are run with `sh -c` in the repo root. The event is passed as JSON on stdin and its
+/// name, the repo root and the name of the custom stage in `CHESTER_EVENT`, `CHESTER_REPO_ROOT`
+/// and `CHESTER_STAGE` environment variables. Command is run in its own process group, so the
+/// processes it started are killed with it on timeout.
+/// HTTP hooks post the event JSON to the URL and fail on non-2xx status. Only plain `http://` is
+/// supported.
+#[derive(Debug)]
+pub struct DefaultHookRunner {
+    cfg: Config,
+}
+
+impl DefaultHookRunner {
+    pub fn make(cfg: Config) -> HookRunner {
+        Arc::new(Self { cfg })
+    }
+
+    fn command(&self, cmd: &str, payload: &HookPayload) -> Result<(), HookRunErr> {
+        let timeout = self.cfg.hook_timeout;
+        let mut child = Command::new("sh")
+            .args(["-c", cmd])
+            .current_dir(&payload.repo_root)
+            .env("CHESTER_EVENT", &payload.event)
+            .env("CHESTER_REPO_ROOT", payload.repo_root.to_string())
+            .env(
+                "CHESTER_STAGE",
+                payload.stage.as_deref().unwrap_or_default(),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // NOTE: Command doesn't have to read the event, so closed stdin is not an error.
+            match stdin.write_all(&serde_json::to_vec(payload)?) {
+                Err(e) if e.kind() != ErrorKind::BrokenPipe => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        let started = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait()? {
+                if !status.success() {
+                    return Err(HookRunErr::Failed(status.to_string()));
+                }
+                return Ok(());
+            }
+            if started.elapsed() >= timeout {
+                debug!("killing hook after {timeout:?}");
+                kill_group(&mut child)?;
+                child.wait()?;
+                return Err(HookRunErr::Timeout(timeout));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn http(&self, url: &str, payload: &HookPayload) -> Result<(), HookRunErr> {
+        let timeout = self.cfg.hook_timeout;
+        let request = Request::post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_vec(payload)?))
+            .map_err(|e| HookRunErr::Failed(format!("invalid url {url}: {e}")))?;
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let posted = async { time::timeout(timeout, Client::new().request(request)).await };
+        let Ok(response) = runtime.block_on(posted) else {
+            return Err(HookRunErr::Timeout(timeout));
+        };
+        let status = response?.status();
+        if !status.is_success() {
+            return Err(HookRunErr::Failed(status.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Kills the whole process group of the child, started with `process_group(0)`.
+fn kill_group(child: &mut Child) -> Result<(), HookRunErr> {
+    let group = format!("-{}", child.id());
+    let killed = Command::new("kill")
+        .args(["-KILL", "--", &group])
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or_default();
+    if !killed {
+        debug!("failed to kill process group {group}");
+        child.kill()?;
+    }
+    Ok(())
+}
+
+impl RunHook for DefaultHookRunner {
+    #[instrument(skip(self))]
+    fn run(&self, action: &HookAction, payload: &HookPayload) -> Result<(), HookRunErr> {
+        match action {
+            HookAction::Command(cmd) => self.command(cmd, payload),
+            HookAction::Http(url) => self.http(url, payload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::repo_root::RepoRoot;
+
+    use anyhow::Result;
+    use std::fs;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::{TcpListener, TcpStream};
+    use tempfile::tempdir;
+
+    #[test]
+    fn command_gets_event_on_stdin_and_in_env() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let runner = DefaultHookRunner::make(cfg(Duration::from_secs(5))?);
+        let payload = HookPayload::new("tests-failed", RepoRoot::new(tmpdir.path()));
+        let cmd = "cat > event.json; echo \"$CHESTER_EVENT $CHESTER_REPO_ROOT\" > env";
+
+        // when
+        runner.run(&HookAction::Command(cmd.into()), &payload)?;
+
+        // then
+        let event: HookPayload =
+            serde_json::from_str(&fs::read_to_string(tmpdir.path().join("event.json"))?)?;
+        assert_eq!(event, payload);
+        assert_eq!(
+            fs::read_to_string(tmpdir.path().join("env"))?,
+            format!("tests-failed {}\n", tmpdir.path().display())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn command_running_longer_than_timeout_is_killed() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let runner = DefaultHookRunner::make(cfg(Duration::from_millis(100))?);
+        let payload = HookPayload::new("tests-failed", RepoRoot::new(tmpdir.path()));
+
+        // when
+        let res = runner.run(&HookAction::Command("sleep 5".into()), &payload);
+
+        // then
+        assert!(matches!(res, Err(HookRunErr::Timeout(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn processes_started_by_command_are_killed_on_timeout() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let runner = DefaultHookRunner::make(cfg(Duration::from_millis(100))?);
+        let payload = HookPayload::new("tests-failed", RepoRoot::new(tmpdir.path()));
+        let cmd = "(sleep 1; touch survived) & wait";
+
+        // when
+        let res = runner.run(&HookAction::Command(cmd.into()), &payload);
+
+        // then
+        assert!(matches!(res, Err(HookRunErr::Timeout(_))));
+        thread::sleep(Duration::from_millis(1500));
+        assert!(!tmpdir.path().join("survived").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failing_command_is_reported() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let runner = DefaultHookRunner::make(cfg(Duration::from_secs(5))?);
+        let payload = HookPayload::new("tests-failed", RepoRoot::new(tmpdir.path()));
+
+        // when
+        let res = runner.run(&HookAction::Command("exit 3".into()), &payload);
+
+        // then
+        assert!(matches!(res, Err(HookRunErr::Failed(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn event_is_posted_to_http_endpoint() -> Result<()> {
+        // given
+        init_tracing();
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/hook", listener.local_addr()?);
+        let server = thread::spawn(move || -> Result<String> {
+            let (mut stream, _) = listener.accept()?;
+            let request = read_request(&mut stream)?;
+            stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n")?;
+            Ok(request)
+        });
+        let runner = DefaultHookRunner::make(cfg(Duration::from_secs(5))?);
+        let payload = HookPayload::new("coverage-passed", RepoRoot::new("/repo"));
+
+        // when
+        runner.run(&HookAction::Http(url), &payload)?;
+
+        // then
+        let Ok(request) = server.join() else {
+            panic!("server thread panicked");
+        };
+        let request = request?;
+        assert!(request.starts_with("POST /hook HTTP/1.1\r\n"));
+        assert!(request.contains("content-type: application/json\r\n"));
+        assert!(request.ends_with(&serde_json::to_string(&payload)?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn http_error_status_is_reported() -> Result<()> {
+        // given
+        init_tracing();
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        thread::spawn(move || -> Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            read_request(&mut stream)?;
+            stream.write_all(b"HTTP/1.1 500 Internal Server Error\r\ncontent-length: 0\r\n\r\n")?;
+            Ok(())
+        });
+        let runner = DefaultHookRunner::make(cfg(Duration::from_secs(5))?);
+        let payload = HookPayload::new("coverage-passed", RepoRoot::new("/repo"));
+
+        // when
+        let res = runner.run(&HookAction::Http(url), &payload);
+
+        // then
+        assert!(matches!(res, Err(HookRunErr::Failed(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn http_endpoint_not_responding_in_time_is_reported() -> Result<()> {
+        // given
+        init_tracing();
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        thread::spawn(move || -> Result<()> {
+            let (mut stream, _) = listener.accept()?;
+            read_request(&mut stream)?;
+            thread::sleep(Duration::from_secs(1));
+            Ok(())
+        });
+        let runner = DefaultHookRunner::make(cfg(Duration::from_millis(100))?);
+        let payload = HookPayload::new("coverage-passed", RepoRoot::new("/repo"));
+
+        // when
+        let res = runner.run(&HookAction::Http(url), &payload);
+
+        // then
+        assert!(matches!(res, Err(HookRunErr::Timeout(_))));
+
+        Ok(())
+    }
+
+    /// Reads the request up to the end of its body, the connection is kept open by the client.
+    fn read_request(stream: &mut TcpStream) -> Result<String> {
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                content_length = len.trim().parse()?;
+            }
+            request.push_str(&line);
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        request.push_str(&String::from_utf8_lossy(&body));
+        Ok(request)
+    }
+
+    fn cfg(timeout: Duration) -> Result<Config> {
+        Ok(ConfigBuilder::default().hook_timeout(timeout).build()?)
+    }
+}
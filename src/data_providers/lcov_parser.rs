@@ -0,0 +1,283 @@
+use crate::entities::coverage_report::{CoverageReport, Metric};
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::file_coverage::FileCoverage;
+use crate::result::{CoverageParseErr, ReportParseErr};
+use crate::use_cases::output_parser::{OutputParser, Parser};
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tracing::{error, instrument, trace};
+
+/// Parses the coverage report in the lcov format.
+///
+/// Lines coverage is calculated from `DA` (line hits) entries of every `SF` (source file).
+/// Branches and functions coverage is read from `BRF`/`BRH` and `FNF`/`FNH` totals.
+/// The rest of the report is ignored. See [`LcovParser::parse`] for details.
+pub struct LcovParser;
+
+impl OutputParser for LcovParser {
+    type Output = CoverageReport;
+    type Error = ReportParseErr;
+
+    /// Parses the lcov report and returns coverage of every file mentioned in the report.
+    ///
+    /// It can fail when:
+    /// - line hits are reported before the source file
+    /// - an entry is malformed (eg.: `DA:abc,1`)
+    #[instrument(skip(self, output))]
+    fn parse(&self, output: String) -> Result<Self::Output, Self::Error> {
+        let mut files = Vec::new();
+        let mut curr_file: Option<FileCoverage> = None;
+        let mut branches: Option<Metric> = None;
+        let mut functions: Option<Metric> = None;
+        for line in output.lines().map(str::trim) {
+            if let Some(path) = line.strip_prefix("SF:") {
+                files.extend(curr_file.take());
+                curr_file = Some(FileCoverage::new(path, BTreeMap::new()));
+            } else if let Some(hits) = line.strip_prefix("DA:") {
+                let Some(file) = curr_file.as_mut() else {
+                    error!("line hits without source file: {line}");
+                    return Err(ReportParseErr::NoSourceFile);
+                };
+                let (line_no, hits) = parse_line_hits(hits)?;
+                *file.lines.entry(line_no).or_default() += hits;
+            } else if let Some(total) = line.strip_prefix("BRF:") {
+                branches.get_or_insert_with(Metric::default).total += parse_count(total)?;
+            } else if let Some(covered) = line.strip_prefix("BRH:") {
+                branches.get_or_insert_with(Metric::default).covered += parse_count(covered)?;
+            } else if let Some(total) = line.strip_prefix("FNF:") {
+                functions.get_or_insert_with(Metric::default).total += parse_count(total)?;
+            } else if let Some(covered) = line.strip_prefix("FNH:") {
+                functions.get_or_insert_with(Metric::default).covered += parse_count(covered)?;
+            } else if line == "end_of_record" {
+                files.extend(curr_file.take());
+            } else {
+                trace!("ignoring '{line}'");
+            }
+        }
+        files.extend(curr_file);
+
+        let lines = Metric::new(
+            files.iter().map(FileCoverage::covered).sum(),
+            files.iter().map(FileCoverage::total).sum(),
+        );
+        Ok(CoverageReport {
+            lines,
+            branches,
+            functions,
+            files,
+        })
+    }
+}
+
+/// Parses the coverage report in the lcov format (eg.: printed by `grcov -t lcov`) and calculates
+/// the coverage summary of all files.
+///
+/// It can fail. See [`LcovCoverageParser::parse`] for details.
+pub struct LcovCoverageParser;
+
+impl LcovCoverageParser {
+    pub fn make() -> Parser<CoverageSummary, CoverageParseErr> {
+        Arc::new(Mutex::new(Self))
+    }
+}
+
+impl OutputParser for LcovCoverageParser {
+    type Output = CoverageSummary;
+    type Error = CoverageParseErr;
+
+    /// Parses the lcov report and returns percentage of covered lines, branches and functions.
+    ///
+    /// It fails when the report is malformed or there are no instrumented lines in it.
+    #[instrument(skip(self, output))]
+    fn parse(&self, output: String) -> Result<Self::Output, Self::Error> {
+        let report = LcovParser.parse(output).map_err(|e| {
+            error!("invalid lcov report: {e}");
+            CoverageParseErr::InvalidOutput
+        })?;
+
+        let Some(summary) = report.summary() else {
+            error!("no instrumented lines in lcov report");
+            return Err(CoverageParseErr::InvalidOutput);
+        };
+
+        Ok(summary)
+    }
+}
+
+fn parse_count(entry: &str) -> Result<u64, ReportParseErr> {
+    entry.parse::<u64>().map_err(|_| {
+        error!("invalid count: {entry}");
+        ReportParseErr::InvalidEntry(entry.to_string())
+    })
+}
+
+fn parse_line_hits(entry: &str) -> Result<(u32, u64), ReportParseErr> {
+    // NOTE: entry can contain a checksum as a third value, it's not needed
+    let mut values = entry.split(',');
+    let line_no = values.next().and_then(|v| v.parse::<u32>().ok());
+    let hits = values.next().and_then(|v| v.parse::<u64>().ok());
+    match (line_no, hits) {
+        (Some(line_no), Some(hits)) => Ok((line_no, hits)),
+        _ => {
+            error!("invalid line hits entry: {entry}");
+            Err(ReportParseErr::InvalidEntry(entry.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn empty_report_has_no_files() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+
+        // when
+        let res = parser.parse(String::new())?;
+
+        // then
+        assert!(res.files.is_empty());
+        assert_eq!(res.lines, Metric::new(0, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn line_hits_of_every_file_are_parsed() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+        let report =
+            "TN:\nSF:/repo/src/lib.rs\nFN:1,main\nDA:1,1\nDA:2,0\nLF:2\nLH:1\nend_of_record\n\
+                      SF:/repo/src/main.rs\nDA:3,5,abcdef\nend_of_record\n";
+
+        // when
+        let res = parser.parse(report.to_string())?;
+
+        // then
+        assert_eq!(
+            res.files,
+            vec![
+                FileCoverage::new("/repo/src/lib.rs", BTreeMap::from([(1, 1), (2, 0)])),
+                FileCoverage::new("/repo/src/main.rs", BTreeMap::from([(3, 5)])),
+            ]
+        );
+        assert_eq!(res.lines, Metric::new(2, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn branches_and_functions_are_summed_from_all_files() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+        let report = "SF:src/lib.rs\nFNF:2\nFNH:1\nBRF:4\nBRH:3\nend_of_record\n\
+                      SF:src/main.rs\nFNF:1\nFNH:1\nend_of_record\n";
+
+        // when
+        let res = parser.parse(report.to_string())?;
+
+        // then
+        assert_eq!(res.functions, Some(Metric::new(2, 3)));
+        assert_eq!(res.branches, Some(Metric::new(3, 4)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn there_are_no_branches_when_report_does_not_contain_them() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+
+        // when
+        let res = parser.parse("SF:src/lib.rs\nDA:1,1\nend_of_record\n".to_string())?;
+
+        // then
+        assert_eq!(res.branches, None);
+        assert_eq!(res.functions, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hits_of_repeated_line_are_summed() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+        let report = "SF:src/lib.rs\nDA:1,1\nDA:1,2\nend_of_record";
+
+        // when
+        let res = parser.parse(report.to_string())?;
+
+        // then
+        assert_eq!(res.files[0].lines, BTreeMap::from([(1, 3)]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_when_line_hits_are_reported_without_source_file() {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+
+        // when
+        let res = parser.parse("DA:1,1\n".to_string());
+
+        // then
+        assert!(matches!(res, Err(ReportParseErr::NoSourceFile)));
+    }
+
+    #[test]
+    fn it_fails_with_malformed_line_hits() {
+        // given
+        init_tracing();
+        let parser = LcovParser;
+
+        // when
+        let res = parser.parse("SF:src/lib.rs\nDA:one,1\n".to_string());
+
+        // then
+        assert!(matches!(res, Err(ReportParseErr::InvalidEntry(_))));
+    }
+
+    #[test]
+    fn coverage_is_calculated_from_lines_of_all_files() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LcovCoverageParser;
+        let report =
+            "SF:src/lib.rs\nDA:1,1\nDA:2,0\nend_of_record\nSF:src/main.rs\nDA:1,3\nDA:2,1\n";
+
+        // when
+        let res = parser.parse(report.to_string())?;
+
+        // then
+        assert_eq!(res, CoverageSummary::new(75.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_fails_when_there_are_no_instrumented_lines() {
+        // given
+        init_tracing();
+        let parser = LcovCoverageParser;
+
+        // when
+        let res = parser.parse("SF:src/lib.rs\nend_of_record\n".to_string());
+
+        // then
+        assert!(matches!(res, Err(CoverageParseErr::InvalidOutput)));
+    }
+}
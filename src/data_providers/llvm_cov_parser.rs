@@ -0,0 +1,173 @@
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::result::CoverageParseErr;
+use crate::use_cases::output_parser::{OutputParser, Parser};
+
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use tracing::{error, instrument};
+
+/// Parses the JSON summary printed by `cargo llvm-cov --json --summary-only`.
+///
+/// It can fail. See [`LlvmCovParser::parse`] for details.
+pub struct LlvmCovParser;
+
+impl LlvmCovParser {
+    pub fn make() -> Parser<CoverageSummary, CoverageParseErr> {
+        Arc::new(Mutex::new(Self))
+    }
+}
+
+impl OutputParser for LlvmCovParser {
+    type Output = CoverageSummary;
+    type Error = CoverageParseErr;
+
+    /// Parses the JSON export and returns percentage of covered lines, branches, functions and
+    /// regions. Branches are present only when `--branch` was used.
+    ///
+    /// It can fail when:
+    /// - output is not a valid llvm-cov JSON export
+    /// - export does not contain any data
+    /// - the coverage value is incorrect (eg.: more than 100%)
+    #[instrument(skip(self, output))]
+    fn parse(&self, output: String) -> Result<Self::Output, Self::Error> {
+        let export: LlvmCovExport = serde_json::from_str(&output).map_err(|e| {
+            error!("invalid llvm-cov export: {e}");
+            CoverageParseErr::InvalidOutput
+        })?;
+
+        let Some(data) = export.data.first() else {
+            error!("no data in llvm-cov export");
+            return Err(CoverageParseErr::InvalidOutput);
+        };
+
+        let totals = &data.totals;
+        Ok(CoverageSummary {
+            lines: percent(&totals.lines)?,
+            branches: reported(totals.branches.as_ref())?,
+            functions: reported(totals.functions.as_ref())?,
+            regions: reported(totals.regions.as_ref())?,
+        })
+    }
+}
+
+// NOTE: llvm-cov exports metrics with zero count when they are not collected (eg.: branches
+// without `--branch`), so these are treated as not reported
+fn reported(summary: Option<&LlvmCovSummary>) -> Result<Option<f32>, CoverageParseErr> {
+    match summary {
+        Some(summary) if summary.count > 0 => Ok(Some(percent(summary)?)),
+        _ => Ok(None),
+    }
+}
+
+fn percent(summary: &LlvmCovSummary) -> Result<f32, CoverageParseErr> {
+    let coverage = summary.percent;
+    if !(0.0..=100.0).contains(&coverage) {
+        return Err(CoverageParseErr::InvalidValue(format!(
+            "{coverage} value is invalid for code coverage"
+        )));
+    }
+    Ok(coverage)
+}
+
+#[derive(Debug, Deserialize)]
+struct LlvmCovExport {
+    data: Vec<LlvmCovData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlvmCovData {
+    totals: LlvmCovTotals,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlvmCovTotals {
+    lines: LlvmCovSummary,
+    branches: Option<LlvmCovSummary>,
+    functions: Option<LlvmCovSummary>,
+    regions: Option<LlvmCovSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LlvmCovSummary {
+    #[serde(default)]
+    count: u64,
+    percent: f32,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn with_correct_export_it_returns_all_reported_metrics() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = LlvmCovParser;
+        let output = r#"{"data":[{"totals":{
+            "lines":{"count":200,"covered":150,"percent":75.0},
+            "branches":{"count":0,"covered":0,"notcovered":0,"percent":0.0},
+            "functions":{"count":10,"covered":5,"percent":50.0},
+            "regions":{"count":4,"covered":1,"notcovered":3,"percent":25.0}
+        }}],"type":"llvm.coverage.json.export","version":"2.0.1"}"#;
+
+        // when
+        let res = parser.parse(output.to_string())?;
+
+        // then
+        assert_eq!(
+            res,
+            CoverageSummary {
+                lines: 75.0,
+                branches: None,
+                functions: Some(50.0),
+                regions: Some(25.0),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_when_output_is_not_json() {
+        // given
+        init_tracing();
+        let parser = LlvmCovParser;
+
+        // when
+        let res = parser.parse("\n75.00% coverage".to_string());
+
+        // then
+        assert!(matches!(res, Err(CoverageParseErr::InvalidOutput)));
+    }
+
+    #[test]
+    fn it_fails_when_export_has_no_data() {
+        // given
+        init_tracing();
+        let parser = LlvmCovParser;
+
+        // when
+        let res = parser.parse(r#"{"data":[]}"#.to_string());
+
+        // then
+        assert!(matches!(res, Err(CoverageParseErr::InvalidOutput)));
+    }
+
+    #[test]
+    fn it_fails_with_more_than_hundred_percent() {
+        // given
+        init_tracing();
+        let parser = LlvmCovParser;
+        let output = r#"{"data":[{"totals":{"lines":{"percent":100.5}}}]}"#;
+
+        // when
+        let res = parser.parse(output.to_string());
+
+        // then
+        assert!(matches!(res, Err(CoverageParseErr::InvalidValue(_))));
+    }
+}
@@ -1,10 +1,23 @@
 pub mod bus;
 pub mod change_watcher;
 pub mod check_runner;
+pub mod cobertura_parser;
 pub mod command;
 pub mod coverage_parser;
 pub mod coverage_runner;
+pub mod diff_parser;
+pub mod fmt_runner;
+pub mod history;
+pub mod hook_runner;
+pub mod lcov_parser;
+pub mod llvm_cov_parser;
+pub mod notifier;
+pub mod quality_analyzer;
 pub mod server;
+pub mod source_files;
+pub mod stage_runner;
 pub mod state;
+pub mod tarpaulin_json_parser;
 pub mod test_runner;
 pub mod tests_index;
+pub mod vcs;
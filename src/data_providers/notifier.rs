@@ -0,0 +1,99 @@
+use crate::configuration::config::Config;
+use crate::entities::notification::Notification;
+use crate::result::NotifierErr;
+use crate::use_cases::notifier::{Notifier, Notify};
+
+use std::env;
+use tracing::{debug, instrument};
+
+/// Timeout of the notification, in milliseconds.
+const EXPIRE_TIMEOUT: &str = "5000";
+
+/// Sends freedesktop notifications over D-Bus, using `Config::notify_cmd`.
+///
+/// Summary and body of the notification, together with empty actions, empty hints and timeout
+/// are appended to the command, so it matches the arguments of `Notify` method.
+#[derive(Debug)]
+pub struct DbusNotifier {
+    cfg: Config,
+}
+
+impl DbusNotifier {
+    pub fn make(cfg: Config) -> Notifier {
+        Box::new(Self { cfg })
+    }
+}
+
+impl Notify for DbusNotifier {
+    #[instrument(skip(self))]
+    fn notify(&self, notification: Notification) -> Result<(), NotifierErr> {
+        let Notification { summary, body } = notification;
+        let cmd =
+            self.cfg
+                .notify_cmd
+                .clone()
+                .with_args(&[&summary, &body, "[]", "{}", EXPIRE_TIMEOUT]);
+        let status = cmd.status(env::temp_dir())?;
+        if !status.success() {
+            debug!("notification failed with: {status}");
+            return Err(NotifierErr::Rejected(status.to_string()));
+        }
+        debug!("notification sent");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::data_providers::command::Cmd;
+
+    use anyhow::Result;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn notification_is_passed_to_notify_cmd() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let out = tmpdir.path().join("notification");
+        let script = format!("echo \"$@\" > {}", out.display());
+        let cfg = ConfigBuilder::default()
+            .notify_cmd(Cmd::new("sh", &["-c", &script, "sh"]))
+            .build()?;
+        let notifier = DbusNotifier::make(cfg);
+
+        // when
+        notifier.notify(Notification::new("tests failed", "first failing: it_works"))?;
+
+        // then
+        assert_eq!(
+            fs::read_to_string(out)?,
+            "tests failed first failing: it_works [] {} 5000\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_notify_cmd_fails_notification_is_rejected() -> Result<()> {
+        // given
+        init_tracing();
+        let cfg = ConfigBuilder::default()
+            .notify_cmd(Cmd::new("false", &[]))
+            .build()?;
+        let notifier = DbusNotifier::make(cfg);
+
+        // when
+        let res = notifier.notify(Notification::new("tests failed", ""));
+
+        // then
+        assert!(matches!(res, Err(NotifierErr::Rejected(_))));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,526 @@
+use crate::configuration::config::Config;
+use crate::data_providers::source_files::rust_files;
+use crate::entities::quality::{
+    maintainability_index, FileQuality, FunctionQuality, Halstead, QualityReport,
+};
+use crate::entities::repo_root::RepoRoot;
+use crate::entities::stats::ProjectStats;
+use crate::result::QualityErr;
+use crate::use_cases::quality_analyzer::{CodeAnalysis, QAnalyzer, QualityAnalyzer};
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::HashSet;
+use std::fs;
+use std::iter::Peekable;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Block, Signature};
+use tracing::{debug, instrument};
+
+/// Operators which are written as words. `self`, `Self`, `true` and `false` are operands.
+const KEYWORDS: [&str; 34] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "super", "trait", "type", "unsafe", "use", "where", "while",
+];
+
+/// It calculates the quality metrics and collects the statistics of all the Rust files in the
+/// repo, except the ignored paths (`Config::ignored_paths`).
+///
+/// Every file is read and parsed with `syn` once for both. Files which can't be parsed are left
+/// out of the metrics, but their lines are still counted.
+#[derive(Debug)]
+pub struct SynQualityAnalyzer {
+    cfg: Config,
+}
+
+impl SynQualityAnalyzer {
+    pub fn make(cfg: Config) -> QualityAnalyzer {
+        Box::new(Self { cfg })
+    }
+}
+
+impl QAnalyzer for SynQualityAnalyzer {
+    #[instrument(skip(self))]
+    fn analyze(&self, repo_root: RepoRoot) -> Result<CodeAnalysis, QualityErr> {
+        let root = repo_root.as_ref();
+        let mut files = Vec::new();
+        let mut stats = ProjectStats::default();
+        for path in rust_files(root, &self.cfg.ignored_paths)? {
+            let source = fs::read_to_string(&path)?;
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let (file, file_stats) = analyze_file(relative.display().to_string(), &source);
+            // NOTE: Locations of the spans are kept by proc-macro2 until they're invalidated.
+            proc_macro2::extra::invalidate_current_thread_spans();
+            stats += file_stats;
+            match file {
+                Some(file) => files.push(file),
+                None => debug!("failed to parse {relative:?}"),
+            }
+        }
+        debug!("quality of {} files calculated", files.len());
+        Ok(CodeAnalysis {
+            quality: QualityReport::new(files),
+            stats,
+        })
+    }
+}
+
+/// Quality metrics of the file, when it can be parsed, and its statistics.
+fn analyze_file(path: String, source: &str) -> (Option<FileQuality>, ProjectStats) {
+    let stats = ProjectStats::count_lines(source);
+    let Some((tokens, file)) = parse(source) else {
+        return (None, stats);
+    };
+    let mut unsafe_blocks = UnsafeBlocks(0);
+    unsafe_blocks.visit_file(&file);
+    let stats = ProjectStats {
+        unsafe_blocks: unsafe_blocks.0,
+        ..stats
+    };
+    (Some(file_quality(path, source, tokens, &file)), stats)
+}
+
+fn parse(source: &str) -> Option<(TokenStream, syn::File)> {
+    let tokens: TokenStream = source.parse().ok()?;
+    let file = syn::parse2::<syn::File>(tokens.clone()).ok()?;
+    Some((tokens, file))
+}
+
+fn file_quality(path: String, source: &str, tokens: TokenStream, file: &syn::File) -> FileQuality {
+    let mut visitor = FunctionsVisitor::default();
+    visitor.visit_file(file);
+
+    let loc = u32::try_from(source.lines().count()).unwrap_or(u32::MAX);
+    let cyclomatic = visitor.functions.iter().map(|f| f.cyclomatic).sum();
+    let halstead = halstead(tokens);
+    FileQuality {
+        path,
+        loc,
+        cyclomatic,
+        maintainability: maintainability_index(halstead.volume(), cyclomatic, loc),
+        halstead,
+        functions: visitor.functions,
+    }
+}
+
+/// Collects all the functions with a body, including methods and nested functions.
+#[derive(Default)]
+struct FunctionsVisitor {
+    types: Vec<String>,
+    functions: Vec<FunctionQuality>,
+}
+
+impl FunctionsVisitor {
+    fn add(&mut self, sig: &Signature, block: &Block) {
+        let name = match self.types.last() {
+            Some(ty) => format!("{ty}::{}", sig.ident),
+            None => sig.ident.to_string(),
+        };
+        // NOTE: Attributes are left out, so doc comments don't count as lines of the function.
+        let (start, end) = (sig.span().start().line, block.span().end().line);
+        let line = u32::try_from(start).unwrap_or(u32::MAX);
+        let loc = u32::try_from(end + 1 - start).unwrap_or(u32::MAX);
+
+        let mut complexity = Complexity(1);
+        complexity.visit_block(block);
+        let mut tokens = sig.to_token_stream();
+        block.to_tokens(&mut tokens);
+        let halstead = halstead(tokens);
+
+        self.functions.push(FunctionQuality {
+            name,
+            line,
+            loc,
+            cyclomatic: complexity.0,
+            maintainability: maintainability_index(halstead.volume(), complexity.0, loc),
+            halstead,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionsVisitor {
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.add(&item.sig, &item.block);
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_item_impl(&mut self, item: &'ast syn::ItemImpl) {
+        self.types.push(type_name(&item.self_ty));
+        visit::visit_item_impl(self, item);
+        self.types.pop();
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.add(&item.sig, &item.block);
+        visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_item_trait(&mut self, item: &'ast syn::ItemTrait) {
+        self.types.push(item.ident.to_string());
+        visit::visit_item_trait(self, item);
+        self.types.pop();
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast syn::TraitItemFn) {
+        if let Some(block) = &item.default {
+            self.add(&item.sig, block);
+        }
+        visit::visit_trait_item_fn(self, item);
+    }
+}
+
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(ty) => ty
+            .path
+            .segments
+            .last()
+            .map_or(String::new(), |segment| segment.ident.to_string()),
+        ty => ty.to_token_stream().to_string(),
+    }
+}
+
+/// Cyclomatic complexity of the function body: one plus the number of branches. Nested items
+/// are left out, nested functions are measured on their own.
+struct Complexity(u32);
+
+impl<'ast> Visit<'ast> for Complexity {
+    fn visit_item(&mut self, _item: &'ast syn::Item) {}
+
+    fn visit_expr_if(&mut self, expr: &'ast syn::ExprIf) {
+        self.0 += 1;
+        visit::visit_expr_if(self, expr);
+    }
+
+    fn visit_expr_while(&mut self, expr: &'ast syn::ExprWhile) {
+        self.0 += 1;
+        visit::visit_expr_while(self, expr);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr: &'ast syn::ExprForLoop) {
+        self.0 += 1;
+        visit::visit_expr_for_loop(self, expr);
+    }
+
+    fn visit_expr_match(&mut self, expr: &'ast syn::ExprMatch) {
+        let arms = u32::try_from(expr.arms.len()).unwrap_or(u32::MAX);
+        self.0 += arms.saturating_sub(1);
+        visit::visit_expr_match(self, expr);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+        if arm.guard.is_some() {
+            self.0 += 1;
+        }
+        visit::visit_arm(self, arm);
+    }
+
+    fn visit_local(&mut self, local: &'ast syn::Local) {
+        if local
+            .init
+            .as_ref()
+            .is_some_and(|init| init.diverge.is_some())
+        {
+            self.0 += 1;
+        }
+        visit::visit_local(self, local);
+    }
+
+    fn visit_expr_binary(&mut self, expr: &'ast syn::ExprBinary) {
+        if matches!(expr.op, syn::BinOp::And(_) | syn::BinOp::Or(_)) {
+            self.0 += 1;
+        }
+        visit::visit_expr_binary(self, expr);
+    }
+}
+
+/// Number of the `unsafe` blocks, including the ones nested in other blocks.
+struct UnsafeBlocks(u32);
+
+impl<'ast> Visit<'ast> for UnsafeBlocks {
+    fn visit_expr_unsafe(&mut self, expr: &'ast syn::ExprUnsafe) {
+        self.0 += 1;
+        visit::visit_expr_unsafe(self, expr);
+    }
+}
+
+/// Counts operators and operands in the tokens. Punctuation, delimiters and keywords are
+/// operators, identifiers and literals are operands. Attributes, including doc comments, are
+/// left out.
+fn halstead(tokens: TokenStream) -> Halstead {
+    let mut counter = HalsteadCounter::default();
+    counter.count(tokens);
+    Halstead {
+        distinct_operators: len(&counter.operators),
+        distinct_operands: len(&counter.operands),
+        total_operators: counter.total_operators,
+        total_operands: counter.total_operands,
+    }
+}
+
+fn len(set: &HashSet<String>) -> u32 {
+    u32::try_from(set.len()).unwrap_or(u32::MAX)
+}
+
+#[derive(Default)]
+struct HalsteadCounter {
+    operators: HashSet<String>,
+    operands: HashSet<String>,
+    total_operators: u32,
+    total_operands: u32,
+}
+
+impl HalsteadCounter {
+    fn count(&mut self, tokens: TokenStream) {
+        let mut tokens = tokens.into_iter().peekable();
+        let mut punct = String::new();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(p) if p.as_char() == '#' && punct.is_empty() => {
+                    if !skip_attribute(&mut tokens) {
+                        self.operator("#".into());
+                    }
+                }
+                TokenTree::Punct(p) => {
+                    punct.push(p.as_char());
+                    if p.spacing() == Spacing::Alone {
+                        self.operator(std::mem::take(&mut punct));
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let delimiter = match group.delimiter() {
+                        Delimiter::Parenthesis => Some("()"),
+                        Delimiter::Brace => Some("{}"),
+                        Delimiter::Bracket => Some("[]"),
+                        Delimiter::None => None,
+                    };
+                    if let Some(delimiter) = delimiter {
+                        self.operator(delimiter.into());
+                    }
+                    self.count(group.stream());
+                }
+                TokenTree::Ident(ident) => {
+                    let ident = ident.to_string();
+                    if KEYWORDS.contains(&ident.as_str()) {
+                        self.operator(ident);
+                    } else {
+                        self.operand(ident);
+                    }
+                }
+                TokenTree::Literal(literal) => self.operand(literal.to_string()),
+            }
+        }
+        if !punct.is_empty() {
+            self.operator(punct);
+        }
+    }
+
+    fn operator(&mut self, operator: String) {
+        self.total_operators += 1;
+        self.operators.insert(operator);
+    }
+
+    fn operand(&mut self, operand: String) {
+        self.total_operands += 1;
+        self.operands.insert(operand);
+    }
+}
+
+/// Skips the attribute following the `#`, returns whether there was one.
+fn skip_attribute<I: Iterator<Item = TokenTree>>(tokens: &mut Peekable<I>) -> bool {
+    if matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '!') {
+        tokens.next();
+    }
+    let is_attribute = matches!(
+        tokens.peek(),
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket
+    );
+    if is_attribute {
+        tokens.next();
+    }
+    is_attribute
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::ignored_path::IgnoredPath;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    #[test]
+    fn cyclomatic_complexity_counts_branches_of_every_function() -> Result<()> {
+        // given
+        init_tracing();
+        let source = r#"
+            fn simple() -> u32 {
+                1
+            }
+
+            struct Parser;
+
+            impl Parser {
+                /// Parses the value.
+                fn parse(&self, val: Option<u32>) -> u32 {
+                    let Some(val) = val else {
+                        return 0;
+                    };
+                    if val > 10 && val < 20 {
+                        return 1;
+                    }
+                    match val {
+                        0 => 0,
+                        v if v % 2 == 0 => 2,
+                        _ => 3,
+                    }
+                }
+            }
+        "#;
+
+        // when
+        let (file, _) = analyze_file("src/lib.rs".into(), source);
+        let file = file.expect("file not parsed");
+
+        // then
+        let functions: Vec<_> = file
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.line, f.loc, f.cyclomatic))
+            .collect();
+        assert_eq!(
+            functions,
+            vec![("simple", 2, 3, 1), ("Parser::parse", 10, 13, 7)]
+        );
+        assert_eq!(file.cyclomatic, 8);
+        assert!(file.functions[0].maintainability > file.functions[1].maintainability);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsafe_blocks_are_counted_next_to_the_lines() -> Result<()> {
+        // given
+        init_tracing();
+        let source = r#"
+            fn read(ptr: *const u32) -> u32 {
+                unsafe { *ptr }
+            }
+
+            fn read_twice(ptr: *const u32) -> u32 {
+                // SAFETY: the pointer is valid.
+                unsafe { *ptr + unsafe { *ptr } }
+            }
+        "#;
+
+        // when
+        let (_, stats) = analyze_file("src/lib.rs".into(), source);
+
+        // then
+        assert_eq!(
+            stats,
+            ProjectStats {
+                files: 1,
+                sloc: 6,
+                comment_lines: 1,
+                blank_lines: 3,
+                unsafe_blocks: 3,
+                ..ProjectStats::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn halstead_counts_ignore_attributes() -> Result<()> {
+        // given
+        init_tracing();
+        let tokens: TokenStream = "#[inline] fn add(a: u32) -> u32 { a + 1 }"
+            .parse()
+            .expect("tokens not parsed");
+
+        // when
+        let halstead = halstead(tokens);
+
+        // then
+        assert_eq!(
+            halstead,
+            Halstead {
+                // fn, (), :, ->, {}, +
+                distinct_operators: 6,
+                // add, a, u32, 1
+                distinct_operands: 4,
+                total_operators: 6,
+                total_operands: 6,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rust_files_outside_ignored_paths_are_analyzed() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let root = tmpdir.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("target"))?;
+        fs::write(root.join("src/main.rs"), "// TODO\nfn main() {}")?;
+        fs::write(root.join("src/broken.rs"), "fn broken( {")?;
+        fs::write(root.join("src/notes.txt"), "fn notes() {}")?;
+        fs::write(root.join("target/build.rs"), "fn build() {}")?;
+        let cfg = ConfigBuilder::default()
+            .ignored_paths(vec![IgnoredPath::new("target")?])
+            .build()?;
+        let analyzer = SynQualityAnalyzer::make(cfg);
+
+        // when
+        let CodeAnalysis { quality, stats } = analyzer.analyze(RepoRoot::new(root))?;
+
+        // then
+        let paths: Vec<_> = quality.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs"]);
+        assert_eq!(quality.files[0].functions[0].name, "main");
+        assert_eq!(
+            stats,
+            ProjectStats {
+                files: 2,
+                sloc: 2,
+                comment_lines: 1,
+                todos: 1,
+                ..ProjectStats::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn symlinks_are_not_followed() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let root = tmpdir.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/main.rs"), "fn main() {}")?;
+        std::os::unix::fs::symlink(root, root.join("src/loop"))?;
+        std::os::unix::fs::symlink(root.join("src/main.rs"), root.join("src/link.rs"))?;
+        let analyzer = SynQualityAnalyzer::make(Config::default());
+
+        // when
+        let report = analyzer.analyze(RepoRoot::new(root))?.quality;
+
+        // then
+        let paths: Vec<_> = report.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs"]);
+
+        Ok(())
+    }
+}
@@ -1,18 +1,37 @@
 use crate::entities::check::CheckState;
-use crate::entities::coverage::CoverageState;
+use crate::entities::coverage::{CoverageState, Severity};
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::custom_stage::CustomStageState;
+use crate::entities::diff_coverage::{DiffCoverage, FileDiffCoverage};
+use crate::entities::file_coverage::{FileCoverage, LineRange};
+use crate::entities::fmt::FmtState;
+use crate::entities::pipeline_run::PipelineRun;
+use crate::entities::provenance::{StageProvenance, TrackedResult};
+use crate::entities::quality::{
+    FileQuality, FunctionQuality, Halstead, HalsteadSummary, QualityReport,
+};
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::{DurationStats, StageTiming};
+use crate::entities::state_snapshot::{StateSnapshot, Verdict};
+use crate::entities::stats::ProjectStats;
 use crate::entities::tests::TestsState;
+use crate::entities::timestamp::Timestamp;
+use crate::entities::watcher::WatcherState;
 use crate::result::ServerErr;
+use crate::use_cases::history::{History, HistoryQuery};
 use crate::use_cases::state::State;
 use crate::use_cases::state::{StateReader, StateWriter};
 
 use actix_service::ServiceFactory;
 use actix_web::body::MessageBody;
 use actix_web::dev::{ServiceRequest, ServiceResponse};
-use actix_web::web::{Data, Json};
-use actix_web::{get, middleware, put, App, HttpResponse, HttpServer};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{get, middleware, post, put, App, HttpResponse, HttpServer};
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::path::PathBuf;
 use tracing::{debug, error, instrument, trace};
@@ -21,12 +40,16 @@ use tracing_actix_web::TracingLogger;
 type Result<T> = std::result::Result<T, ServerErr>;
 type StateWriterData = Data<StateWriter>;
 type StateReaderData = Data<StateReader>;
+type HistoryData = Data<History>;
 
-#[instrument(skip(state))]
-pub async fn start_server(state: State) -> std::io::Result<()> {
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+const WORST_OFFENDERS_LIMIT: usize = 10;
+
+#[instrument(skip(state, history))]
+pub async fn start_server(state: State, history: History) -> std::io::Result<()> {
     let socket_path = dirs::runtime_dir().unwrap_or(PathBuf::from("/run"));
     let socket_path = socket_path.join("chester.sock");
-    HttpServer::new(move || app(&state))
+    HttpServer::new(move || app(&state, &history))
         .bind_uds(socket_path)?
         .workers(1)
         .run()
@@ -36,6 +59,7 @@ pub async fn start_server(state: State) -> std::io::Result<()> {
 // NOTE: Complex type taken from https://github.com/actix/actix-web/issues/1190
 pub fn app(
     state: &State,
+    history: &History,
 ) -> App<
     impl ServiceFactory<
         ServiceRequest,
@@ -52,10 +76,188 @@ pub fn app(
         .wrap(middleware::Logger::default())
         .app_data(Data::new(state.reader()))
         .app_data(Data::new(state.writer()))
+        .app_data(Data::new(history.clone()))
+        .service(status_endpt)
+        .service(timings_endpt)
+        .service(metrics_endpt)
         .service(tests_status_endpt)
         .service(check_status_endpt)
+        .service(fmt_status_endpt)
         .service(coverage_status_endpt)
+        .service(coverage_status_v2_endpt)
+        .service(freshness_endpt)
+        .service(custom_stage_status_endpt)
         .service(change_root)
+        .service(run_pipeline)
+        .service(run_stage)
+        .service(watcher_status_endpt)
+        .service(pause_watcher)
+        .service(resume_watcher)
+        .service(history_endpt)
+        .service(coverage_history_endpt)
+        .service(coverage_files_endpt)
+        .service(coverage_file_endpt)
+        .service(coverage_diff_endpt)
+        .service(quality_status_endpt)
+        .service(quality_files_endpt)
+        .service(halstead_endpt)
+        .service(halstead_history_endpt)
+        .service(stats_endpt)
+}
+
+/// Returns the state of the whole pipeline, read at once, so all the stages are consistent.
+#[instrument(level = "trace")]
+#[get("/status")]
+async fn status_endpt(state: StateReaderData) -> Result<Json<StatusResp>> {
+    let snapshot = state
+        .snapshot()
+        .map_err(|e| server_err("Error while reading state snapshot.", e))?;
+    trace!("responding with {snapshot:?}");
+    Ok(Json(StatusResp::new(&snapshot)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct StatusResp {
+    verdict: Verdict,
+    repo_root: RepoRoot,
+    watcher: WatcherState,
+    running: Option<Stage>,
+    stages: StagesResp,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct StagesResp {
+    check: StageStatusResp,
+    tests: StageStatusResp,
+    coverage: StageStatusResp,
+    #[serde(default)]
+    fmt: FmtState,
+    #[serde(default)]
+    custom: HashMap<String, CustomStageStatusResp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct StageStatusResp {
+    state: String,
+    /// Why the stage was not run, when it was skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    stale: bool,
+    started_at: Option<Timestamp>,
+    finished_at: Option<Timestamp>,
+    duration_ms: Option<u64>,
+}
+
+impl StatusResp {
+    fn new(snapshot: &StateSnapshot) -> Self {
+        let stage = |stage: Stage, state: String, reason: Option<&str>| {
+            let timing = snapshot.timings.get(&stage);
+            StageStatusResp {
+                state,
+                reason: reason.map(ToString::to_string),
+                stale: snapshot.is_stale(&TrackedResult::Stage(stage)),
+                started_at: timing.map(|timing| timing.started_at),
+                finished_at: timing.and_then(|timing| timing.finished_at),
+                duration_ms: timing.and_then(StageTiming::duration).map(as_millis),
+            }
+        };
+        Self {
+            verdict: snapshot.verdict(),
+            repo_root: snapshot.repo_root.clone(),
+            watcher: snapshot.watcher.clone(),
+            running: snapshot.running(),
+            stages: StagesResp {
+                check: stage(Stage::Check, snapshot.check.to_string(), None),
+                tests: stage(
+                    Stage::Tests,
+                    snapshot.tests.to_string(),
+                    snapshot.tests.skip_reason(),
+                ),
+                coverage: stage(
+                    Stage::Coverage,
+                    snapshot.coverage.to_string(),
+                    snapshot.coverage.skip_reason(),
+                ),
+                fmt: snapshot.fmt.clone(),
+                custom: snapshot
+                    .custom_stages
+                    .iter()
+                    .map(|(name, state)| {
+                        let stale = snapshot.is_stale(&TrackedResult::Custom(name.clone()));
+                        (
+                            name.clone(),
+                            CustomStageStatusResp::new(state.clone(), stale),
+                        )
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+fn as_millis(duration: std::time::Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Returns the timing of the last run of every stage, with the average and the 95th percentile
+/// of durations of the latest runs.
+#[instrument(level = "trace")]
+#[get("/timings")]
+async fn timings_endpt(state: StateReaderData) -> Result<Json<TimingsResp>> {
+    let snapshot = state
+        .snapshot()
+        .map_err(|e| server_err("Error while reading state snapshot.", e))?;
+    trace!("responding with {:?}", snapshot.timings);
+    Ok(Json(TimingsResp::new(&snapshot)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct TimingsResp {
+    timings: HashMap<Stage, TimingResp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct TimingResp {
+    started_at: Timestamp,
+    finished_at: Option<Timestamp>,
+    duration_ms: Option<u64>,
+    average_ms: Option<u64>,
+    p95_ms: Option<u64>,
+}
+
+impl TimingsResp {
+    fn new(snapshot: &StateSnapshot) -> Self {
+        let timings = snapshot
+            .timings
+            .iter()
+            .map(|(stage, timing)| {
+                let stats = snapshot.durations.get(stage);
+                let resp = TimingResp {
+                    started_at: timing.started_at,
+                    finished_at: timing.finished_at,
+                    duration_ms: timing.duration().map(as_millis),
+                    average_ms: stats.and_then(DurationStats::average).map(as_millis),
+                    p95_ms: stats.and_then(DurationStats::p95).map(as_millis),
+                };
+                (*stage, resp)
+            })
+            .collect();
+        Self { timings }
+    }
+}
+
+/// Returns the metrics in Prometheus text format.
+#[instrument(level = "trace")]
+#[get("/metrics")]
+async fn metrics_endpt(state: StateReaderData) -> Result<HttpResponse> {
+    let snapshot = state
+        .snapshot()
+        .map_err(|e| server_err("Error while reading state snapshot.", e))?;
+    let metrics = snapshot.metrics.to_prometheus(snapshot.coverage.value());
+    trace!("responding with {metrics}");
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics))
 }
 
 #[instrument(level = "trace")]
@@ -64,8 +266,9 @@ async fn tests_status_endpt(state: StateReaderData) -> Result<Json<TestsStatusRe
     let status = state
         .tests()
         .map_err(|e| server_err("Error while checking tests status.", e))?;
+    let stale = is_stale(&state, &TrackedResult::Stage(Stage::Tests))?;
     trace!("responding with {status}");
-    Ok(Json(TestsStatusResp::new(status)))
+    Ok(Json(TestsStatusResp::new(status, stale)))
 }
 
 #[instrument(level = "trace")]
@@ -74,8 +277,21 @@ async fn check_status_endpt(state: StateReaderData) -> Result<Json<CheckStatusRe
     let status = state
         .check()
         .map_err(|e| server_err("Error while checking tests status.", e))?;
+    let stale = is_stale(&state, &TrackedResult::Stage(Stage::Check))?;
+    trace!("responding with {status}");
+    Ok(Json(CheckStatusResp::new(status, stale)))
+}
+
+/// Returns the result of the formatting check with the files which are not formatted.
+#[instrument(level = "trace")]
+#[get("/fmt/status")]
+async fn fmt_status_endpt(state: StateReaderData) -> Result<Json<FmtStatusResp>> {
+    let status = state
+        .fmt()
+        .map_err(|e| server_err("Error while checking formatting status.", e))?;
+    let stale = is_stale(&state, &TrackedResult::Fmt)?;
     trace!("responding with {status}");
-    Ok(Json(CheckStatusResp::new(status)))
+    Ok(Json(FmtStatusResp::new(status, stale)))
 }
 
 #[instrument(level = "trace")]
@@ -84,8 +300,552 @@ async fn coverage_status_endpt(state: StateReaderData) -> Result<Json<CoverageSt
     let status = state
         .coverage()
         .map_err(|e| server_err("Error while checking coverage status.", e))?;
+    let stale = is_stale(&state, &TrackedResult::Stage(Stage::Coverage))?;
     trace!("responding with {status}");
-    Ok(Json(CoverageStatusResp::new(status)))
+    Ok(Json(CoverageStatusResp::new(status, stale)))
+}
+
+/// Returns the coverage state with all the metrics reported by the coverage tool.
+///
+/// `/coverage/status` keeps returning the coverage as a plain string for existing clients.
+#[instrument(level = "trace")]
+#[get("/v2/coverage/status")]
+async fn coverage_status_v2_endpt(state: StateReaderData) -> Result<Json<CoverageStatusV2Resp>> {
+    let status = state
+        .coverage()
+        .map_err(|e| server_err("Error while checking coverage status.", e))?;
+    let summary = state
+        .coverage_summary()
+        .map_err(|e| server_err("Error while reading coverage summary.", e))?;
+    let stale = is_stale(&state, &TrackedResult::Stage(Stage::Coverage))?;
+    trace!("responding with {status} and {summary:?}");
+    Ok(Json(CoverageStatusV2Resp::new(&status, summary, stale)))
+}
+
+/// Returns the source tree on which the result of every finished stage was produced and whether
+/// the result is outdated.
+#[instrument(level = "trace")]
+#[get("/freshness")]
+async fn freshness_endpt(state: StateReaderData) -> Result<Json<FreshnessResp>> {
+    let snapshot = state
+        .snapshot()
+        .map_err(|e| server_err("Error while reading state snapshot.", e))?;
+    trace!("responding with {:?}", snapshot.provenances);
+    Ok(Json(FreshnessResp::new(&snapshot)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct FreshnessResp {
+    freshness: HashMap<String, Freshness>,
+}
+
+/// Source tree on which the result of the stage was produced and whether it's outdated.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct Freshness {
+    stale: bool,
+    provenance: StageProvenance,
+}
+
+impl FreshnessResp {
+    fn new(snapshot: &StateSnapshot) -> Self {
+        let freshness = snapshot
+            .provenances
+            .iter()
+            .map(|(result, provenance)| {
+                let freshness = Freshness {
+                    stale: snapshot.is_stale(result),
+                    provenance: provenance.clone(),
+                };
+                (result.to_string(), freshness)
+            })
+            .collect();
+        Self { freshness }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct CoverageStatusV2Resp {
+    coverage_status: CoverageStatusV2,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct CoverageStatusV2 {
+    state: String,
+    summary: Option<CoverageSummary>,
+    stale: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    regression: Option<RegressionResp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    threshold: Option<ThresholdResp>,
+    /// Why the coverage was not run, when it was skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct RegressionResp {
+    baseline: f32,
+    severity: Severity,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct ThresholdResp {
+    required: f32,
+    /// File whose coverage is below its minimum, none when the whole project coverage is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+}
+
+impl CoverageStatusV2Resp {
+    fn new(status: &CoverageState, summary: Option<CoverageSummary>, stale: bool) -> Self {
+        let state = match status {
+            CoverageState::Pending => "pending",
+            CoverageState::Failure => "failure",
+            CoverageState::Success(_) => "success",
+            CoverageState::Regressed { .. } => "regressed",
+            CoverageState::BelowThreshold { .. } => "below_threshold",
+            CoverageState::Skipped { .. } => "skipped",
+        };
+        let regression = match status {
+            CoverageState::Regressed {
+                baseline, severity, ..
+            } => Some(RegressionResp {
+                baseline: *baseline,
+                severity: *severity,
+            }),
+            _ => None,
+        };
+        let threshold = match status {
+            CoverageState::BelowThreshold { required, file, .. } => Some(ThresholdResp {
+                required: *required,
+                file: file.clone(),
+            }),
+            _ => None,
+        };
+        // NOTE: coverage restored from the history has no summary, only the lines coverage
+        let summary = status
+            .value()
+            .map(|lines| summary.unwrap_or(CoverageSummary::new(lines)));
+        Self {
+            coverage_status: CoverageStatusV2 {
+                state: state.to_string(),
+                summary,
+                stale,
+                regression,
+                threshold,
+                reason: status.skip_reason().map(ToString::to_string),
+            },
+        }
+    }
+}
+
+#[instrument(level = "trace")]
+#[get("/coverage/files")]
+async fn coverage_files_endpt(state: StateReaderData) -> Result<Json<CoverageFilesResp>> {
+    let files = state
+        .coverage_files()
+        .map_err(|e| server_err("Error while reading coverage of files.", e))?;
+    trace!("responding with {} files", files.len());
+    let files = files.iter().map(FileCoverageResp::new).collect();
+    Ok(Json(CoverageFilesResp::new(files)))
+}
+
+/// Returns the state of the stage defined by the user.
+#[instrument(level = "trace")]
+#[get("/stages/{name}/status")]
+async fn custom_stage_status_endpt(
+    state: StateReaderData,
+    name: Path<String>,
+) -> Result<Json<CustomStageStatusResp>> {
+    let name = name.into_inner();
+    let stages = state
+        .custom_stages()
+        .map_err(|e| server_err("Error while reading custom stages.", e))?;
+    let Some(stage) = stages.get(&name) else {
+        debug!("no custom stage {name}");
+        return Err(ServerErr::NotFound(name));
+    };
+    let stale = is_stale(&state, &TrackedResult::Custom(name))?;
+    Ok(Json(CustomStageStatusResp::new(stage.clone(), stale)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct CustomStageStatusResp {
+    #[serde(flatten)]
+    state: CustomStageState,
+    stale: bool,
+}
+
+impl CustomStageStatusResp {
+    fn new(state: CustomStageState, stale: bool) -> Self {
+        Self { state, stale }
+    }
+}
+
+/// Whether the result was produced on other source tree than the current one.
+fn is_stale(state: &StateReaderData, result: &TrackedResult) -> Result<bool> {
+    let snapshot = state
+        .snapshot()
+        .map_err(|e| server_err("Error while reading state snapshot.", e))?;
+    Ok(snapshot.is_stale(result))
+}
+
+#[instrument(level = "trace")]
+#[get("/coverage/files/{path:.*}")]
+async fn coverage_file_endpt(
+    state: StateReaderData,
+    path: Path<String>,
+) -> Result<Json<FileCoverageResp>> {
+    let path = path.into_inner();
+    let files = state
+        .coverage_files()
+        .map_err(|e| server_err("Error while reading coverage of files.", e))?;
+    let Some(file) = files.iter().find(|file| file.path == path) else {
+        debug!("no coverage for {path}");
+        return Err(ServerErr::NotFound(path));
+    };
+    Ok(Json(FileCoverageResp::new(file)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct FileCoverageResp {
+    path: String,
+    covered: u64,
+    total: u64,
+    uncovered: Vec<LineRange>,
+}
+
+impl FileCoverageResp {
+    fn new(file: &FileCoverage) -> Self {
+        Self {
+            path: file.path.clone(),
+            covered: file.covered(),
+            total: file.total(),
+            uncovered: file.uncovered(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CoverageFilesResp {
+    files: Vec<FileCoverageResp>,
+}
+
+impl CoverageFilesResp {
+    fn new(files: Vec<FileCoverageResp>) -> Self {
+        Self { files }
+    }
+}
+
+/// Returns coverage of lines changed compared to the base commit with uncovered changed lines.
+///
+/// It's calculated after every successful coverage, so it's not available before that.
+#[instrument(level = "trace")]
+#[get("/coverage/diff")]
+async fn coverage_diff_endpt(state: StateReaderData) -> Result<Json<DiffCoverageResp>> {
+    let diff = state
+        .diff_coverage()
+        .map_err(|e| server_err("Error while reading diff coverage.", e))?;
+    let Some(diff) = diff else {
+        debug!("diff coverage not calculated yet");
+        return Err(ServerErr::NotFound("diff coverage".to_string()));
+    };
+    trace!("responding with {} changed files", diff.files.len());
+    Ok(Json(DiffCoverageResp::new(diff)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct DiffCoverageResp {
+    base: String,
+    covered: u64,
+    total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percent: Option<f32>,
+    files: Vec<FileDiffCoverage>,
+}
+
+impl DiffCoverageResp {
+    fn new(diff: DiffCoverage) -> Self {
+        Self {
+            percent: diff.percent(),
+            base: diff.base,
+            covered: diff.covered,
+            total: diff.total,
+            files: diff.files,
+        }
+    }
+}
+
+/// Returns the aggregated quality metrics with the most complex functions and the least
+/// maintainable files.
+///
+/// It's calculated after every change, so it's not available before the first one.
+#[instrument(level = "trace")]
+#[get("/quality/status")]
+async fn quality_status_endpt(state: StateReaderData) -> Result<Json<QualityStatusResp>> {
+    let report = quality_report(&state)?;
+    let stale = is_stale(&state, &TrackedResult::Quality)?;
+    trace!("responding with quality of {} files", report.files.len());
+    Ok(Json(QualityStatusResp::new(&report, stale)))
+}
+
+/// Returns the quality metrics of every file and every function in it.
+#[instrument(level = "trace")]
+#[get("/quality/files")]
+async fn quality_files_endpt(state: StateReaderData) -> Result<Json<QualityFilesResp>> {
+    let report = quality_report(&state)?;
+    trace!("responding with quality of {} files", report.files.len());
+    Ok(Json(QualityFilesResp {
+        files: report.files,
+    }))
+}
+
+/// Returns Halstead metrics (volume, difficulty and effort) of every module and every function in
+/// it, with the metrics of the whole repo.
+#[instrument(level = "trace")]
+#[get("/quality/halstead")]
+async fn halstead_endpt(state: StateReaderData) -> Result<Json<HalsteadResp>> {
+    let report = quality_report(&state)?;
+    trace!(
+        "responding with halstead metrics of {} files",
+        report.files.len()
+    );
+    Ok(Json(HalsteadResp::new(&report)))
+}
+
+/// Returns Halstead metrics of the code in the past runs, from the newest one. By default, runs of
+/// the current repository are returned.
+#[instrument(skip(history, state))]
+#[get("/quality/halstead/history")]
+async fn halstead_history_endpt(
+    history: HistoryData,
+    state: StateReaderData,
+    req: Query<HistoryReq>,
+) -> Result<Json<HalsteadHistoryResp>> {
+    let req = req.into_inner();
+    let repo_root = match req.repo {
+        Some(repo_root) => repo_root,
+        None => state
+            .repo_root()
+            .map_err(|e| server_err("Error while reading repo root.", e))?,
+    };
+    let query = HistoryQuery {
+        repo_root: Some(repo_root),
+        ..HistoryQuery::all()
+    };
+    let runs = history
+        .runs(&query)
+        .map_err(|e| server_err("Error while reading history.", e))?;
+    let entries: Vec<HalsteadHistoryEntry> = runs
+        .into_iter()
+        .filter_map(HalsteadHistoryEntry::from_run)
+        .skip(req.offset.unwrap_or(0))
+        .take(req.limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .collect();
+    trace!("responding with {} halstead entries", entries.len());
+    Ok(Json(HalsteadHistoryResp { halstead: entries }))
+}
+
+fn quality_report(state: &StateReaderData) -> Result<QualityReport> {
+    let report = state
+        .quality()
+        .map_err(|e| server_err("Error while reading quality of the code.", e))?;
+    report.ok_or_else(|| {
+        debug!("quality not calculated yet");
+        ServerErr::NotFound("quality".to_string())
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct QualityStatusResp {
+    files: usize,
+    functions: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avg_cyclomatic: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_cyclomatic: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avg_maintainability: Option<f32>,
+    most_complex_functions: Vec<FunctionQualityResp>,
+    least_maintainable_files: Vec<FileQualityResp>,
+    stale: bool,
+}
+
+impl QualityStatusResp {
+    fn new(report: &QualityReport, stale: bool) -> Self {
+        Self {
+            files: report.files.len(),
+            functions: report.functions().count(),
+            avg_cyclomatic: report.avg_cyclomatic(),
+            max_cyclomatic: report.max_cyclomatic(),
+            avg_maintainability: report.avg_maintainability(),
+            most_complex_functions: report
+                .most_complex_functions(WORST_OFFENDERS_LIMIT)
+                .into_iter()
+                .map(|(file, function)| FunctionQualityResp::new(file, function))
+                .collect(),
+            least_maintainable_files: report
+                .least_maintainable_files(WORST_OFFENDERS_LIMIT)
+                .into_iter()
+                .map(FileQualityResp::new)
+                .collect(),
+            stale,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct FunctionQualityResp {
+    path: String,
+    name: String,
+    line: u32,
+    cyclomatic: u32,
+    maintainability: f32,
+}
+
+impl FunctionQualityResp {
+    fn new(file: &FileQuality, function: &FunctionQuality) -> Self {
+        Self {
+            path: file.path.clone(),
+            name: function.name.clone(),
+            line: function.line,
+            cyclomatic: function.cyclomatic,
+            maintainability: function.maintainability,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct FileQualityResp {
+    path: String,
+    loc: u32,
+    cyclomatic: u32,
+    maintainability: f32,
+}
+
+impl FileQualityResp {
+    fn new(file: &FileQuality) -> Self {
+        Self {
+            path: file.path.clone(),
+            loc: file.loc,
+            cyclomatic: file.cyclomatic,
+            maintainability: file.maintainability,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct QualityFilesResp {
+    files: Vec<FileQuality>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct HalsteadResp {
+    summary: HalsteadSummary,
+    modules: Vec<ModuleHalsteadResp>,
+}
+
+impl HalsteadResp {
+    fn new(report: &QualityReport) -> Self {
+        Self {
+            summary: report.halstead(),
+            modules: report.files.iter().map(ModuleHalsteadResp::new).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct ModuleHalsteadResp {
+    module: String,
+    path: String,
+    #[serde(flatten)]
+    metrics: HalsteadMetricsResp,
+    functions: Vec<FunctionHalsteadResp>,
+}
+
+impl ModuleHalsteadResp {
+    fn new(file: &FileQuality) -> Self {
+        Self {
+            module: file.module(),
+            path: file.path.clone(),
+            metrics: HalsteadMetricsResp::new(file.halstead),
+            functions: file
+                .functions
+                .iter()
+                .map(|function| FunctionHalsteadResp {
+                    name: function.name.clone(),
+                    line: function.line,
+                    metrics: HalsteadMetricsResp::new(function.halstead),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct FunctionHalsteadResp {
+    name: String,
+    line: u32,
+    #[serde(flatten)]
+    metrics: HalsteadMetricsResp,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct HalsteadMetricsResp {
+    volume: f32,
+    difficulty: f32,
+    effort: f32,
+}
+
+impl HalsteadMetricsResp {
+    fn new(halstead: Halstead) -> Self {
+        Self {
+            volume: halstead.volume(),
+            difficulty: halstead.difficulty(),
+            effort: halstead.effort(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct HalsteadHistoryEntry {
+    run_id: RunId,
+    timestamp: Timestamp,
+    commit: Option<String>,
+    #[serde(flatten)]
+    halstead: HalsteadSummary,
+}
+
+impl HalsteadHistoryEntry {
+    fn from_run(run: PipelineRun) -> Option<Self> {
+        Some(Self {
+            halstead: run.halstead?,
+            run_id: run.id,
+            timestamp: run.finished_at.unwrap_or(run.started_at),
+            commit: run.commit,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HalsteadHistoryResp {
+    halstead: Vec<HalsteadHistoryEntry>,
+}
+
+/// Returns the statistics of the code: number of files, lines of code, comment and blank lines,
+/// tests, unsafe blocks and TODOs.
+#[instrument(level = "trace")]
+#[get("/stats")]
+async fn stats_endpt(state: StateReaderData) -> Result<Json<ProjectStats>> {
+    let stats = state
+        .stats()
+        .map_err(|e| server_err("Error while reading stats of the code.", e))?;
+    let stats = stats.ok_or_else(|| {
+        debug!("stats not collected yet");
+        ServerErr::NotFound("stats".to_string())
+    })?;
+    trace!("responding with stats of {} files", stats.files);
+    Ok(Json(stats))
 }
 
 fn server_err<S: Into<String>, E: Display>(msg: S, e: E) -> ServerErr {
@@ -97,32 +857,67 @@ fn server_err<S: Into<String>, E: Display>(msg: S, e: E) -> ServerErr {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 struct TestsStatusResp {
     tests_status: TestsState,
+    stale: bool,
 }
 
 impl TestsStatusResp {
-    fn new(tests_status: TestsState) -> Self {
-        Self { tests_status }
+    fn new(tests_status: TestsState, stale: bool) -> Self {
+        Self {
+            tests_status,
+            stale,
+        }
     }
 }
 
 #[derive(Debug, Serialize)]
 struct CheckStatusResp {
     check_status: CheckState,
+    stale: bool,
 }
 
 impl CheckStatusResp {
-    fn new(check_status: CheckState) -> Self {
-        Self { check_status }
+    fn new(check_status: CheckState, stale: bool) -> Self {
+        Self {
+            check_status,
+            stale,
+        }
     }
 }
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct FmtStatusResp {
+    fmt_status: String,
+    files: Vec<String>,
+    stale: bool,
+}
+
+impl FmtStatusResp {
+    fn new(fmt_status: FmtState, stale: bool) -> Self {
+        let status = fmt_status.to_string();
+        let files = match fmt_status {
+            FmtState::Failure { files, .. } => files,
+            _ => Vec::new(),
+        };
+        Self {
+            fmt_status: status,
+            files,
+            stale,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 struct CoverageStatusResp {
-    coverage_status: CoverageState,
+    coverage_status: String,
+    stale: bool,
 }
 
 impl CoverageStatusResp {
-    fn new(coverage_status: CoverageState) -> Self {
-        Self { coverage_status }
+    fn new(coverage_status: CoverageState, stale: bool) -> Self {
+        let coverage_status = coverage_status.to_string();
+        Self {
+            coverage_status,
+            stale,
+        }
     }
 }
 
@@ -141,34 +936,1191 @@ struct ChangeRootReq {
     repo_root: RepoRoot,
 }
 
+#[instrument(level = "trace")]
+#[get("/watcher/status")]
+async fn watcher_status_endpt(state: StateReaderData) -> Result<Json<WatcherStatusResp>> {
+    let status = state
+        .watcher()
+        .map_err(|e| server_err("Error while checking watcher status.", e))?;
+    trace!("responding with {status}");
+    Ok(Json(WatcherStatusResp::new(status)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WatcherStatusResp {
+    watcher_status: WatcherState,
+}
+
+impl WatcherStatusResp {
+    fn new(watcher_status: WatcherState) -> Self {
+        Self { watcher_status }
+    }
+}
+
+#[instrument(skip(state))]
+#[post("/watcher/pause")]
+async fn pause_watcher(state: StateWriterData) -> Result<HttpResponse> {
+    debug!("pausing watcher");
+    state
+        .watcher(WatcherState::Paused)
+        .map_err(|e| server_err("Error while pausing watcher.", e))?;
+    Ok(HttpResponse::NoContent().into()) // 204
+}
+
+#[instrument(skip(state))]
+#[post("/watcher/resume")]
+async fn resume_watcher(state: StateWriterData) -> Result<HttpResponse> {
+    debug!("resuming watcher");
+    state
+        .watcher(WatcherState::Watching)
+        .map_err(|e| server_err("Error while resuming watcher.", e))?;
+    Ok(HttpResponse::NoContent().into()) // 204
+}
+
+#[instrument(skip(history))]
+#[get("/history")]
+async fn history_endpt(history: HistoryData, req: Query<HistoryReq>) -> Result<Json<HistoryResp>> {
+    let req = req.into_inner();
+    let query = HistoryQuery {
+        repo_root: req.repo,
+        offset: req.offset.unwrap_or(0),
+        limit: req.limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+    };
+    let runs = history
+        .runs(&query)
+        .map_err(|e| server_err("Error while reading history.", e))?;
+    trace!("responding with {} runs", runs.len());
+    Ok(Json(HistoryResp::new(runs)))
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryReq {
+    repo: Option<RepoRoot>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HistoryResp {
+    runs: Vec<PipelineRun>,
+}
+
+impl HistoryResp {
+    fn new(runs: Vec<PipelineRun>) -> Self {
+        Self { runs }
+    }
+}
+
+#[instrument(skip(history, state))]
+#[get("/coverage/history")]
+async fn coverage_history_endpt(
+    history: HistoryData,
+    state: StateReaderData,
+    req: Query<HistoryReq>,
+) -> Result<Json<CoverageHistoryResp>> {
+    let req = req.into_inner();
+    let repo_root = match req.repo {
+        Some(repo_root) => repo_root,
+        None => state
+            .repo_root()
+            .map_err(|e| server_err("Error while reading repo root.", e))?,
+    };
+    let query = HistoryQuery {
+        repo_root: Some(repo_root),
+        ..HistoryQuery::all()
+    };
+    let runs = history
+        .runs(&query)
+        .map_err(|e| server_err("Error while reading history.", e))?;
+    let entries: Vec<CoverageHistoryEntry> = runs
+        .into_iter()
+        .filter_map(CoverageHistoryEntry::from_run)
+        .skip(req.offset.unwrap_or(0))
+        .take(req.limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .collect();
+    trace!("responding with {} coverage entries", entries.len());
+    Ok(Json(CoverageHistoryResp::new(entries)))
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct CoverageHistoryEntry {
+    run_id: RunId,
+    timestamp: Timestamp,
+    commit: Option<String>,
+    coverage: f32,
+}
+
+impl CoverageHistoryEntry {
+    fn from_run(run: PipelineRun) -> Option<Self> {
+        let coverage = run.coverage.as_ref()?.value()?;
+        Some(Self {
+            run_id: run.id,
+            timestamp: run.finished_at.unwrap_or(run.started_at),
+            commit: run.commit,
+            coverage,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CoverageHistoryResp {
+    coverage: Vec<CoverageHistoryEntry>,
+}
+
+impl CoverageHistoryResp {
+    fn new(coverage: Vec<CoverageHistoryEntry>) -> Self {
+        Self { coverage }
+    }
+}
+
+#[instrument(skip(state))]
+#[post("/pipeline/run")]
+async fn run_pipeline(state: StateWriterData) -> Result<HttpResponse> {
+    trigger(&state, Stage::Check)
+}
+
+#[instrument(skip(state))]
+#[post("/{stage}/run")]
+async fn run_stage(state: StateWriterData, stage: Path<Stage>) -> Result<HttpResponse> {
+    let stage = stage.into_inner();
+    if !stage.is_triggerable() {
+        debug!("run can't be started from {stage} stage");
+        return Err(ServerErr::NotFound(format!("{stage}/run")));
+    }
+    trigger(&state, stage)
+}
+
+fn trigger(state: &StateWriterData, stage: Stage) -> Result<HttpResponse> {
+    debug!("starting run from {stage} stage");
+    let run_id = state
+        .trigger(stage)
+        .map_err(|e| server_err("Error while starting the run.", e))?;
+    Ok(HttpResponse::Accepted().json(RunResp::new(run_id))) // 202
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RunResp {
+    run_id: RunId,
+}
+
+impl RunResp {
+    fn new(run_id: RunId) -> Self {
+        Self { run_id }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use crate::testingtools::state;
+    use crate::entities::metrics::Metrics;
+    use crate::entities::pipeline_run::Trigger;
+    use crate::entities::provenance::{Fingerprint, TreeSnapshot};
+    use crate::entities::watcher::WatchedEvents;
+    use crate::testingtools::quality_analyzer::halstead;
+    use crate::testingtools::state::StateValues;
+    use crate::testingtools::{history, state};
 
     use actix_web::body::to_bytes;
     use actix_web::test::{call_service, init_service, TestRequest};
     use anyhow::Result;
     use serde::de::DeserializeOwned;
+    use std::collections::BTreeMap;
+    use std::time::Duration;
 
     #[actix_web::test]
-    async fn calling_tests_status_endpoint_returns_response_with_status() -> Result<()> {
+    async fn calling_status_endpoint_returns_state_of_all_stages() -> Result<()> {
         // given
-        let svc = init_service(app(&state::working())).await;
-        let req = TestRequest::default().uri("/tests/status").to_request();
+        let mut check = StageTiming::start();
+        check.finish(true);
+        let state = state::working_with(StateValues {
+            tests_state: TestsState::Pending,
+            timings: HashMap::from([(Stage::Check, check), (Stage::Tests, StageTiming::start())]),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/status").to_request();
 
         // when
         let resp = call_service(&svc, req).await;
 
         // then
         assert!(resp.status().is_success());
-        let resp: TestsStatusResp = to_resp(resp).await;
-        assert_eq!(resp.tests_status, TestsState::Success);
+        let resp: StatusResp = to_resp(resp).await;
+        assert_eq!(resp.verdict, Verdict::Yellow);
+        assert_eq!(resp.running, Some(Stage::Tests));
+        assert_eq!(resp.stages.check.state, "success");
+        assert_eq!(resp.stages.check.finished_at, check.finished_at);
+        assert!(resp.stages.check.duration_ms.is_some());
+        assert_eq!(resp.stages.tests.state, "pending");
+        assert_eq!(resp.stages.tests.finished_at, None);
+        assert_eq!(resp.stages.coverage.state, "20");
+        assert_eq!(resp.stages.coverage.started_at, None);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_status_endpoint_returns_skipped_stages_with_reason() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            check_state: CheckState::Failure,
+            tests_state: TestsState::Skipped {
+                reason: "check failed".into(),
+            },
+            cov_state: CoverageState::Skipped {
+                reason: "check failed".into(),
+            },
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: StatusResp = to_resp(resp).await;
+        assert_eq!(resp.stages.check.reason, None);
+        assert_eq!(resp.stages.tests.state, "skipped");
+        assert_eq!(resp.stages.tests.reason.as_deref(), Some("check failed"));
+        assert_eq!(resp.stages.coverage.state, "skipped");
+        assert_eq!(resp.stages.coverage.reason.as_deref(), Some("check failed"));
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_status_endpoint_when_stage_failed_returns_red_verdict() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            check_state: CheckState::Failure,
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: StatusResp = to_resp(resp).await;
+        assert_eq!(resp.verdict, Verdict::Red);
+        assert_eq!(resp.running, None);
 
         Ok(())
     }
 
+    #[actix_web::test]
+    async fn calling_timings_endpoint_returns_duration_statistics_of_stages() -> Result<()> {
+        // given
+        let mut index = StageTiming::start();
+        index.finish(true);
+        let mut stats = DurationStats::default();
+        stats.record(Duration::from_secs(2));
+        stats.record(Duration::from_secs(4));
+        let state = state::working_with(StateValues {
+            timings: HashMap::from([(Stage::Index, index)]),
+            durations: HashMap::from([(Stage::Index, stats)]),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/timings").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: TimingsResp = to_resp(resp).await;
+        let timing = &resp.timings[&Stage::Index];
+        assert_eq!(timing.finished_at, index.finished_at);
+        assert_eq!(timing.average_ms, Some(3000));
+        assert_eq!(timing.p95_ms, Some(4000));
+        assert!(!resp.timings.contains_key(&Stage::Coverage));
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_metrics_endpoint_returns_metrics_in_prometheus_format() -> Result<()> {
+        // given
+        let mut metrics = Metrics::default();
+        metrics.watched(WatchedEvents {
+            received: 3,
+            ignored: 1,
+        });
+        let state = state::working_with(StateValues {
+            metrics,
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/metrics").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let Ok(body) = to_bytes(resp.into_body()).await else {
+            panic!("failed to convert to bytes");
+        };
+        let body = String::from_utf8(body.to_vec())?;
+        assert!(body.contains("chester_coverage_percent 20\n"));
+        assert!(body.contains("chester_watcher_events_total 3\n"));
+        assert!(body.contains("chester_watcher_ignored_events_total 1\n"));
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_tests_status_endpoint_returns_response_with_status() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/tests/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: TestsStatusResp = to_resp(resp).await;
+        assert_eq!(resp.tests_status, TestsState::Success);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_pipeline_run_endpoint_returns_accepted_with_run_id() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::post().uri("/pipeline/run").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 202);
+        let resp: RunResp = to_resp(resp).await;
+        assert!(!resp.run_id.to_string().is_empty());
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_stage_run_endpoint_returns_accepted() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::post().uri("/coverage/run").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 202);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_run_endpoint_with_index_stage_returns_not_found() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::post().uri("/index/run").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 404);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_run_endpoint_with_unknown_stage_returns_not_found() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::post().uri("/unknown/run").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 404);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_watcher_status_endpoint_returns_response_with_status() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/watcher/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: WatcherStatusResp = to_resp(resp).await;
+        assert_eq!(resp.watcher_status, WatcherState::Watching);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_watcher_pause_endpoint_returns_no_content() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::post().uri("/watcher/pause").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 204);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_history_endpoint_returns_runs_of_requested_repo() -> Result<()> {
+        // given
+        let run = PipelineRun::new(RunId::new(), Trigger::Change, RepoRoot::new("/repo"), None);
+        let other_run =
+            PipelineRun::new(RunId::new(), Trigger::Change, RepoRoot::new("/other"), None);
+        let history = history::working(vec![run.clone(), other_run]);
+        let svc = init_service(app(&state::working(), &history)).await;
+        let req = TestRequest::default()
+            .uri("/history?repo=/repo&offset=0&limit=10")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: HistoryResp = to_resp(resp).await;
+        assert_eq!(resp.runs, vec![run]);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_status_endpoint_returns_coverage_as_string() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/coverage/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CoverageStatusResp = to_resp(resp).await;
+        assert_eq!(resp.coverage_status, "20");
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_status_v2_endpoint_returns_coverage_summary() -> Result<()> {
+        // given
+        let summary = CoverageSummary {
+            branches: Some(40.0),
+            ..CoverageSummary::new(20.0)
+        };
+        let state = state::working_with(StateValues {
+            coverage_summary: Some(summary),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default()
+            .uri("/v2/coverage/status")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CoverageStatusV2Resp = to_resp(resp).await;
+        assert_eq!(
+            resp.coverage_status,
+            CoverageStatusV2 {
+                state: "success".into(),
+                summary: Some(summary),
+                stale: false,
+                regression: None,
+                threshold: None,
+                reason: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn status_endpoints_of_results_produced_on_other_tree_return_stale_flag() -> Result<()> {
+        // given
+        let old = TreeSnapshot::new(Fingerprint::new(Some("abc".into()), None));
+        let current = TreeSnapshot::new(Fingerprint::new(Some("def".into()), None));
+        let provenances = [
+            TrackedResult::Stage(Stage::Check),
+            TrackedResult::Stage(Stage::Tests),
+            TrackedResult::Stage(Stage::Coverage),
+            TrackedResult::Fmt,
+            TrackedResult::Quality,
+            TrackedResult::Custom("doc".into()),
+        ]
+        .into_iter()
+        .map(|result| (result, StageProvenance::new(old.clone())))
+        .collect();
+        let state = state::working_with(StateValues {
+            quality: Some(quality_report()),
+            custom_stages: HashMap::from([("doc".to_string(), CustomStageState::Pending)]),
+            source_tree: Some(current),
+            provenances,
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let uris = [
+            "/check/status",
+            "/tests/status",
+            "/fmt/status",
+            "/coverage/status",
+            "/v2/coverage/status",
+            "/quality/status",
+            "/stages/doc/status",
+        ];
+
+        for uri in uris {
+            // when
+            let req = TestRequest::default().uri(uri).to_request();
+            let resp = call_service(&svc, req).await;
+
+            // then
+            assert!(resp.status().is_success());
+            let resp: serde_json::Value = to_resp(resp).await;
+            let stale = resp
+                .get("stale")
+                .or_else(|| resp.get("coverage_status").and_then(|cov| cov.get("stale")));
+            assert_eq!(stale, Some(&serde_json::Value::Bool(true)), "{uri}");
+        }
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_freshness_endpoint_returns_provenance_of_finished_stages() -> Result<()> {
+        // given
+        let tree = TreeSnapshot::new(Fingerprint::new(Some("abc".into()), None));
+        let current = TreeSnapshot::new(Fingerprint::new(Some("abc".into()), Some("123".into())));
+        let check = StageProvenance::new(current.clone());
+        let coverage = StageProvenance::new(tree);
+        let state = state::working_with(StateValues {
+            source_tree: Some(current),
+            provenances: HashMap::from([
+                (TrackedResult::Stage(Stage::Check), check.clone()),
+                (TrackedResult::Stage(Stage::Coverage), coverage.clone()),
+                (TrackedResult::Custom("doc".into()), coverage.clone()),
+            ]),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/freshness").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: FreshnessResp = to_resp(resp).await;
+        assert_eq!(
+            resp.freshness,
+            HashMap::from([
+                (
+                    "check".to_string(),
+                    Freshness {
+                        stale: false,
+                        provenance: check,
+                    }
+                ),
+                (
+                    "coverage".to_string(),
+                    Freshness {
+                        stale: true,
+                        provenance: coverage.clone(),
+                    }
+                ),
+                (
+                    "stages/doc".to_string(),
+                    Freshness {
+                        stale: true,
+                        provenance: coverage,
+                    }
+                ),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_status_v2_endpoint_returns_regression() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            cov_state: CoverageState::Regressed {
+                actual: 10.0,
+                baseline: 20.0,
+                severity: Severity::Failure,
+            },
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default()
+            .uri("/v2/coverage/status")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CoverageStatusV2Resp = to_resp(resp).await;
+        assert_eq!(
+            resp.coverage_status,
+            CoverageStatusV2 {
+                state: "regressed".into(),
+                summary: Some(CoverageSummary::new(10.0)),
+                stale: false,
+                regression: Some(RegressionResp {
+                    baseline: 20.0,
+                    severity: Severity::Failure,
+                }),
+                threshold: None,
+                reason: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_status_v2_endpoint_returns_required_threshold() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            cov_state: CoverageState::BelowThreshold {
+                actual: 70.0,
+                required: 80.0,
+                file: Some("src/lib.rs".into()),
+            },
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default()
+            .uri("/v2/coverage/status")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CoverageStatusV2Resp = to_resp(resp).await;
+        assert_eq!(
+            resp.coverage_status,
+            CoverageStatusV2 {
+                state: "below_threshold".into(),
+                summary: Some(CoverageSummary::new(70.0)),
+                stale: false,
+                regression: None,
+                threshold: Some(ThresholdResp {
+                    required: 80.0,
+                    file: Some("src/lib.rs".into()),
+                }),
+                reason: None,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_history_endpoint_returns_only_runs_with_coverage() -> Result<()> {
+        // given
+        let mut with_coverage = run("/repo");
+        with_coverage.commit = Some("abc".into());
+        with_coverage.coverage = Some(CoverageState::Success(82.0));
+        let mut failed = run("/repo");
+        failed.coverage = Some(CoverageState::Failure);
+        let without_coverage = run("/repo");
+        let history = history::working(vec![without_coverage, failed, with_coverage.clone()]);
+        let svc = init_service(app(&state::working(), &history)).await;
+        let req = TestRequest::default()
+            .uri("/coverage/history?repo=/repo")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CoverageHistoryResp = to_resp(resp).await;
+        assert_eq!(
+            resp.coverage,
+            vec![CoverageHistoryEntry {
+                run_id: with_coverage.id,
+                timestamp: with_coverage.started_at,
+                commit: Some("abc".into()),
+                coverage: 82.0,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_files_endpoint_returns_coverage_of_every_file() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            coverage_files: vec![
+                FileCoverage::new("src/lib.rs", BTreeMap::from([(1, 1), (2, 0), (3, 0)])),
+                FileCoverage::new("src/main.rs", BTreeMap::from([(1, 1)])),
+            ],
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/coverage/files").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CoverageFilesResp = to_resp(resp).await;
+        assert_eq!(
+            resp.files,
+            vec![
+                FileCoverageResp {
+                    path: "src/lib.rs".into(),
+                    covered: 1,
+                    total: 3,
+                    uncovered: vec![LineRange::new(2, 3)],
+                },
+                FileCoverageResp {
+                    path: "src/main.rs".into(),
+                    covered: 1,
+                    total: 1,
+                    uncovered: Vec::new(),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_file_endpoint_returns_coverage_of_the_file() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            coverage_files: vec![FileCoverage::new(
+                "src/data/lib.rs",
+                BTreeMap::from([(1, 0), (2, 1)]),
+            )],
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default()
+            .uri("/coverage/files/src/data/lib.rs")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: FileCoverageResp = to_resp(resp).await;
+        assert_eq!(resp.uncovered, vec![LineRange::new(1, 1)]);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_file_endpoint_with_unknown_file_returns_not_found() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default()
+            .uri("/coverage/files/src/unknown.rs")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 404);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_fmt_status_endpoint_returns_unformatted_files() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            fmt_state: FmtState::Failure {
+                files: vec!["src/main.rs".into()],
+                blocking: true,
+            },
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/fmt/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: FmtStatusResp = to_resp(resp).await;
+        assert_eq!(
+            resp,
+            FmtStatusResp {
+                fmt_status: "failure".into(),
+                files: vec!["src/main.rs".into()],
+                stale: false,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_custom_stage_status_endpoint_returns_state_of_the_stage() -> Result<()> {
+        // given
+        let fmt = CustomStageState::Failure {
+            summary: Some("src/lib.rs".into()),
+        };
+        let state = state::working_with(StateValues {
+            custom_stages: HashMap::from([("fmt".to_string(), fmt.clone())]),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default()
+            .uri("/stages/fmt/status")
+            .to_request();
+        let unknown_req = TestRequest::default()
+            .uri("/stages/doc/status")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+        let unknown_resp = call_service(&svc, unknown_req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: CustomStageStatusResp = to_resp(resp).await;
+        assert_eq!(resp, CustomStageStatusResp::new(fmt, false));
+        assert_eq!(unknown_resp.status(), 404);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_diff_endpoint_returns_coverage_of_changed_lines() -> Result<()> {
+        // given
+        let files = vec![FileDiffCoverage {
+            path: "src/lib.rs".into(),
+            covered: 1,
+            total: 4,
+            uncovered: vec![LineRange::new(2, 4)],
+        }];
+        let state = state::working_with(StateValues {
+            diff_coverage: Some(DiffCoverage {
+                base: "HEAD".into(),
+                covered: 1,
+                total: 4,
+                files: files.clone(),
+            }),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/coverage/diff").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: DiffCoverageResp = to_resp(resp).await;
+        assert_eq!(
+            resp,
+            DiffCoverageResp {
+                base: "HEAD".into(),
+                covered: 1,
+                total: 4,
+                percent: Some(25.0),
+                files,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_coverage_diff_endpoint_before_coverage_returns_not_found() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/coverage/diff").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 404);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_quality_status_endpoint_returns_aggregates_and_worst_offenders() -> Result<()>
+    {
+        // given
+        let state = state::working_with(StateValues {
+            quality: Some(quality_report()),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/quality/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: QualityStatusResp = to_resp(resp).await;
+        assert_eq!(resp.files, 2);
+        assert_eq!(resp.functions, 3);
+        assert_eq!(resp.avg_cyclomatic, Some(4.0));
+        assert_eq!(resp.max_cyclomatic, Some(8));
+        assert_eq!(resp.avg_maintainability, Some(60.0));
+        assert_eq!(
+            resp.most_complex_functions[0],
+            FunctionQualityResp {
+                path: "src/lib.rs".into(),
+                name: "Parser::parse".into(),
+                line: 12,
+                cyclomatic: 8,
+                maintainability: 30.0,
+            }
+        );
+        assert_eq!(resp.least_maintainable_files[0].path, "src/lib.rs");
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_quality_files_endpoint_returns_quality_of_all_functions() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            quality: Some(quality_report()),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/quality/files").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: QualityFilesResp = to_resp(resp).await;
+        assert_eq!(resp.files, quality_report().files);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_quality_status_endpoint_before_analysis_returns_not_found() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/quality/status").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 404);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_halstead_endpoint_returns_metrics_of_modules_and_functions() -> Result<()> {
+        // given
+        let state = state::working_with(StateValues {
+            quality: Some(quality_report()),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/quality/halstead").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: HalsteadResp = to_resp(resp).await;
+        let metrics = || HalsteadMetricsResp {
+            volume: 48.0,
+            difficulty: 3.0,
+            effort: 144.0,
+        };
+        assert_eq!(
+            resp.summary,
+            HalsteadSummary {
+                volume: 96.0,
+                difficulty: 3.0,
+                effort: 288.0,
+            }
+        );
+        assert_eq!(
+            resp.modules[1],
+            ModuleHalsteadResp {
+                module: "crate".into(),
+                path: "src/main.rs".into(),
+                metrics: metrics(),
+                functions: vec![FunctionHalsteadResp {
+                    name: "main".into(),
+                    line: 12,
+                    metrics: metrics(),
+                }],
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_halstead_history_endpoint_returns_only_runs_with_halstead() -> Result<()> {
+        // given
+        let summary = quality_report().halstead();
+        let mut with_halstead = run("/repo");
+        with_halstead.halstead = Some(summary);
+        let without_halstead = run("/repo");
+        let history = history::working(vec![without_halstead, with_halstead.clone()]);
+        let svc = init_service(app(&state::working(), &history)).await;
+        let req = TestRequest::default()
+            .uri("/quality/halstead/history?repo=/repo")
+            .to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: HalsteadHistoryResp = to_resp(resp).await;
+        assert_eq!(
+            resp.halstead,
+            vec![HalsteadHistoryEntry {
+                run_id: with_halstead.id,
+                timestamp: with_halstead.started_at,
+                commit: None,
+                halstead: summary,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_stats_endpoint_returns_stats_of_the_code() -> Result<()> {
+        // given
+        let stats = ProjectStats {
+            files: 12,
+            sloc: 1500,
+            comment_lines: 200,
+            blank_lines: 180,
+            tests: Some(40),
+            unsafe_blocks: 1,
+            todos: 3,
+        };
+        let state = state::working_with(StateValues {
+            stats: Some(stats),
+            ..StateValues::default()
+        });
+        let svc = init_service(app(&state, &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/stats").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert!(resp.status().is_success());
+        let resp: ProjectStats = to_resp(resp).await;
+        assert_eq!(resp, stats);
+
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn calling_stats_endpoint_before_stats_are_collected_returns_not_found() -> Result<()> {
+        // given
+        let svc = init_service(app(&state::working(), &history::working(Vec::new()))).await;
+        let req = TestRequest::default().uri("/stats").to_request();
+
+        // when
+        let resp = call_service(&svc, req).await;
+
+        // then
+        assert_eq!(resp.status(), 404);
+
+        Ok(())
+    }
+
+    fn quality_report() -> QualityReport {
+        let function = |name: &str, cyclomatic: u32, maintainability: f32| FunctionQuality {
+            name: name.into(),
+            line: 12,
+            loc: 10,
+            cyclomatic,
+            maintainability,
+            halstead: halstead(),
+        };
+        QualityReport::new(vec![
+            FileQuality {
+                path: "src/main.rs".into(),
+                loc: 10,
+                cyclomatic: 1,
+                maintainability: 80.0,
+                halstead: halstead(),
+                functions: vec![function("main", 1, 80.0)],
+            },
+            FileQuality {
+                path: "src/lib.rs".into(),
+                loc: 100,
+                cyclomatic: 11,
+                maintainability: 40.0,
+                halstead: halstead(),
+                functions: vec![
+                    function("Parser::parse", 8, 30.0),
+                    function("Parser::new", 3, 60.0),
+                ],
+            },
+        ])
+    }
+
+    fn run(repo_root: &str) -> PipelineRun {
+        PipelineRun::new(
+            RunId::new(),
+            Trigger::Change,
+            RepoRoot::new(repo_root),
+            None,
+        )
+    }
+
     async fn to_resp<T: DeserializeOwned>(resp: ServiceResponse<impl MessageBody>) -> T {
         let resp = resp.into_body();
         let Ok(resp) = to_bytes(resp).await else {
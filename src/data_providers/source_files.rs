@@ -0,0 +1,43 @@
+use crate::entities::ignored_path::IgnoredPath;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::trace;
+
+/// Lists all the Rust files in the repo, except the ignored paths.
+///
+/// Symlinks are not followed, so a symlink loop can't make it recurse forever.
+pub fn rust_files(repo_root: &Path, ignored_paths: &[IgnoredPath]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    collect(repo_root, repo_root, ignored_paths, &mut files)?;
+    Ok(files)
+}
+
+fn collect(
+    repo_root: &Path,
+    dir: &Path,
+    ignored_paths: &[IgnoredPath],
+    files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = path.strip_prefix(repo_root).unwrap_or(&path);
+        if ignored_paths.iter().any(|p| p.matched_by(relative)) {
+            trace!("{path:?} ignored");
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            trace!("{path:?} is a symlink, skipping");
+            continue;
+        }
+        if file_type.is_dir() {
+            collect(repo_root, &path, ignored_paths, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use crate::data_providers::command::Cmd;
+use crate::entities::custom_stage::{CustomStage, CustomStageState};
+use crate::entities::repo_root::RepoRoot;
+use crate::result::StageErr;
+use crate::use_cases::stage_runner::{SRunner, StageRunner};
+
+use std::sync::Arc;
+use tracing::{debug, instrument};
+
+/// Runs the command of the custom stage and evaluates its result with the stage's success
+/// criterion and output parser.
+///
+/// Stage with `CustomStage::own_target_dir` set is run with `CARGO_TARGET_DIR` pointing to
+/// `target/chester/<stage name>` in the repo root. Command which can't be executed makes the
+/// stage fail.
+#[derive(Debug)]
+pub struct DefaultStageRunner;
+
+impl DefaultStageRunner {
+    pub fn make() -> StageRunner {
+        Arc::new(Self)
+    }
+}
+
+impl SRunner for DefaultStageRunner {
+    #[instrument(skip(self, stage), fields(stage = stage.name))]
+    fn run(&self, stage: &CustomStage, repo_root: RepoRoot) -> Result<CustomStageState, StageErr> {
+        let Some((tool, args)) = stage.cmd.split_first() else {
+            return Ok(CustomStageState::Failure { summary: None });
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let mut cmd: Cmd = Cmd::new(tool, &args);
+        if stage.own_target_dir {
+            let target_dir = repo_root
+                .as_ref()
+                .join("target")
+                .join("chester")
+                .join(&stage.name);
+            cmd = cmd.with_env("CARGO_TARGET_DIR", target_dir.to_string_lossy());
+        }
+        debug!("running {} in {repo_root}", stage.name);
+        let Ok((status, output)) = cmd.status_and_output(&repo_root) else {
+            debug!("command failed");
+            return Ok(CustomStageState::Failure { summary: None });
+        };
+        Ok(stage.evaluate(status.success(), &output))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::custom_stage::SuccessCriterion;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    #[test]
+    fn stage_is_evaluated_from_output_of_its_command() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let runner = DefaultStageRunner::make();
+        let stage = stage(&["sh", "-c", "echo 'warning: 2 warnings' >&2"]);
+
+        // when
+        let res = runner.run(&stage, RepoRoot::new(tmpdir.path()))?;
+
+        // then
+        assert_eq!(
+            res,
+            CustomStageState::Failure {
+                summary: Some("2".into())
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_command_can_not_be_run_stage_fails() -> Result<()> {
+        // given
+        init_tracing();
+        let runner = DefaultStageRunner::make();
+        let stage = stage(&["true"]);
+
+        // when
+        let res = runner.run(&stage, RepoRoot::new("/not/existing/path"))?;
+
+        // then
+        assert_eq!(res, CustomStageState::Failure { summary: None });
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_with_own_target_dir_gets_separate_cargo_target_dir() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let runner = DefaultStageRunner::make();
+        let stage = CustomStage {
+            success: SuccessCriterion::ExitCode,
+            parser: Some("target dir: (.*)".into()),
+            own_target_dir: true,
+            continue_on_failure: false,
+            ..stage(&["sh", "-c", "echo target dir: $CARGO_TARGET_DIR"])
+        };
+
+        // when
+        let res = runner.run(&stage, RepoRoot::new(tmpdir.path()))?;
+
+        // then
+        let target_dir = tmpdir.path().join("target").join("chester").join("clippy");
+        assert_eq!(
+            res,
+            CustomStageState::Success {
+                summary: Some(target_dir.to_string_lossy().to_string())
+            }
+        );
+
+        Ok(())
+    }
+
+    fn stage(cmd: &[&str]) -> CustomStage {
+        CustomStage {
+            name: "clippy".into(),
+            cmd: cmd.iter().map(ToString::to_string).collect(),
+            depends_on: Vec::new(),
+            success: SuccessCriterion::OutputNotContains("warning:".into()),
+            parser: Some(r"(\d+) warnings".into()),
+            own_target_dir: false,
+            continue_on_failure: false,
+        }
+    }
+}
@@ -1,15 +1,30 @@
 use crate::entities::check::CheckState;
 use crate::entities::coverage::CoverageState;
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::custom_stage::CustomStageState;
+use crate::entities::diff_coverage::DiffCoverage;
+use crate::entities::file_coverage::FileCoverage;
+use crate::entities::fmt::FmtState;
+use crate::entities::metrics::Metrics;
+use crate::entities::provenance::{StageProvenance, TrackedResult, TreeSnapshot};
+use crate::entities::quality::QualityReport;
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::{DurationStats, StageTiming};
+use crate::entities::state_snapshot::StateSnapshot;
+use crate::entities::stats::ProjectStats;
 use crate::entities::tests::TestsState;
+use crate::entities::watcher::{WatchedEvents, WatcherState};
 use crate::result::{StateReaderErr, StateWriterErr};
 use crate::use_cases::bus::{BusEvent, EventPublisher};
 use crate::use_cases::state::{
     AppState, AppStateReader, AppStateWriter, State, StateReader, StateWriter,
 };
 
-use std::sync::{Arc, RwLock};
-use tracing::instrument;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use tracing::{debug, instrument};
 
 #[derive(Debug, Default, Clone)]
 struct Status<T: Clone> {
@@ -18,21 +33,45 @@ struct Status<T: Clone> {
 
 impl<T: Clone> Status<T> {
     fn read(&self) -> T {
-        self.value.read().expect("poisoned mutex").clone()
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> RwLockReadGuard<'_, T> {
+        self.value.read().expect("poisoned mutex")
     }
 
     fn write(&self, new_val: T) {
         let mut old_val = self.value.write().expect("poisoned mutex");
         *old_val = new_val;
     }
+
+    fn update<F: FnOnce(&mut T)>(&self, update_fn: F) {
+        let mut val = self.value.write().expect("poisoned mutex");
+        update_fn(&mut val);
+    }
 }
 
 #[derive(Debug, Default, Clone)]
 struct StateValues {
     tests_state: Status<TestsState>,
+    failed_test: Status<Option<String>>,
     check_state: Status<CheckState>,
+    fmt_state: Status<FmtState>,
     coverage_state: Status<CoverageState>,
+    coverage_summary: Status<Option<CoverageSummary>>,
+    coverage_files: Status<Vec<FileCoverage>>,
+    diff_coverage: Status<Option<DiffCoverage>>,
+    quality: Status<Option<QualityReport>>,
+    stats: Status<Option<ProjectStats>>,
+    tests_count: Status<Option<u32>>,
+    source_tree: Status<Option<TreeSnapshot>>,
+    provenances: Status<HashMap<TrackedResult, StageProvenance>>,
+    timings: Status<HashMap<Stage, StageTiming>>,
+    durations: Status<HashMap<Stage, DurationStats>>,
+    metrics: Status<Metrics>,
+    custom_stages: Status<HashMap<String, CustomStageState>>,
     repo_root: Status<RepoRoot>,
+    watcher_state: Status<WatcherState>,
 }
 
 pub struct InMemoryState {
@@ -76,20 +115,102 @@ impl AppStateReader for InMemoryStateReader {
         Ok(self.values.tests_state.read())
     }
 
+    #[instrument(level = "trace")]
+    fn failed_test(&self) -> Result<Option<String>, StateReaderErr> {
+        Ok(self.values.failed_test.read())
+    }
+
     #[instrument(level = "trace")]
     fn check(&self) -> Result<CheckState, StateReaderErr> {
         Ok(self.values.check_state.read())
     }
 
+    #[instrument(level = "trace")]
+    fn fmt(&self) -> Result<FmtState, StateReaderErr> {
+        Ok(self.values.fmt_state.read())
+    }
+
     #[instrument(level = "trace")]
     fn coverage(&self) -> Result<CoverageState, StateReaderErr> {
         Ok(self.values.coverage_state.read())
     }
 
+    #[instrument(level = "trace")]
+    fn coverage_summary(&self) -> Result<Option<CoverageSummary>, StateReaderErr> {
+        Ok(self.values.coverage_summary.read())
+    }
+
+    #[instrument(level = "trace")]
+    fn coverage_files(&self) -> Result<Vec<FileCoverage>, StateReaderErr> {
+        Ok(self.values.coverage_files.read())
+    }
+
+    #[instrument(level = "trace")]
+    fn diff_coverage(&self) -> Result<Option<DiffCoverage>, StateReaderErr> {
+        Ok(self.values.diff_coverage.read())
+    }
+
+    #[instrument(level = "trace")]
+    fn quality(&self) -> Result<Option<QualityReport>, StateReaderErr> {
+        Ok(self.values.quality.read())
+    }
+
+    #[instrument(level = "trace")]
+    fn stats(&self) -> Result<Option<ProjectStats>, StateReaderErr> {
+        let tests = self.values.tests_count.read();
+        Ok(self
+            .values
+            .stats
+            .read()
+            .map(|stats| ProjectStats { tests, ..stats }))
+    }
+
+    #[instrument(level = "trace")]
+    fn custom_stages(&self) -> Result<HashMap<String, CustomStageState>, StateReaderErr> {
+        Ok(self.values.custom_stages.read())
+    }
+
     #[instrument(level = "trace")]
     fn repo_root(&self) -> Result<RepoRoot, StateReaderErr> {
         Ok(self.values.repo_root.read())
     }
+
+    #[instrument(level = "trace")]
+    fn watcher(&self) -> Result<WatcherState, StateReaderErr> {
+        Ok(self.values.watcher_state.read())
+    }
+
+    /// Holds all the values locked while reading them, so none of them can be changed in the
+    /// meantime. Writers lock only one value at a time, so it can't deadlock.
+    #[instrument(level = "trace")]
+    fn snapshot(&self) -> Result<StateSnapshot, StateReaderErr> {
+        let repo_root = self.values.repo_root.lock();
+        let watcher = self.values.watcher_state.lock();
+        let check = self.values.check_state.lock();
+        let fmt = self.values.fmt_state.lock();
+        let tests = self.values.tests_state.lock();
+        let coverage = self.values.coverage_state.lock();
+        let source_tree = self.values.source_tree.lock();
+        let provenances = self.values.provenances.lock();
+        let timings = self.values.timings.lock();
+        let durations = self.values.durations.lock();
+        let metrics = self.values.metrics.lock();
+        let custom_stages = self.values.custom_stages.lock();
+        Ok(StateSnapshot {
+            repo_root: repo_root.clone(),
+            watcher: watcher.clone(),
+            check: check.clone(),
+            fmt: fmt.clone(),
+            tests: tests.clone(),
+            coverage: coverage.clone(),
+            source_tree: source_tree.clone(),
+            provenances: provenances.clone(),
+            timings: timings.clone(),
+            durations: durations.clone(),
+            metrics: metrics.clone(),
+            custom_stages: custom_stages.clone(),
+        })
+    }
 }
 
 pub struct InMemoryStateWriter {
@@ -110,24 +231,152 @@ impl AppStateWriter for InMemoryStateWriter {
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn failed_test(&self, new_name: Option<String>) -> Result<(), StateWriterErr> {
+        self.values.failed_test.write(new_name);
+        Ok(())
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn check(&self, new_check_state: CheckState) -> Result<(), StateWriterErr> {
         self.values.check_state.write(new_check_state);
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn fmt(&self, new_fmt_state: FmtState) -> Result<(), StateWriterErr> {
+        self.values.fmt_state.write(new_fmt_state);
+        Ok(())
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn coverage(&self, new_coverage: CoverageState) -> Result<(), StateWriterErr> {
         self.values.coverage_state.write(new_coverage);
         Ok(())
     }
 
+    #[instrument(level = "trace", skip(self))]
+    fn coverage_summary(&self, new_summary: CoverageSummary) -> Result<(), StateWriterErr> {
+        self.values.coverage_summary.write(Some(new_summary));
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self, new_files))]
+    fn coverage_files(&self, new_files: Vec<FileCoverage>) -> Result<(), StateWriterErr> {
+        self.values.coverage_files.write(new_files);
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self, new_diff))]
+    fn diff_coverage(&self, new_diff: DiffCoverage) -> Result<(), StateWriterErr> {
+        self.values.diff_coverage.write(Some(new_diff));
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self, new_report))]
+    fn quality(&self, new_report: QualityReport) -> Result<(), StateWriterErr> {
+        self.values.quality.write(Some(new_report));
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn stats(&self, new_stats: ProjectStats) -> Result<(), StateWriterErr> {
+        self.values.stats.write(Some(new_stats));
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn tests_count(&self, new_count: u32) -> Result<(), StateWriterErr> {
+        self.values.tests_count.write(Some(new_count));
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn source_tree(&self, new_tree: TreeSnapshot) -> Result<(), StateWriterErr> {
+        self.values.source_tree.write(Some(new_tree));
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn provenance(
+        &self,
+        result: TrackedResult,
+        new_provenance: StageProvenance,
+    ) -> Result<(), StateWriterErr> {
+        self.values.provenances.update(|provenances| {
+            provenances.insert(result, new_provenance);
+        });
+        Ok(())
+    }
+
+    /// Finished stage is also added to the duration statistics and metrics of the stage.
+    #[instrument(level = "trace", skip(self))]
+    fn timing(&self, stage: Stage, new_timing: StageTiming) -> Result<(), StateWriterErr> {
+        self.values.timings.update(|timings| {
+            timings.insert(stage, new_timing);
+        });
+        if let Some(duration) = new_timing.duration() {
+            self.values.durations.update(|durations| {
+                durations.entry(stage).or_default().record(duration);
+            });
+            self.values
+                .metrics
+                .update(|metrics| metrics.stage_finished(stage, &new_timing));
+        }
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn custom_stage(
+        &self,
+        name: String,
+        new_state: CustomStageState,
+    ) -> Result<(), StateWriterErr> {
+        self.values.custom_stages.update(|stages| {
+            stages.insert(name, new_state);
+        });
+        Ok(())
+    }
+
     #[instrument(level = "trace", skip(self))]
     fn repo_root(&self, new_repo_root: RepoRoot) -> Result<(), StateWriterErr> {
         self.values.repo_root.write(new_repo_root);
         self.publ.send(BusEvent::ChangeDetected)?;
         Ok(())
     }
+
+    #[instrument(level = "trace", skip(self))]
+    fn trigger(&self, stage: Stage) -> Result<RunId, StateWriterErr> {
+        let first_event = match stage {
+            Stage::Check => BusEvent::ChangeDetected,
+            Stage::Tests => BusEvent::TestsRequested,
+            Stage::Index => return Err(StateWriterErr::NotTriggerable(stage)),
+            Stage::Coverage => BusEvent::CoverageRequested,
+        };
+        let run_id = RunId::new();
+        debug!("starting run {run_id} from {stage} stage");
+        self.publ.send(BusEvent::RunRequested(run_id.clone()))?;
+        self.publ.send(first_event)?;
+        Ok(run_id)
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn watcher(&self, new_watcher_state: WatcherState) -> Result<(), StateWriterErr> {
+        self.values.watcher_state.write(new_watcher_state.clone());
+        self.publ.send(match new_watcher_state {
+            WatcherState::Watching => BusEvent::WatcherResumed,
+            WatcherState::Paused => BusEvent::WatcherPaused,
+        })?;
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip(self))]
+    fn watched(&self, events: WatchedEvents) -> Result<(), StateWriterErr> {
+        self.values
+            .metrics
+            .update(|metrics| metrics.watched(events));
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -157,6 +406,50 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn snapshot_contains_values_of_all_stages() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let state = InMemoryState::make(bus.publisher());
+        let timing = StageTiming::start();
+        state.writer().check(CheckState::Success)?;
+        state.writer().tests(TestsState::Failure)?;
+        state.writer().timing(Stage::Tests, timing)?;
+
+        // when
+        let snapshot = state.reader().snapshot()?;
+
+        // then
+        assert_eq!(snapshot.check, CheckState::Success);
+        assert_eq!(snapshot.tests, TestsState::Failure);
+        assert_eq!(snapshot.coverage, CoverageState::Pending);
+        assert_eq!(snapshot.timings.get(&Stage::Tests), Some(&timing));
+
+        Ok(())
+    }
+
+    #[test]
+    fn duration_of_finished_stage_is_added_to_statistics() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let state = InMemoryState::make(bus.publisher());
+        let mut timing = StageTiming::start();
+        state.writer().timing(Stage::Index, timing)?;
+
+        // when
+        timing.finish(true);
+        state.writer().timing(Stage::Index, timing)?;
+
+        // then
+        let snapshot = state.reader().snapshot()?;
+        let stats = &snapshot.durations[&Stage::Index];
+        assert_eq!(stats.average(), timing.duration());
+
+        Ok(())
+    }
+
     #[test]
     fn pending_check_status_is_set_as_default() -> Result<()> {
         // given
@@ -227,6 +520,38 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn stats_are_read_with_tests_counted_by_tests_index() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let state = InMemoryState::make(bus.publisher());
+        let state_reader = state.reader();
+        let state_writer = state.writer();
+        let stats = ProjectStats {
+            files: 2,
+            sloc: 40,
+            ..ProjectStats::default()
+        };
+        state_writer.stats(stats)?;
+        assert_eq!(state_reader.stats()?, Some(stats));
+
+        // when
+        state_writer.tests_count(12)?;
+        state_writer.stats(stats)?;
+
+        // then
+        assert_eq!(
+            state_reader.stats()?,
+            Some(ProjectStats {
+                tests: Some(12),
+                ..stats
+            })
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn check_status_written_to_state_can_be_read() -> Result<()> {
         // given
@@ -302,4 +627,156 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn triggering_check_stage_publishes_change_detected_message() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let sub = bus.subscriber();
+        let state = InMemoryState::make(bus.publisher());
+        let state_writer = state.writer();
+
+        // when
+        let run_id = state_writer.trigger(Stage::Check)?;
+
+        // then
+        assert_eq!(sub.recv()?, BusEvent::RunRequested(run_id));
+        assert_eq!(sub.recv()?, BusEvent::ChangeDetected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn triggering_tests_stage_publishes_tests_requested_message() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let sub = bus.subscriber();
+        let state = InMemoryState::make(bus.publisher());
+        let state_writer = state.writer();
+
+        // when
+        let run_id = state_writer.trigger(Stage::Tests)?;
+
+        // then
+        assert_eq!(sub.recv()?, BusEvent::RunRequested(run_id));
+        assert_eq!(sub.recv()?, BusEvent::TestsRequested);
+
+        Ok(())
+    }
+
+    #[test]
+    fn triggering_coverage_stage_publishes_coverage_requested_message() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let sub = bus.subscriber();
+        let state = InMemoryState::make(bus.publisher());
+        let state_writer = state.writer();
+
+        // when
+        let run_id = state_writer.trigger(Stage::Coverage)?;
+
+        // then
+        assert_eq!(sub.recv()?, BusEvent::RunRequested(run_id));
+        assert_eq!(sub.recv()?, BusEvent::CoverageRequested);
+
+        Ok(())
+    }
+
+    #[test]
+    fn triggering_index_refresh_fails_without_publishing_anything() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let sub = bus.subscriber();
+        let state = InMemoryState::make(bus.publisher());
+        let state_writer = state.writer();
+
+        // when
+        let res = state_writer.trigger(Stage::Index);
+        let run_id = state_writer.trigger(Stage::Check)?;
+
+        // then
+        assert!(matches!(
+            res,
+            Err(StateWriterErr::NotTriggerable(Stage::Index))
+        ));
+        assert_eq!(sub.recv()?, BusEvent::RunRequested(run_id));
+
+        Ok(())
+    }
+
+    #[test]
+    fn watching_status_is_set_as_default() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let state = InMemoryState::make(bus.publisher());
+        let state = state.reader();
+
+        // when
+        let status = state.watcher()?;
+
+        // then
+        assert_eq!(status, WatcherState::Watching);
+
+        Ok(())
+    }
+
+    #[test]
+    fn watcher_status_written_to_state_can_be_read() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let state = InMemoryState::make(bus.publisher());
+        let state_reader = state.reader();
+        let state_writer = state.writer();
+        assert_eq!(state_reader.watcher()?, WatcherState::Watching);
+
+        // when
+        state_writer.watcher(WatcherState::Paused)?;
+
+        // then
+        assert_eq!(state_reader.watcher()?, WatcherState::Paused);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pausing_watcher_publishes_watcher_paused_message() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let sub = bus.subscriber();
+        let state = InMemoryState::make(bus.publisher());
+        let state_writer = state.writer();
+
+        // when
+        state_writer.watcher(WatcherState::Paused)?;
+
+        // then
+        assert_eq!(sub.recv()?, BusEvent::WatcherPaused);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resuming_watcher_publishes_watcher_resumed_message() -> Result<()> {
+        // given
+        init_tracing();
+        let bus = event_bus()?;
+        let sub = bus.subscriber();
+        let state = InMemoryState::make(bus.publisher());
+        let state_writer = state.writer();
+
+        // when
+        state_writer.watcher(WatcherState::Watching)?;
+
+        // then
+        assert_eq!(sub.recv()?, BusEvent::WatcherResumed);
+
+        Ok(())
+    }
 }
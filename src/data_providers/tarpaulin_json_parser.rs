@@ -0,0 +1,126 @@
+use crate::entities::coverage_report::{CoverageReport, Metric};
+use crate::entities::file_coverage::FileCoverage;
+use crate::result::ReportParseErr;
+use crate::use_cases::output_parser::OutputParser;
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use tracing::{error, instrument};
+
+/// Parses the JSON report written by `cargo tarpaulin --out Json` (`tarpaulin-report.json`).
+///
+/// It can fail. See [`TarpaulinJsonParser::parse`] for details.
+pub struct TarpaulinJsonParser;
+
+impl OutputParser for TarpaulinJsonParser {
+    type Output = CoverageReport;
+    type Error = ReportParseErr;
+
+    /// Parses the JSON report and returns coverage of every file mentioned in the report.
+    ///
+    /// Only line traces are taken into account, tarpaulin does not report branches and functions
+    /// coverage. It fails when the output is not a valid tarpaulin report.
+    #[instrument(skip(self, output))]
+    fn parse(&self, output: String) -> Result<Self::Output, Self::Error> {
+        let report: TarpaulinReport = serde_json::from_str(&output).map_err(|e| {
+            error!("invalid tarpaulin report: {e}");
+            ReportParseErr::InvalidReport(e.to_string())
+        })?;
+
+        let files: Vec<FileCoverage> = report.files.into_iter().map(to_file_coverage).collect();
+        let lines = Metric::new(
+            files.iter().map(FileCoverage::covered).sum(),
+            files.iter().map(FileCoverage::total).sum(),
+        );
+        Ok(CoverageReport {
+            lines,
+            branches: None,
+            functions: None,
+            files,
+        })
+    }
+}
+
+fn to_file_coverage(file: TarpaulinFile) -> FileCoverage {
+    let path: PathBuf = file.path.iter().collect();
+    let lines: BTreeMap<u32, u64> = file
+        .traces
+        .iter()
+        .filter_map(|trace| Some((trace.line, trace.stats.get("Line")?.as_u64()?)))
+        .collect();
+    FileCoverage::new(path.to_string_lossy(), lines)
+}
+
+#[derive(Debug, Deserialize)]
+struct TarpaulinReport {
+    files: Vec<TarpaulinFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TarpaulinFile {
+    path: Vec<String>,
+    traces: Vec<TarpaulinTrace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TarpaulinTrace {
+    line: u32,
+    stats: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn with_correct_report_it_returns_coverage_of_files() -> Result<()> {
+        // given
+        init_tracing();
+        let parser = TarpaulinJsonParser;
+        let report = r#"{"files":[{
+            "path":["/","repo","src","lib.rs"],
+            "content":"",
+            "traces":[
+                {"line":1,"address":[1],"length":1,"stats":{"Line":3}},
+                {"line":2,"address":[2],"length":1,"stats":{"Line":0}},
+                {"line":3,"address":[3],"length":1,"stats":{"Branch":{"been_true":true}}}
+            ],
+            "covered":1,"coverable":2
+        }],"coverage":50.0,"covered":1,"coverable":2}"#;
+
+        // when
+        let res = parser.parse(report.to_string())?;
+
+        // then
+        assert_eq!(
+            res.files,
+            vec![FileCoverage::new(
+                "/repo/src/lib.rs",
+                BTreeMap::from([(1, 3), (2, 0)])
+            )]
+        );
+        assert_eq!(res.lines, Metric::new(1, 2));
+        assert_eq!(res.branches, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_fails_when_report_is_not_valid() {
+        // given
+        init_tracing();
+        let parser = TarpaulinJsonParser;
+
+        // when
+        let res = parser.parse("\n50.00% coverage".to_string());
+
+        // then
+        assert!(matches!(res, Err(ReportParseErr::InvalidReport(_))));
+    }
+}
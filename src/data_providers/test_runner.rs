@@ -20,14 +20,18 @@ impl TRunner for DefaultTestRunner {
     #[instrument(skip(self))]
     fn run(&self, repo_root: RepoRoot) -> Result<TestsRunStatus, RunnerErr> {
         debug!("running tests in {repo_root}");
-        let Ok(status) = self.cfg.tests_cmd.status(repo_root) else {
+        let Ok((status, failed_test)) = self
+            .cfg
+            .tests_cmd
+            .status_and_find_in_stdout(repo_root, failed_test)
+        else {
             debug!("command failed");
-            return Ok(TestsRunStatus::Failure);
+            return Ok(TestsRunStatus::Failure(None));
         };
 
         if !status.success() {
             debug!("tests failed with: {status}");
-            return Ok(TestsRunStatus::Failure);
+            return Ok(TestsRunStatus::Failure(failed_test));
         }
 
         debug!("tests succeeded");
@@ -35,6 +39,13 @@ impl TRunner for DefaultTestRunner {
     }
 }
 
+/// Name of the test reported by libtest as failed, eg.: `test module::name ... FAILED`.
+fn failed_test(line: &str) -> Option<String> {
+    line.strip_prefix("test ")?
+        .strip_suffix(" ... FAILED")
+        .map(ToString::to_string)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -61,7 +72,7 @@ mod test {
         let res = runner.run(invalid_repo_root)?;
 
         // then
-        assert_eq!(res, TestsRunStatus::Failure);
+        assert_eq!(res, TestsRunStatus::Failure(None));
 
         Ok(())
     }
@@ -88,7 +99,7 @@ mod test {
         let res = runner.run(root)?;
 
         // then
-        assert_eq!(res, TestsRunStatus::Failure);
+        assert_eq!(res, TestsRunStatus::Failure(Some("test".into())));
 
         Ok(())
     }
@@ -56,6 +56,15 @@ impl TIndex for DefaultTestsIndex {
 
         Ok(IndexStatus::TestsSetNotChanged)
     }
+
+    fn tests_count(&self) -> Option<u32> {
+        let curr_tests = self.curr_tests.borrow();
+        if curr_tests.is_empty() {
+            return None;
+        }
+        let count = curr_tests.iter().filter(|t| !t.trim().is_empty()).count();
+        Some(u32::try_from(count).unwrap_or(u32::MAX))
+    }
 }
 
 #[cfg(test)]
@@ -162,6 +171,27 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn tests_are_counted_after_they_were_listed() -> Result<()> {
+        // given
+        init_tracing();
+        let cfg = ConfigBuilder::default()
+            .list_tests_cmd(Cmd::new("echo", &["a: test\nb: test\n\nc: test\n"]))
+            .build()?;
+        let tmpdir = tempdir()?;
+        let state = noop();
+        let index = DefaultTestsIndex::make(cfg, state.reader());
+        assert_eq!(index.tests_count(), None);
+
+        // when
+        index.refresh(RepoRoot::new(&tmpdir))?;
+
+        // then
+        assert_eq!(index.tests_count(), Some(3));
+
+        Ok(())
+    }
+
     #[test]
     fn when_tests_changed_correct_status_is_returned() -> Result<()> {
         // given
@@ -0,0 +1,361 @@
+use crate::configuration::config::Config;
+use crate::data_providers::diff_parser::DiffParser;
+use crate::entities::diff_coverage::ChangedLines;
+use crate::entities::provenance::Fingerprint;
+use crate::entities::repo_root::RepoRoot;
+use crate::result::VcsErr;
+use crate::use_cases::output_parser::OutputParser;
+use crate::use_cases::vcs::{Vcs, VersionControl};
+
+use std::fs;
+use std::hash::Hasher;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tracing::{debug, instrument};
+
+/// It reads the information about the repository using git. Commands are passed in via
+/// `Config::head_cmd`, `Config::diff_cmd`, `Config::status_cmd` and `Config::toplevel_cmd`.
+#[derive(Debug)]
+pub struct GitVcs {
+    cfg: Config,
+}
+
+impl GitVcs {
+    pub fn make(cfg: Config) -> VersionControl {
+        Arc::new(Self { cfg })
+    }
+}
+
+impl Vcs for GitVcs {
+    /// Executes `head_cmd` on the path specified by `repo_root`.
+    ///
+    /// It returns `None` when the command exits with non-zero status code (for example
+    /// `repo_root` is not a git repository or there are no commits yet).
+    #[instrument(skip(self))]
+    fn head(&self, repo_root: RepoRoot) -> Result<Option<String>, VcsErr> {
+        let Some(output) = self.cfg.head_cmd.successful_stdout(repo_root)? else {
+            debug!("failed to read head commit");
+            return Ok(None);
+        };
+        let head = output.trim();
+        if head.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(head.to_string()))
+    }
+
+    /// Executes `diff_cmd` on the path specified by `repo_root` and parses the diff.
+    ///
+    /// Paths are relative to `repo_root`, the same as paths of the coverage of files, and changes
+    /// outside of it are not included (`diff_cmd` uses `--relative`). Untracked files are not
+    /// included. It returns no changes when the command exits with non-zero status code (for
+    /// example `repo_root` is not a git repository).
+    #[instrument(skip(self))]
+    fn changes(&self, repo_root: RepoRoot) -> Result<Vec<ChangedLines>, VcsErr> {
+        let Some(output) = self.cfg.diff_cmd.successful_stdout(repo_root)? else {
+            debug!("failed to read diff");
+            return Ok(Vec::new());
+        };
+        Ok(DiffParser.parse(output)?)
+    }
+
+    /// Combines the head commit with the hash of the status of changed and untracked files.
+    ///
+    /// Files are not read, only their size and modification time are hashed together with the
+    /// output of `status_cmd`, so it's cheap enough to run on every change. Hash is missing when
+    /// there are no changes or `repo_root` is not a git repository.
+    #[instrument(skip(self))]
+    fn fingerprint(&self, repo_root: RepoRoot) -> Result<Fingerprint, VcsErr> {
+        let commit = self.head(repo_root.clone())?;
+        let status = self.cfg.status_cmd.successful_stdout(&repo_root)?;
+        let toplevel = self.cfg.toplevel_cmd.successful_stdout(&repo_root)?;
+        let (Some(status), Some(toplevel)) = (status, toplevel) else {
+            debug!("failed to read status of the repository");
+            return Ok(Fingerprint::new(commit, None));
+        };
+        if status.is_empty() {
+            return Ok(Fingerprint::new(commit, None));
+        }
+        let toplevel = Path::new(toplevel.trim_end());
+        let mut hasher = Fnv1aHasher::default();
+        hasher.write(status.as_bytes());
+        let mut entries = status.split_terminator('\0');
+        while let Some(entry) = entries.next() {
+            // NOTE: entry is `XY path`, renamed or copied file is followed by its original path
+            let (Some(xy), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+                continue;
+            };
+            if xy.contains(['R', 'C']) {
+                entries.next();
+            }
+            // NOTE: file could be removed in the meantime, then only its status is hashed
+            let Ok(metadata) = fs::metadata(toplevel.join(path)) else {
+                continue;
+            };
+            hasher.write_u64(metadata.len());
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .unwrap_or_default();
+            hasher.write_u128(modified.as_nanos());
+        }
+        Ok(Fingerprint::new(
+            commit,
+            Some(format!("{:016x}", hasher.finish())),
+        ))
+    }
+}
+
+/// 64-bit FNV-1a hash. Unlike `DefaultHasher` it's stable between runs and Rust versions, so
+/// fingerprints stay comparable after restart.
+struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::data_providers::command::Cmd;
+
+    use anyhow::Result;
+    use cmd_lib::run_cmd;
+    use std::collections::BTreeSet;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn when_directory_is_not_a_repository_there_is_no_head() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let cfg = ConfigBuilder::default()
+            .head_cmd(Cmd::new("git", &["rev-parse", "HEAD"]))
+            .build()?;
+        let vcs = GitVcs::make(cfg);
+
+        // when
+        let head = vcs.head(RepoRoot::new(&tmpdir))?;
+
+        // then
+        assert_eq!(head, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn changed_lines_are_returned_for_a_repository() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        fs::write(tmpdir_path.join("lib.rs"), "a\nb\nc\n")?;
+        run_cmd!(
+            cd $tmpdir_path;
+            git init -q;
+            git add lib.rs;
+            git -c user.name=test -c user.email=test@test commit -q -m init
+        )?;
+        fs::write(tmpdir_path.join("lib.rs"), "a\nchanged\nc\nd\n")?;
+        let vcs = GitVcs::make(diff_cfg()?);
+
+        // when
+        let changes = vcs.changes(RepoRoot::new(&tmpdir))?;
+
+        // then
+        assert_eq!(
+            changes,
+            vec![ChangedLines::new("lib.rs", BTreeSet::from([2, 4]))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn changed_lines_are_relative_to_repo_root_in_subdirectory() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        fs::create_dir_all(tmpdir_path.join("crate/src"))?;
+        fs::write(tmpdir_path.join("crate/src/lib.rs"), "a\n")?;
+        fs::write(tmpdir_path.join("other.rs"), "a\n")?;
+        run_cmd!(
+            cd $tmpdir_path;
+            git init -q;
+            git add .;
+            git -c user.name=test -c user.email=test@test commit -q -m init
+        )?;
+        fs::write(tmpdir_path.join("crate/src/lib.rs"), "a\nb\n")?;
+        fs::write(tmpdir_path.join("other.rs"), "a\nb\n")?;
+        let vcs = GitVcs::make(diff_cfg()?);
+
+        // when
+        let changes = vcs.changes(RepoRoot::new(tmpdir_path.join("crate")))?;
+
+        // then
+        assert_eq!(
+            changes,
+            vec![ChangedLines::new("src/lib.rs", BTreeSet::from([2]))]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_directory_is_not_a_repository_there_are_no_changes() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let vcs = GitVcs::make(diff_cfg()?);
+
+        // when
+        let changes = vcs.changes(RepoRoot::new(&tmpdir))?;
+
+        // then
+        assert!(changes.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn fingerprint_changes_with_uncommitted_and_untracked_files() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        fs::write(tmpdir_path.join("lib.rs"), "a\n")?;
+        run_cmd!(
+            cd $tmpdir_path;
+            git init -q;
+            git add lib.rs;
+            git -c user.name=test -c user.email=test@test commit -q -m init
+        )?;
+        let vcs = GitVcs::make(fingerprint_cfg()?);
+        let repo_root = RepoRoot::new(&tmpdir);
+
+        // when
+        let clean = vcs.fingerprint(repo_root.clone())?;
+        fs::write(tmpdir_path.join("lib.rs"), "b\n")?;
+        let modified = vcs.fingerprint(repo_root.clone())?;
+        fs::write(tmpdir_path.join("new.rs"), "c\n")?;
+        let untracked = vcs.fingerprint(repo_root.clone())?;
+        fs::write(tmpdir_path.join("new.rs"), "dd\n")?;
+        let untracked_modified = vcs.fingerprint(repo_root.clone())?;
+        fs::remove_file(tmpdir_path.join("new.rs"))?;
+        fs::write(tmpdir_path.join("lib.rs"), "a\n")?;
+        let reverted = vcs.fingerprint(repo_root)?;
+
+        // then
+        assert!(clean.commit.is_some());
+        assert_eq!(clean.dirty, None);
+        assert!(modified.dirty.is_some());
+        assert_ne!(modified, untracked);
+        assert_ne!(untracked, untracked_modified);
+        assert_eq!(reverted, clean);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fingerprint_hash_is_stable() {
+        // given
+        init_tracing();
+        let mut hasher = Fnv1aHasher::default();
+
+        // when
+        hasher.write(b"a");
+
+        // then
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn when_directory_is_not_a_repository_fingerprint_is_unknown() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let vcs = GitVcs::make(fingerprint_cfg()?);
+
+        // when
+        let fingerprint = vcs.fingerprint(RepoRoot::new(&tmpdir))?;
+
+        // then
+        assert_eq!(fingerprint, Fingerprint::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn head_commit_is_returned_for_a_repository() -> Result<()> {
+        // given
+        init_tracing();
+        let tmpdir = tempdir()?;
+        let tmpdir_path = tmpdir.path();
+        run_cmd!(
+            cd $tmpdir_path;
+            git init -q;
+            git -c user.name=test -c user.email=test@test commit -q --allow-empty -m init
+        )?;
+        let cfg = ConfigBuilder::default()
+            .head_cmd(Cmd::new("git", &["rev-parse", "HEAD"]))
+            .build()?;
+        let vcs = GitVcs::make(cfg);
+
+        // when
+        let head = vcs.head(RepoRoot::new(&tmpdir))?;
+
+        // then
+        assert!(matches!(head, Some(sha) if sha.len() == 40));
+
+        Ok(())
+    }
+
+    fn fingerprint_cfg() -> Result<Config> {
+        Ok(ConfigBuilder::default()
+            .head_cmd(Cmd::new("git", &["rev-parse", "HEAD"]))
+            .status_cmd(Cmd::new(
+                "git",
+                &["status", "--porcelain", "-z", "--untracked-files=all"],
+            ))
+            .toplevel_cmd(Cmd::new("git", &["rev-parse", "--show-toplevel"]))
+            .build()?)
+    }
+
+    fn diff_cfg() -> Result<Config> {
+        Ok(ConfigBuilder::default()
+            .diff_cmd(Cmd::new(
+                "git",
+                &[
+                    "diff",
+                    "--relative",
+                    "--unified=0",
+                    "--no-color",
+                    "--no-ext-diff",
+                    "HEAD",
+                ],
+            ))
+            .build()?)
+    }
+}
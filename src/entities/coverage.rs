@@ -1,11 +1,84 @@
-use serde::{Serialize, Serializer};
+use crate::entities::coverage_report::Metric;
+use crate::entities::file_coverage::FileCoverage;
+use crate::result::RegressionThresholdErr;
+
+use glob::Pattern;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::VecDeque;
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(remote = "Self", rename_all = "lowercase")]
 pub enum CoverageState {
     Pending,
     Failure,
     Success(f32),
+    Regressed {
+        actual: f32,
+        baseline: f32,
+        severity: Severity,
+    },
+    BelowThreshold {
+        actual: f32,
+        required: f32,
+        /// File whose coverage is below its minimum, none when the whole project coverage is.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file: Option<String>,
+    },
+    /// Coverage was not run, because a stage before it failed.
+    Skipped {
+        reason: String,
+    },
+}
+
+impl CoverageState {
+    /// Returns calculated coverage, if there is any.
+    pub fn value(&self) -> Option<f32> {
+        match self {
+            CoverageState::Success(val) => Some(*val),
+            CoverageState::Regressed { actual, .. }
+            | CoverageState::BelowThreshold { actual, .. } => Some(*actual),
+            CoverageState::Pending | CoverageState::Failure | CoverageState::Skipped { .. } => None,
+        }
+    }
+
+    /// Returns the value which the next coverage result should be compared against, when there
+    /// are no recent results to take the baseline from (see `CoverageBaseline`).
+    ///
+    /// Regressed coverage keeps its baseline. Coverage below the minimum is not accepted, so the
+    /// baseline of the result before it is used.
+    pub fn baseline(&self) -> Option<f32> {
+        match self {
+            CoverageState::Success(val) => Some(*val),
+            CoverageState::Regressed { baseline, .. } => Some(*baseline),
+            CoverageState::Pending
+            | CoverageState::Failure
+            | CoverageState::BelowThreshold { .. }
+            | CoverageState::Skipped { .. } => None,
+        }
+    }
+
+    /// Returns why the coverage was not run, if it was skipped.
+    pub fn skip_reason(&self) -> Option<&str> {
+        match self {
+            CoverageState::Skipped { reason } => Some(reason),
+            _ => None,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            CoverageState::Failure
+                | CoverageState::BelowThreshold { .. }
+                | CoverageState::Regressed {
+                    severity: Severity::Failure,
+                    ..
+                }
+        )
+    }
 }
 
 impl Serialize for CoverageState {
@@ -13,10 +86,37 @@ impl Serialize for CoverageState {
     where
         S: Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        CoverageState::serialize(self, serializer)
+    }
+}
+
+/// Besides the current form, it reads the textual form which was stored in the history before the
+/// coverage regressions were tracked (`pending`, `failure` or the value, eg. `"84.5"`).
+impl<'de> Deserialize<'de> for CoverageState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Stored {
+            Current(#[serde(with = "CoverageState")] CoverageState),
+            Legacy(String),
+        }
+
+        match Stored::deserialize(deserializer)? {
+            Stored::Current(state) => Ok(state),
+            Stored::Legacy(val) => val
+                .parse()
+                .map(CoverageState::Success)
+                .map_err(|_| D::Error::custom(format!("invalid coverage state: {val}"))),
+        }
     }
 }
 
+/// Textual form of the state, used by the API.
+///
+/// Coverage which regressed only to the level of a warning is still shown as a coverage value.
 impl Display for CoverageState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -26,11 +126,165 @@ impl Display for CoverageState {
                 CoverageState::Pending => "pending".to_string(),
                 CoverageState::Failure => "failure".to_string(),
                 CoverageState::Success(val) => format!("{val}"),
+                CoverageState::Regressed {
+                    actual,
+                    severity: Severity::Warning,
+                    ..
+                } => format!("{actual}"),
+                CoverageState::Regressed {
+                    severity: Severity::Failure,
+                    ..
+                } => "regressed".to_string(),
+                CoverageState::BelowThreshold { .. } => "below_threshold".to_string(),
+                CoverageState::Skipped { .. } => "skipped".to_string(),
             }
         )
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Failure,
+}
+
+/// Recent coverage results, the best of them is the baseline for the next result.
+///
+/// Comparing with the best result in the window, instead of the previous one, catches the
+/// coverage which drops in small steps, each of them below the regression threshold.
+#[derive(Debug, Default, Clone)]
+pub struct CoverageBaseline {
+    results: VecDeque<f32>,
+}
+
+impl CoverageBaseline {
+    /// Number of the recent results the baseline is taken from.
+    pub const WINDOW: usize = 20;
+
+    /// The best of the recent results.
+    pub fn value(&self) -> Option<f32> {
+        self.results.iter().copied().reduce(f32::max)
+    }
+
+    pub fn add(&mut self, val: f32) {
+        self.results.push_back(val);
+        if self.results.len() > Self::WINDOW {
+            self.results.pop_front();
+        }
+    }
+
+    /// Starts the window with the value, eg. the baseline of the result restored from the
+    /// history, unless there are results already.
+    pub fn seed(&mut self, val: Option<f32>) {
+        if let (true, Some(val)) = (self.results.is_empty(), val) {
+            self.add(val);
+        }
+    }
+}
+
+/// Maximal drop of the coverage (in percentage points) compared to the baseline, which is not
+/// reported as a regression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegressionThreshold {
+    pub max_drop: f32,
+    pub severity: Severity,
+}
+
+impl RegressionThreshold {
+    pub fn new(max_drop: f32, severity: Severity) -> Self {
+        Self { max_drop, severity }
+    }
+
+    /// Returns the state of the coverage `actual` compared to `baseline`.
+    pub fn evaluate(&self, actual: f32, baseline: f32) -> CoverageState {
+        if baseline - actual > self.max_drop {
+            CoverageState::Regressed {
+                actual,
+                baseline,
+                severity: self.severity,
+            }
+        } else {
+            CoverageState::Success(actual)
+        }
+    }
+}
+
+impl Default for RegressionThreshold {
+    fn default() -> Self {
+        Self::new(5.0, Severity::Warning)
+    }
+}
+
+impl FromStr for RegressionThreshold {
+    type Err = RegressionThresholdErr;
+
+    /// Parses the maximal drop in percentage points, optionally followed by the severity after
+    /// a colon, eg.: `2.5` or `2.5:failure`. Severity is `warning` by default.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || RegressionThresholdErr::Unknown(s.to_string());
+        let (max_drop, severity) = match s.split_once(':') {
+            Some((max_drop, "warning")) => (max_drop, Severity::Warning),
+            Some((max_drop, "failure")) => (max_drop, Severity::Failure),
+            Some(_) => return Err(unknown()),
+            None => (s, Severity::Warning),
+        };
+        match max_drop.trim().parse() {
+            Ok(max_drop) if max_drop >= 0.0 => Ok(Self::new(max_drop, severity)),
+            _ => Err(unknown()),
+        }
+    }
+}
+
+/// Minimal coverage (in percents) required from the whole project and from the files matching
+/// glob patterns. Paths of the files are relative to the repo root.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MinimumCoverage {
+    pub global: Option<f32>,
+    pub files: Vec<FileMinimum>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMinimum {
+    pub pattern: Pattern,
+    pub required: f32,
+}
+
+impl FileMinimum {
+    pub fn new(pattern: Pattern, required: f32) -> Self {
+        Self { pattern, required }
+    }
+}
+
+impl MinimumCoverage {
+    /// Returns `CoverageState::BelowThreshold` when the project coverage `actual` or coverage of
+    /// any matching file is lower than required.
+    ///
+    /// The project coverage is checked first, then files in the order they are given. Files
+    /// without instrumented lines are skipped.
+    pub fn evaluate(&self, actual: f32, files: &[FileCoverage]) -> Option<CoverageState> {
+        if let Some(required) = self.global.filter(|required| actual < *required) {
+            return Some(CoverageState::BelowThreshold {
+                actual,
+                required,
+                file: None,
+            });
+        }
+        files.iter().find_map(|file| {
+            let actual = Metric::new(file.covered(), file.total()).percent()?;
+            self.files
+                .iter()
+                .filter(|min| min.pattern.matches(&file.path))
+                .find(|min| actual < min.required)
+                .map(|min| CoverageState::BelowThreshold {
+                    actual,
+                    required: min.required,
+                    file: Some(file.path.clone()),
+                })
+        })
+    }
+}
+
 impl Default for CoverageState {
     fn default() -> Self {
         Self::Pending
@@ -43,6 +297,8 @@ mod test {
 
     use crate::configuration::tracing::init_tracing;
 
+    use std::collections::BTreeMap;
+
     #[test]
     fn default_coverage_status_is_pending() {
         // given
@@ -64,5 +320,281 @@ mod test {
         assert_eq!(CoverageState::Pending.to_string(), "pending");
         assert_eq!(CoverageState::Failure.to_string(), "failure");
         assert_eq!(CoverageState::Success(10.1).to_string(), "10.1");
+        let regressed = |severity| CoverageState::Regressed {
+            actual: 10.1,
+            baseline: 20.0,
+            severity,
+        };
+        assert_eq!(regressed(Severity::Warning).to_string(), "10.1");
+        assert_eq!(regressed(Severity::Failure).to_string(), "regressed");
+        let below = CoverageState::BelowThreshold {
+            actual: 10.1,
+            required: 20.0,
+            file: None,
+        };
+        assert_eq!(below.to_string(), "below_threshold");
+        let skipped = CoverageState::Skipped {
+            reason: "tests failed".into(),
+        };
+        assert_eq!(skipped.to_string(), "skipped");
+    }
+
+    #[test]
+    fn small_drops_are_a_regression_once_they_add_up_to_more_than_threshold() {
+        // given
+        init_tracing();
+        let threshold = RegressionThreshold::new(5.0, Severity::Warning);
+        let mut baseline = CoverageBaseline::default();
+        baseline.seed(Some(82.0));
+
+        // when
+        let states: Vec<_> = [80.0, 78.0, 76.0, 74.0]
+            .into_iter()
+            .map(|val| {
+                let state = threshold.evaluate(val, baseline.value().unwrap_or(val));
+                baseline.add(val);
+                state
+            })
+            .collect();
+
+        // then
+        assert_eq!(
+            states,
+            vec![
+                CoverageState::Success(80.0),
+                CoverageState::Success(78.0),
+                CoverageState::Regressed {
+                    actual: 76.0,
+                    baseline: 82.0,
+                    severity: Severity::Warning,
+                },
+                CoverageState::Regressed {
+                    actual: 74.0,
+                    baseline: 82.0,
+                    severity: Severity::Warning,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn baseline_is_the_best_of_the_recent_results() {
+        // given
+        init_tracing();
+        let mut baseline = CoverageBaseline::default();
+        baseline.add(90.0);
+
+        // when
+        for _ in 0..CoverageBaseline::WINDOW {
+            baseline.add(70.0);
+        }
+        baseline.seed(Some(95.0));
+
+        // then
+        assert_eq!(baseline.value(), Some(70.0));
+    }
+
+    #[test]
+    fn drop_bigger_than_threshold_is_a_regression() {
+        // given
+        init_tracing();
+        let threshold = RegressionThreshold::new(5.0, Severity::Failure);
+
+        // when
+        let state = threshold.evaluate(74.0, 82.0);
+
+        // then
+        assert_eq!(
+            state,
+            CoverageState::Regressed {
+                actual: 74.0,
+                baseline: 82.0,
+                severity: Severity::Failure
+            }
+        );
+    }
+
+    #[test]
+    fn drop_within_threshold_is_not_a_regression() {
+        // given
+        init_tracing();
+        let threshold = RegressionThreshold::new(5.0, Severity::Failure);
+
+        // when
+        let state = threshold.evaluate(78.0, 82.0);
+
+        // then
+        assert_eq!(state, CoverageState::Success(78.0));
+    }
+
+    #[test]
+    fn regression_threshold_can_be_parsed_with_optional_severity() -> anyhow::Result<()> {
+        // given
+        init_tracing();
+
+        // when
+        let warning: RegressionThreshold = "2.5".parse()?;
+        let failure: RegressionThreshold = "10:failure".parse()?;
+
+        // then
+        assert_eq!(warning, RegressionThreshold::new(2.5, Severity::Warning));
+        assert_eq!(failure, RegressionThreshold::new(10.0, Severity::Failure));
+        assert!("-1".parse::<RegressionThreshold>().is_err());
+        assert!("5:fatal".parse::<RegressionThreshold>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_below_global_minimum_is_below_threshold() {
+        // given
+        init_tracing();
+        let minimum = MinimumCoverage {
+            global: Some(80.0),
+            files: Vec::new(),
+        };
+
+        // when
+        let state = minimum.evaluate(75.0, &[]);
+
+        // then
+        assert_eq!(
+            state,
+            Some(CoverageState::BelowThreshold {
+                actual: 75.0,
+                required: 80.0,
+                file: None,
+            })
+        );
+        assert!(state.is_some_and(|state| state.is_failure()));
+    }
+
+    #[test]
+    fn file_below_minimum_of_matching_pattern_is_below_threshold() -> anyhow::Result<()> {
+        // given
+        init_tracing();
+        let minimum = MinimumCoverage {
+            global: Some(50.0),
+            files: vec![
+                FileMinimum::new(Pattern::new("src/core/**")?, 90.0),
+                FileMinimum::new(Pattern::new("src/*.rs")?, 10.0),
+            ],
+        };
+        let files = [
+            FileCoverage::new("src/main.rs", BTreeMap::from([(1, 0), (2, 1)])),
+            FileCoverage::new("src/core/lib.rs", BTreeMap::from([(1, 0), (2, 1), (3, 1)])),
+        ];
+
+        // when
+        let state = minimum.evaluate(60.0, &files);
+
+        // then
+        assert!(matches!(
+            state,
+            Some(CoverageState::BelowThreshold { actual, required, file })
+                if required == 90.0
+                    && (actual - 66.67).abs() < 0.01
+                    && file.as_deref() == Some("src/core/lib.rs")
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_meeting_all_minimums_is_not_below_threshold() -> anyhow::Result<()> {
+        // given
+        init_tracing();
+        let minimum = MinimumCoverage {
+            global: Some(50.0),
+            files: vec![FileMinimum::new(Pattern::new("src/*.rs")?, 50.0)],
+        };
+        let files = [
+            FileCoverage::new("src/main.rs", BTreeMap::from([(1, 0), (2, 1)])),
+            FileCoverage::new("tests/it.rs", BTreeMap::from([(1, 0)])),
+        ];
+
+        // when
+        let state = minimum.evaluate(50.0, &files);
+
+        // then
+        assert_eq!(state, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_below_minimum_is_not_a_baseline() {
+        // given
+        init_tracing();
+        let below = CoverageState::BelowThreshold {
+            actual: 40.0,
+            required: 80.0,
+            file: None,
+        };
+
+        // then
+        assert_eq!(below.baseline(), None);
+        assert_eq!(CoverageState::Success(40.0).baseline(), Some(40.0));
+    }
+
+    #[test]
+    fn coverage_status_can_be_deserialized_from_its_serialized_form() -> anyhow::Result<()> {
+        // given
+        init_tracing();
+        let states = [
+            CoverageState::Pending,
+            CoverageState::Failure,
+            CoverageState::Success(10.1),
+            CoverageState::Regressed {
+                actual: 10.1,
+                baseline: 20.0,
+                severity: Severity::Warning,
+            },
+            CoverageState::BelowThreshold {
+                actual: 10.1,
+                required: 20.0,
+                file: Some("src/lib.rs".into()),
+            },
+            CoverageState::Skipped {
+                reason: "tests failed".into(),
+            },
+        ];
+
+        for state in states {
+            // when
+            let serialized = serde_json::to_string(&state)?;
+            let deserialized: CoverageState = serde_json::from_str(&serialized)?;
+
+            // then
+            assert_eq!(deserialized, state);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_status_can_be_deserialized_from_legacy_textual_form() -> anyhow::Result<()> {
+        // given
+        init_tracing();
+        let legacy = ["\"pending\"", "\"failure\"", "\"84.5\""];
+
+        // when
+        let states = legacy
+            .iter()
+            .map(|state| serde_json::from_str(state))
+            .collect::<Result<Vec<CoverageState>, _>>()?;
+
+        // then
+        assert_eq!(
+            states,
+            vec![
+                CoverageState::Pending,
+                CoverageState::Failure,
+                CoverageState::Success(84.5)
+            ]
+        );
+        assert!(serde_json::from_str::<CoverageState>("\"unknown\"").is_err());
+
+        Ok(())
     }
 }
@@ -0,0 +1,94 @@
+use crate::result::CoverageBackendErr;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Tool used to calculate the code coverage.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum CoverageBackend {
+    #[default]
+    Tarpaulin,
+    LlvmCov,
+    Grcov,
+}
+
+impl FromStr for CoverageBackend {
+    type Err = CoverageBackendErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tarpaulin" => Ok(CoverageBackend::Tarpaulin),
+            "llvm-cov" => Ok(CoverageBackend::LlvmCov),
+            "grcov" => Ok(CoverageBackend::Grcov),
+            _ => Err(CoverageBackendErr::Unknown(s.to_string())),
+        }
+    }
+}
+
+impl Display for CoverageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CoverageBackend::Tarpaulin => "tarpaulin",
+                CoverageBackend::LlvmCov => "llvm-cov",
+                CoverageBackend::Grcov => "grcov",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn default_coverage_backend_is_tarpaulin() {
+        // given
+        init_tracing();
+
+        // when
+        let backend = CoverageBackend::default();
+
+        // then
+        assert_eq!(backend, CoverageBackend::Tarpaulin);
+    }
+
+    #[test]
+    fn coverage_backend_can_be_parsed_from_its_name() -> Result<()> {
+        // given
+        init_tracing();
+        let backends = [
+            CoverageBackend::Tarpaulin,
+            CoverageBackend::LlvmCov,
+            CoverageBackend::Grcov,
+        ];
+
+        for backend in backends {
+            // when
+            let parsed: CoverageBackend = backend.to_string().parse()?;
+
+            // then
+            assert_eq!(parsed, backend);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_coverage_backend_is_not_parsed() {
+        // given
+        init_tracing();
+
+        // when
+        let res = "kcov".parse::<CoverageBackend>();
+
+        // then
+        assert!(matches!(res, Err(CoverageBackendErr::Unknown(_))));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::file_coverage::FileCoverage;
+
+use std::path::{Path, PathBuf};
+
+/// Coverage read from the report generated by the coverage tool.
+///
+/// Branches and functions are present only when the report contains them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CoverageReport {
+    pub lines: Metric,
+    pub branches: Option<Metric>,
+    pub functions: Option<Metric>,
+    pub files: Vec<FileCoverage>,
+}
+
+impl CoverageReport {
+    /// Returns percentages of all metrics in the report or `None` when there are no instrumented
+    /// lines.
+    pub fn summary(&self) -> Option<CoverageSummary> {
+        Some(CoverageSummary {
+            lines: self.lines.percent()?,
+            branches: self.branches.as_ref().and_then(Metric::percent),
+            functions: self.functions.as_ref().and_then(Metric::percent),
+            regions: None,
+        })
+    }
+}
+
+/// Number of covered items (lines, branches, etc.) out of the total number of them.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct Metric {
+    pub covered: u64,
+    pub total: u64,
+}
+
+impl Metric {
+    pub fn new(covered: u64, total: u64) -> Self {
+        Self { covered, total }
+    }
+
+    /// Returns percentage of covered items or `None` when there is nothing to cover.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percent(&self) -> Option<f32> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(self.covered as f32 / self.total as f32 * 100.0)
+    }
+}
+
+/// Format of the coverage report file.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum ReportFormat {
+    #[default]
+    Lcov,
+    Cobertura,
+    TarpaulinJson,
+}
+
+impl ReportFormat {
+    /// Guesses the format from the extension of the report: `.info` (lcov), `.xml` (Cobertura)
+    /// or `.json` (tarpaulin).
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "info" => Some(ReportFormat::Lcov),
+            "xml" => Some(ReportFormat::Cobertura),
+            "json" => Some(ReportFormat::TarpaulinJson),
+            _ => None,
+        }
+    }
+}
+
+/// Location of the coverage report, relative to the repo root, and its format.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ReportLocation {
+    pub path: PathBuf,
+    pub format: ReportFormat,
+}
+
+impl ReportLocation {
+    pub fn new<P: Into<PathBuf>>(path: P, format: ReportFormat) -> Self {
+        let path = path.into();
+        Self { path, format }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn percent_of_covered_items_is_calculated() {
+        // given
+        init_tracing();
+        let metric = Metric::new(3, 4);
+
+        // when
+        let percent = metric.percent();
+
+        // then
+        assert_eq!(percent, Some(75.0));
+    }
+
+    #[test]
+    fn summary_contains_percentages_of_all_metrics() {
+        // given
+        init_tracing();
+        let report = CoverageReport {
+            lines: Metric::new(1, 2),
+            branches: Some(Metric::new(0, 0)),
+            functions: Some(Metric::new(1, 4)),
+            files: Vec::new(),
+        };
+
+        // when
+        let summary = report.summary();
+
+        // then
+        assert_eq!(
+            summary,
+            Some(CoverageSummary {
+                lines: 50.0,
+                branches: None,
+                functions: Some(25.0),
+                regions: None,
+            })
+        );
+    }
+
+    #[test]
+    fn there_is_no_summary_without_instrumented_lines() {
+        // given
+        init_tracing();
+        let report = CoverageReport::default();
+
+        // then
+        assert_eq!(report.summary(), None);
+    }
+
+    #[test]
+    fn report_format_is_guessed_from_extension() {
+        // given
+        init_tracing();
+
+        // then
+        assert_eq!(
+            ReportFormat::from_path("lcov.info"),
+            Some(ReportFormat::Lcov)
+        );
+        assert_eq!(
+            ReportFormat::from_path("target/cobertura.xml"),
+            Some(ReportFormat::Cobertura)
+        );
+        assert_eq!(
+            ReportFormat::from_path("tarpaulin-report.json"),
+            Some(ReportFormat::TarpaulinJson)
+        );
+        assert_eq!(ReportFormat::from_path("report.html"), None);
+        assert_eq!(ReportFormat::from_path("report"), None);
+    }
+
+    #[test]
+    fn there_is_no_percent_when_there_is_nothing_to_cover() {
+        // given
+        init_tracing();
+        let metric = Metric::new(0, 0);
+
+        // when
+        let percent = metric.percent();
+
+        // then
+        assert_eq!(percent, None);
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Percentages of covered lines, branches, functions and regions.
+///
+/// Only lines coverage is always present, the rest depends on what the coverage tool reports.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct CoverageSummary {
+    pub lines: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branches: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub functions: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regions: Option<f32>,
+}
+
+impl CoverageSummary {
+    pub fn new(lines: f32) -> Self {
+        Self {
+            lines,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn missing_metrics_are_not_serialized() -> Result<()> {
+        // given
+        init_tracing();
+        let summary = CoverageSummary {
+            branches: Some(50.0),
+            ..CoverageSummary::new(75.0)
+        };
+
+        // when
+        let json = serde_json::to_string(&summary)?;
+
+        // then
+        assert_eq!(json, r#"{"lines":75.0,"branches":50.0}"#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_metrics_are_deserialized_as_none() -> Result<()> {
+        // given
+        init_tracing();
+
+        // when
+        let summary: CoverageSummary = serde_json::from_str(r#"{"lines":75.0}"#)?;
+
+        // then
+        assert_eq!(summary, CoverageSummary::new(75.0));
+
+        Ok(())
+    }
+}
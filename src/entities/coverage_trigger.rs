@@ -0,0 +1,114 @@
+use crate::result::CoverageTriggerErr;
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Policy deciding when the code coverage is run after the tests passed.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum CoverageTrigger {
+    /// Only when the tests set changed.
+    #[default]
+    TestsSetChanged,
+    /// After every successful tests run.
+    TestsPassed,
+    /// When the tests set changed or any file in the coverage scope was modified since the last
+    /// coverage.
+    SourceChanged,
+    /// When there was no change for the given time after the tests passed.
+    Idle(Duration),
+    /// After the tests passed, when the given time elapsed since the last coverage.
+    Periodic(Duration),
+}
+
+impl FromStr for CoverageTrigger {
+    type Err = CoverageTriggerErr;
+
+    /// Parses the policy from its name, idle and periodic policies have the time in seconds after
+    /// a colon, eg.: `idle:30` or `periodic:600`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tests-set-changed" => Ok(CoverageTrigger::TestsSetChanged),
+            "tests-passed" => Ok(CoverageTrigger::TestsPassed),
+            "source-changed" => Ok(CoverageTrigger::SourceChanged),
+            _ => {
+                let (name, secs) = s
+                    .split_once(':')
+                    .ok_or(CoverageTriggerErr::Unknown(s.to_string()))?;
+                let time = secs
+                    .parse()
+                    .map(Duration::from_secs)
+                    .map_err(|_| CoverageTriggerErr::Unknown(s.to_string()))?;
+                match name {
+                    "idle" => Ok(CoverageTrigger::Idle(time)),
+                    "periodic" => Ok(CoverageTrigger::Periodic(time)),
+                    _ => Err(CoverageTriggerErr::Unknown(s.to_string())),
+                }
+            }
+        }
+    }
+}
+
+impl Display for CoverageTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoverageTrigger::TestsSetChanged => write!(f, "tests-set-changed"),
+            CoverageTrigger::TestsPassed => write!(f, "tests-passed"),
+            CoverageTrigger::SourceChanged => write!(f, "source-changed"),
+            CoverageTrigger::Idle(idle) => write!(f, "idle:{}", idle.as_secs()),
+            CoverageTrigger::Periodic(period) => write!(f, "periodic:{}", period.as_secs()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn coverage_trigger_can_be_parsed_from_its_name() -> Result<()> {
+        // given
+        init_tracing();
+        let triggers = [
+            CoverageTrigger::TestsSetChanged,
+            CoverageTrigger::TestsPassed,
+            CoverageTrigger::SourceChanged,
+            CoverageTrigger::Idle(Duration::from_secs(30)),
+            CoverageTrigger::Periodic(Duration::from_secs(600)),
+        ];
+
+        for trigger in triggers {
+            // when
+            let parsed: CoverageTrigger = trigger.to_string().parse()?;
+
+            // then
+            assert_eq!(parsed, trigger);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_coverage_trigger_is_not_parsed() {
+        // given
+        init_tracing();
+
+        // then
+        assert!(matches!(
+            "always".parse::<CoverageTrigger>(),
+            Err(CoverageTriggerErr::Unknown(_))
+        ));
+        assert!(matches!(
+            "idle:soon".parse::<CoverageTrigger>(),
+            Err(CoverageTriggerErr::Unknown(_))
+        ));
+        assert!(matches!(
+            "hourly:60".parse::<CoverageTrigger>(),
+            Err(CoverageTriggerErr::Unknown(_))
+        ));
+    }
+}
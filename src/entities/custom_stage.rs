@@ -0,0 +1,300 @@
+use crate::result::PipelineErr;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+/// Names of the built-in stages which custom stages can depend on, in the order they are run.
+pub const BUILT_IN_STAGES: [&str; 3] = ["check", "tests", "coverage"];
+
+/// Stages defined by the user, run after the stages they depend on.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineDef {
+    pub stages: Vec<CustomStage>,
+}
+
+/// Stage defined by the user, eg.: `cargo fmt --check` or `cargo deny check`.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CustomStage {
+    pub name: String,
+    /// Program followed by its arguments, run in the repo root.
+    pub cmd: Vec<String>,
+    /// Stages (built-in or custom) which have to pass before this stage is run. Stage without
+    /// dependencies is run when a change is detected.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub success: SuccessCriterion,
+    /// Regex matched against the output of the command. The first capture group (or the whole
+    /// match) is the summary of the stage.
+    #[serde(default)]
+    pub parser: Option<String>,
+    /// Runs the stage with its own `CARGO_TARGET_DIR`, so it doesn't wait for the lock of the
+    /// build directory held by the stages running in parallel.
+    #[serde(default)]
+    pub own_target_dir: bool,
+    /// Stages depending on this stage are run even when it failed.
+    #[serde(default)]
+    pub continue_on_failure: bool,
+}
+
+/// Decides if the stage passed, based on the exit code and the output of the command.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuccessCriterion {
+    /// Command exits with zero.
+    #[default]
+    ExitCode,
+    /// Output of the command contains the text, regardless of the exit code.
+    OutputContains(String),
+    /// Command exits with zero and its output doesn't contain the text.
+    OutputNotContains(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum CustomStageState {
+    #[default]
+    Pending,
+    Success {
+        summary: Option<String>,
+    },
+    Failure {
+        summary: Option<String>,
+    },
+    /// Stage was not run, because a stage it depends on didn't pass.
+    Skipped {
+        reason: String,
+    },
+}
+
+impl PipelineDef {
+    /// Checks that the names are unique, made of ASCII letters, digits, `-` and `_` only (they are
+    /// used in paths and URLs) and don't clash with built-in stages, that every dependency is
+    /// known, parsers are valid regexes and there are no dependency cycles.
+    pub fn validate(&self) -> Result<(), PipelineErr> {
+        let mut names = HashSet::new();
+        for stage in &self.stages {
+            if !is_valid_name(&stage.name) {
+                return Err(PipelineErr::InvalidName(stage.name.clone()));
+            }
+            if BUILT_IN_STAGES.contains(&stage.name.as_str()) || stage.name == "index" {
+                return Err(PipelineErr::ReservedName(stage.name.clone()));
+            }
+            if !names.insert(stage.name.as_str()) {
+                return Err(PipelineErr::DuplicateStage(stage.name.clone()));
+            }
+            if stage.cmd.is_empty() {
+                return Err(PipelineErr::EmptyCmd(stage.name.clone()));
+            }
+            if let Some(parser) = &stage.parser {
+                Regex::new(parser).map_err(|_| PipelineErr::InvalidParser(parser.clone()))?;
+            }
+        }
+        for stage in &self.stages {
+            for dep in &stage.depends_on {
+                if !names.contains(dep.as_str()) && !BUILT_IN_STAGES.contains(&dep.as_str()) {
+                    return Err(PipelineErr::UnknownDependency {
+                        stage: stage.name.clone(),
+                        dependency: dep.clone(),
+                    });
+                }
+            }
+        }
+        self.check_cycles()
+    }
+
+    fn check_cycles(&self) -> Result<(), PipelineErr> {
+        let deps: HashMap<&str, &[String]> = self
+            .stages
+            .iter()
+            .map(|stage| (stage.name.as_str(), stage.depends_on.as_slice()))
+            .collect();
+        let mut done = HashSet::new();
+        for stage in &self.stages {
+            let mut path = Vec::new();
+            visit(&stage.name, &deps, &mut path, &mut done)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Depth-first search, failing when the stage is reached again from itself.
+fn visit<'a>(
+    name: &'a str,
+    deps: &HashMap<&'a str, &'a [String]>,
+    path: &mut Vec<&'a str>,
+    done: &mut HashSet<&'a str>,
+) -> Result<(), PipelineErr> {
+    if done.contains(name) {
+        return Ok(());
+    }
+    if path.contains(&name) {
+        return Err(PipelineErr::Cycle(name.to_string()));
+    }
+    path.push(name);
+    for dep in deps.get(name).copied().unwrap_or_default() {
+        visit(dep, deps, path, done)?;
+    }
+    path.pop();
+    done.insert(name);
+    Ok(())
+}
+
+impl CustomStage {
+    /// Returns the state of the stage after its command finished.
+    pub fn evaluate(&self, exit_success: bool, output: &str) -> CustomStageState {
+        let passed = match &self.success {
+            SuccessCriterion::ExitCode => exit_success,
+            SuccessCriterion::OutputContains(text) => output.contains(text),
+            SuccessCriterion::OutputNotContains(text) => exit_success && !output.contains(text),
+        };
+        let summary = self.summary(output);
+        if passed {
+            CustomStageState::Success { summary }
+        } else {
+            CustomStageState::Failure { summary }
+        }
+    }
+
+    fn summary(&self, output: &str) -> Option<String> {
+        let parser = Regex::new(self.parser.as_ref()?).ok()?;
+        let captures = parser.captures(output)?;
+        let summary = captures.get(1).or_else(|| captures.get(0))?;
+        Some(summary.as_str().trim().to_string())
+    }
+}
+
+impl CustomStageState {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, CustomStageState::Failure { .. })
+    }
+}
+
+impl Display for CustomStageState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CustomStageState::Pending => "pending",
+                CustomStageState::Success { .. } => "success",
+                CustomStageState::Failure { .. } => "failure",
+                CustomStageState::Skipped { .. } => "skipped",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn pipeline_is_parsed_from_json() -> Result<()> {
+        // given
+        init_tracing();
+        let json = r#"{"stages": [
+            {"name": "fmt", "cmd": ["cargo", "fmt", "--check"]},
+            {
+                "name": "clippy",
+                "cmd": ["cargo", "clippy"],
+                "depends_on": ["check"],
+                "success": {"output_not_contains": "warning:"},
+                "parser": "(\\d+) warnings?"
+            }
+        ]}"#;
+
+        // when
+        let pipeline: PipelineDef = serde_json::from_str(json)?;
+
+        // then
+        assert_eq!(pipeline.stages[0].success, SuccessCriterion::ExitCode);
+        assert!(pipeline.stages[0].depends_on.is_empty());
+        assert_eq!(
+            pipeline.stages[1].success,
+            SuccessCriterion::OutputNotContains("warning:".into())
+        );
+        assert!(pipeline.validate().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_pipelines_are_rejected() {
+        // given
+        init_tracing();
+        let pipelines = [
+            vec![stage("tests", &[])],
+            vec![stage("", &[])],
+            vec![stage("../../etc", &[])],
+            vec![stage("fmt check", &[])],
+            vec![stage("fmt", &[]), stage("fmt", &[])],
+            vec![stage("fmt", &["unknown"])],
+            vec![stage("a", &["b"]), stage("b", &["c"]), stage("c", &["a"])],
+            vec![CustomStage {
+                cmd: Vec::new(),
+                ..stage("fmt", &[])
+            }],
+            vec![CustomStage {
+                parser: Some("(".into()),
+                ..stage("fmt", &[])
+            }],
+        ];
+
+        for stages in pipelines {
+            // then
+            assert!(PipelineDef { stages }.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn stage_is_evaluated_with_its_criterion_and_parser() {
+        // given
+        init_tracing();
+        let stage = CustomStage {
+            success: SuccessCriterion::OutputNotContains("warning:".into()),
+            parser: Some(r"(\d+) warnings?".into()),
+            ..stage("clippy", &["check"])
+        };
+
+        // when
+        let clean = stage.evaluate(true, "Finished");
+        let warned = stage.evaluate(true, "warning: unused\nwarning: 1 warning emitted");
+
+        // then
+        assert_eq!(clean, CustomStageState::Success { summary: None });
+        assert_eq!(
+            warned,
+            CustomStageState::Failure {
+                summary: Some("1".into())
+            }
+        );
+    }
+
+    fn stage(name: &str, depends_on: &[&str]) -> CustomStage {
+        CustomStage {
+            name: name.into(),
+            cmd: vec!["true".into()],
+            depends_on: depends_on.iter().map(ToString::to_string).collect(),
+            success: SuccessCriterion::ExitCode,
+            parser: None,
+            own_target_dir: false,
+            continue_on_failure: false,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::entities::file_coverage::{FileCoverage, LineRange};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Lines of the file which were added or modified compared to the base commit.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    pub path: String,
+    pub lines: BTreeSet<u32>,
+}
+
+impl ChangedLines {
+    pub fn new<S: Into<String>>(path: S, lines: BTreeSet<u32>) -> Self {
+        let path = path.into();
+        Self { path, lines }
+    }
+}
+
+/// Coverage of the lines changed compared to the `base` commit.
+///
+/// Only changed lines which are instrumented by the coverage tool are counted.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct DiffCoverage {
+    pub base: String,
+    pub covered: u64,
+    pub total: u64,
+    pub files: Vec<FileDiffCoverage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileDiffCoverage {
+    pub path: String,
+    pub covered: u64,
+    pub total: u64,
+    pub uncovered: Vec<LineRange>,
+}
+
+impl DiffCoverage {
+    pub fn calculate<S: Into<String>>(
+        base: S,
+        changes: &[ChangedLines],
+        coverage: &[FileCoverage],
+    ) -> Self {
+        let files: Vec<FileDiffCoverage> = changes
+            .iter()
+            .filter_map(|changed| {
+                let file = coverage.iter().find(|file| file.path == changed.path)?;
+                Some(file_diff_coverage(changed, file))
+            })
+            .filter(|file| file.total > 0)
+            .collect();
+        Self {
+            base: base.into(),
+            covered: files.iter().map(|file| file.covered).sum(),
+            total: files.iter().map(|file| file.total).sum(),
+            files,
+        }
+    }
+
+    /// Returns percentage of covered changed lines or `None` when no instrumented line changed.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percent(&self) -> Option<f32> {
+        if self.total == 0 {
+            return None;
+        }
+        Some(self.covered as f32 / self.total as f32 * 100.0)
+    }
+}
+
+fn file_diff_coverage(changed: &ChangedLines, file: &FileCoverage) -> FileDiffCoverage {
+    let instrumented: Vec<(u32, u64)> = changed
+        .lines
+        .iter()
+        .filter_map(|line| Some((*line, *file.lines.get(line)?)))
+        .collect();
+    let uncovered = instrumented
+        .iter()
+        .filter(|(_, hits)| *hits == 0)
+        .map(|(line, _)| *line);
+    FileDiffCoverage {
+        path: changed.path.clone(),
+        covered: instrumented.iter().filter(|(_, hits)| *hits > 0).count() as u64,
+        total: instrumented.len() as u64,
+        uncovered: LineRange::merge(uncovered),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn only_instrumented_changed_lines_are_counted() {
+        // given
+        init_tracing();
+        let changes = vec![
+            ChangedLines::new("src/lib.rs", BTreeSet::from([1, 2, 3, 5, 10])),
+            ChangedLines::new("README.md", BTreeSet::from([1])),
+        ];
+        let coverage = vec![FileCoverage::new(
+            "src/lib.rs",
+            BTreeMap::from([(1, 1), (2, 0), (3, 0), (4, 1), (5, 0), (6, 1)]),
+        )];
+
+        // when
+        let diff = DiffCoverage::calculate("HEAD", &changes, &coverage);
+
+        // then
+        assert_eq!(
+            diff,
+            DiffCoverage {
+                base: "HEAD".into(),
+                covered: 1,
+                total: 4,
+                files: vec![FileDiffCoverage {
+                    path: "src/lib.rs".into(),
+                    covered: 1,
+                    total: 4,
+                    uncovered: vec![LineRange::new(2, 3), LineRange::new(5, 5)],
+                }],
+            }
+        );
+        assert_eq!(diff.percent(), Some(25.0));
+    }
+
+    #[test]
+    fn there_is_no_percent_when_no_instrumented_line_changed() {
+        // given
+        init_tracing();
+        let changes = vec![ChangedLines::new("src/lib.rs", BTreeSet::from([7]))];
+        let coverage = vec![FileCoverage::new("src/lib.rs", BTreeMap::from([(1, 1)]))];
+
+        // when
+        let diff = DiffCoverage::calculate("HEAD", &changes, &coverage);
+
+        // then
+        assert!(diff.files.is_empty());
+        assert_eq!(diff.percent(), None);
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Coverage of a single source file.
+///
+/// `lines` maps the number of every instrumented line to the number of times it was hit.
+/// Lines which are not instrumented (comments, blank lines, etc.) are not present at all.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct FileCoverage {
+    pub path: String,
+    pub lines: BTreeMap<u32, u64>,
+}
+
+impl FileCoverage {
+    pub fn new<S: Into<String>>(path: S, lines: BTreeMap<u32, u64>) -> Self {
+        let path = path.into();
+        Self { path, lines }
+    }
+
+    pub fn covered(&self) -> u64 {
+        self.lines.values().filter(|hits| **hits > 0).count() as u64
+    }
+
+    pub fn total(&self) -> u64 {
+        self.lines.len() as u64
+    }
+
+    /// Returns ranges of consecutive instrumented lines which were not hit.
+    ///
+    /// Not instrumented lines don't break the range, so the range reflects what the user sees in
+    /// the editor.
+    pub fn uncovered(&self) -> Vec<LineRange> {
+        let mut ranges: Vec<LineRange> = Vec::new();
+        let mut prev_hit = true;
+        for (line, hits) in &self.lines {
+            if *hits > 0 {
+                prev_hit = true;
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(range) if !prev_hit => range.end = *line,
+                _ => ranges.push(LineRange::new(*line, *line)),
+            }
+            prev_hit = false;
+        }
+        ranges
+    }
+}
+
+/// Inclusive range of line numbers.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl LineRange {
+    pub fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+
+    /// Merges consecutive line numbers into ranges. Lines have to be sorted.
+    pub fn merge<I: IntoIterator<Item = u32>>(lines: I) -> Vec<LineRange> {
+        let mut ranges: Vec<LineRange> = Vec::new();
+        for line in lines {
+            match ranges.last_mut() {
+                Some(range) if range.end + 1 == line => range.end = line,
+                _ => ranges.push(LineRange::new(line, line)),
+            }
+        }
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn covered_and_total_lines_are_counted_from_instrumented_lines() {
+        // given
+        init_tracing();
+        let file = FileCoverage::new("src/main.rs", BTreeMap::from([(1, 1), (2, 0), (5, 3)]));
+
+        // then
+        assert_eq!(file.covered(), 2);
+        assert_eq!(file.total(), 3);
+    }
+
+    #[test]
+    fn consecutive_uncovered_lines_are_merged_into_ranges() {
+        // given
+        init_tracing();
+        let lines = BTreeMap::from([(1, 0), (2, 0), (4, 0), (5, 2), (6, 0), (8, 1), (9, 0)]);
+        let file = FileCoverage::new("src/main.rs", lines);
+
+        // when
+        let uncovered = file.uncovered();
+
+        // then
+        assert_eq!(
+            uncovered,
+            vec![
+                LineRange::new(1, 4),
+                LineRange::new(6, 6),
+                LineRange::new(9, 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn only_consecutive_lines_are_merged() {
+        // given
+        init_tracing();
+
+        // when
+        let ranges = LineRange::merge([1, 2, 3, 5, 7, 8]);
+
+        // then
+        assert_eq!(
+            ranges,
+            vec![
+                LineRange::new(1, 3),
+                LineRange::new(5, 5),
+                LineRange::new(7, 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn fully_covered_file_has_no_uncovered_ranges() {
+        // given
+        init_tracing();
+        let file = FileCoverage::new("src/main.rs", BTreeMap::from([(1, 1), (2, 1)]));
+
+        // then
+        assert!(file.uncovered().is_empty());
+    }
+}
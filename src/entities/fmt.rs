@@ -0,0 +1,103 @@
+use crate::result::FmtModeErr;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum FmtState {
+    #[default]
+    Pending,
+    Success,
+    /// Files which are not formatted, relative to the repo root.
+    Failure {
+        files: Vec<String>,
+        /// Whether the failure stops the tests, see `FmtMode::Blocking`.
+        #[serde(default)]
+        blocking: bool,
+    },
+}
+
+impl FmtState {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, FmtState::Failure { .. })
+    }
+
+    /// Failure which stopped the tests. Failure of the informational check doesn't stop anything.
+    pub fn is_blocking_failure(&self) -> bool {
+        matches!(self, FmtState::Failure { blocking: true, .. })
+    }
+}
+
+impl Display for FmtState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                FmtState::Pending => "pending",
+                FmtState::Success => "success",
+                FmtState::Failure { .. } => "failure",
+            }
+        )
+    }
+}
+
+/// Decides whether the formatting check is run and whether it stops the tests.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum FmtMode {
+    /// Formatting is not checked.
+    #[default]
+    Off,
+    /// Formatting is checked together with the check stage, its result doesn't stop the tests.
+    Informational,
+    /// Formatting is checked after the check stage passed, tests are run only when it passed.
+    Blocking,
+}
+
+impl FromStr for FmtMode {
+    type Err = FmtModeErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(FmtMode::Off),
+            "informational" => Ok(FmtMode::Informational),
+            "blocking" => Ok(FmtMode::Blocking),
+            _ => Err(FmtModeErr::Unknown(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn fmt_status_has_display_trait_implemented() {
+        // given
+        init_tracing();
+        let failure = FmtState::Failure {
+            files: vec!["src/main.rs".into()],
+            blocking: false,
+        };
+
+        // then
+        assert_eq!(FmtState::Pending.to_string(), "pending");
+        assert_eq!(FmtState::Success.to_string(), "success");
+        assert_eq!(failure.to_string(), "failure");
+    }
+
+    #[test]
+    fn fmt_mode_is_parsed() {
+        // given
+        init_tracing();
+
+        // then
+        assert_eq!("blocking".parse::<FmtMode>().ok(), Some(FmtMode::Blocking));
+        assert_eq!("off".parse::<FmtMode>().ok(), Some(FmtMode::Off));
+        assert!("strict".parse::<FmtMode>().is_err());
+    }
+}
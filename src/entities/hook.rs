@@ -0,0 +1,129 @@
+use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
+use crate::entities::timestamp::Timestamp;
+use crate::result::HookErr;
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Action run when the event with the given name is published.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hook {
+    pub event: String,
+    pub action: HookAction,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum HookAction {
+    /// Shell command, run in the repo root.
+    Command(String),
+    /// URL of the HTTP endpoint to which the event is posted.
+    Http(String),
+}
+
+impl FromStr for Hook {
+    type Err = HookErr;
+
+    /// Parses the hook from `<event>=<action>`, eg.: `tests-failed=./notify.sh`. Action starting
+    /// with `http://` is posted to, any other action is run as a shell command.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((event, action)) = s.split_once('=') else {
+            return Err(HookErr::Invalid(s.to_string()));
+        };
+        let (event, action) = (event.trim(), action.trim());
+        if event.is_empty() || action.is_empty() {
+            return Err(HookErr::Invalid(s.to_string()));
+        }
+        let action = if action.starts_with("http://") {
+            HookAction::Http(action.to_string())
+        } else {
+            HookAction::Command(action.to_string())
+        };
+        let event = event.to_string();
+        Ok(Self { event, action })
+    }
+}
+
+/// Event passed to the hook, as JSON on stdin of the command or in the body of the request.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HookPayload {
+    pub event: String,
+    /// Name of the custom stage the event is about.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<String>,
+    /// Identifier of the requested run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<RunId>,
+    /// Coverage in percents, when coverage passed or failed with a value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<f32>,
+    /// Name of the first failing test, when tests failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_test: Option<String>,
+    pub repo_root: RepoRoot,
+    pub timestamp: Timestamp,
+}
+
+impl HookPayload {
+    pub fn new<S: Into<String>>(event: S, repo_root: RepoRoot) -> Self {
+        let event = event.into();
+        let timestamp = Timestamp::now();
+        Self {
+            event,
+            stage: None,
+            run_id: None,
+            coverage: None,
+            failed_test: None,
+            repo_root,
+            timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn hooks_are_parsed_from_event_and_action() -> Result<()> {
+        // given
+        init_tracing();
+
+        // when
+        let cmd: Hook = "tests-failed=./notify.sh --loud".parse()?;
+        let http: Hook = " coverage-passed = http://localhost:9000/hook".parse()?;
+
+        // then
+        assert_eq!(
+            cmd,
+            Hook {
+                event: "tests-failed".into(),
+                action: HookAction::Command("./notify.sh --loud".into()),
+            }
+        );
+        assert_eq!(
+            http,
+            Hook {
+                event: "coverage-passed".into(),
+                action: HookAction::Http("http://localhost:9000/hook".into()),
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn hook_without_event_or_action_is_not_parsed() {
+        // given
+        init_tracing();
+
+        // then
+        assert!("tests-failed".parse::<Hook>().is_err());
+        assert!("=./notify.sh".parse::<Hook>().is_err());
+        assert!("tests-failed=".parse::<Hook>().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::StageTiming;
+use crate::entities::watcher::WatchedEvents;
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Upper bounds (in seconds) of the buckets of the stage duration histogram.
+const DURATION_BUCKETS: [f64; 11] = [
+    0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0,
+];
+
+/// Counters collected since chester was started, exported in Prometheus text format.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Metrics {
+    stages: HashMap<Stage, StageMetrics>,
+    watcher_events: u64,
+    ignored_events: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct StageMetrics {
+    runs: u64,
+    failures: u64,
+    durations: Histogram,
+}
+
+/// Histogram with cumulative buckets, as expected by Prometheus.
+#[derive(Debug, Clone, PartialEq)]
+struct Histogram {
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: [0; DURATION_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        for (bound, bucket) in DURATION_BUCKETS.iter().zip(self.buckets.iter_mut()) {
+            if secs <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += secs;
+        self.count += 1;
+    }
+}
+
+impl Metrics {
+    /// Counts the run of the stage. Stage which is still running is not counted.
+    pub fn stage_finished(&mut self, stage: Stage, timing: &StageTiming) {
+        let Some(duration) = timing.duration() else {
+            return;
+        };
+        let metrics = self.stages.entry(stage).or_default();
+        metrics.runs += 1;
+        if timing.failed {
+            metrics.failures += 1;
+        }
+        metrics.durations.observe(duration.as_secs_f64());
+    }
+
+    pub fn watched(&mut self, events: WatchedEvents) {
+        self.watcher_events += events.received;
+        self.ignored_events += events.ignored;
+    }
+
+    /// Renders the metrics in Prometheus text format, together with the current coverage.
+    pub fn to_prometheus(&self, coverage: Option<f32>) -> String {
+        let mut out = String::new();
+        let mut stages: Vec<_> = self.stages.iter().collect();
+        stages.sort_by_key(|(stage, _)| stage.to_string());
+
+        let name = "chester_stage_runs_total";
+        header(&mut out, name, "counter", "Finished runs of the stage.");
+        for (stage, metrics) in &stages {
+            let _ = writeln!(out, "{name}{{stage=\"{stage}\"}} {}", metrics.runs);
+        }
+        let name = "chester_stage_failures_total";
+        header(&mut out, name, "counter", "Failed runs of the stage.");
+        for (stage, metrics) in &stages {
+            let _ = writeln!(out, "{name}{{stage=\"{stage}\"}} {}", metrics.failures);
+        }
+        let name = "chester_stage_duration_seconds";
+        header(&mut out, name, "histogram", "Duration of the stage runs.");
+        for (stage, metrics) in &stages {
+            let Histogram {
+                buckets,
+                sum,
+                count,
+            } = &metrics.durations;
+            let labels = format!("stage=\"{stage}\"");
+            for (bound, bucket) in DURATION_BUCKETS.iter().zip(buckets) {
+                let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {bucket}");
+            }
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {count}");
+            let _ = writeln!(out, "{name}_sum{{{labels}}} {sum}");
+            let _ = writeln!(out, "{name}_count{{{labels}}} {count}");
+        }
+        if let Some(coverage) = coverage {
+            let name = "chester_coverage_percent";
+            header(&mut out, name, "gauge", "Current line coverage.");
+            let _ = writeln!(out, "{name} {coverage}");
+        }
+        let name = "chester_watcher_events_total";
+        header(&mut out, name, "counter", "Filesystem events received.");
+        let _ = writeln!(out, "{name} {}", self.watcher_events);
+        let name = "chester_watcher_ignored_events_total";
+        header(
+            &mut out,
+            name,
+            "counter",
+            "Filesystem events in ignored paths.",
+        );
+        let _ = writeln!(out, "{name} {}", self.ignored_events);
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn finished_runs_and_failures_are_counted() {
+        // given
+        init_tracing();
+        let mut metrics = Metrics::default();
+        let mut passed = StageTiming::start();
+        passed.finish(true);
+        let mut failed = StageTiming::start();
+        failed.finish(false);
+
+        // when
+        metrics.stage_finished(Stage::Tests, &passed);
+        metrics.stage_finished(Stage::Tests, &failed);
+        metrics.stage_finished(Stage::Tests, &StageTiming::start());
+
+        // then
+        let text = metrics.to_prometheus(None);
+        assert!(text.contains("chester_stage_runs_total{stage=\"tests\"} 2\n"));
+        assert!(text.contains("chester_stage_failures_total{stage=\"tests\"} 1\n"));
+        assert!(
+            text.contains("chester_stage_duration_seconds_bucket{stage=\"tests\",le=\"0.1\"} 2\n")
+        );
+        assert!(text.contains("chester_stage_duration_seconds_count{stage=\"tests\"} 2\n"));
+        assert!(!text.contains("chester_coverage_percent"));
+    }
+
+    #[test]
+    fn watcher_events_and_coverage_are_exported() {
+        // given
+        init_tracing();
+        let mut metrics = Metrics::default();
+
+        // when
+        metrics.watched(WatchedEvents {
+            received: 5,
+            ignored: 2,
+        });
+        metrics.watched(WatchedEvents {
+            received: 1,
+            ignored: 0,
+        });
+
+        // then
+        let text = metrics.to_prometheus(Some(75.5));
+        assert!(text.contains("# TYPE chester_coverage_percent gauge\n"));
+        assert!(text.contains("chester_coverage_percent 75.5\n"));
+        assert!(text.contains("chester_watcher_events_total 6\n"));
+        assert!(text.contains("chester_watcher_ignored_events_total 2\n"));
+    }
+}
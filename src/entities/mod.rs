@@ -1,5 +1,26 @@
 pub mod check;
 pub mod coverage;
+pub mod coverage_backend;
+pub mod coverage_report;
+pub mod coverage_summary;
+pub mod coverage_trigger;
+pub mod custom_stage;
+pub mod diff_coverage;
+pub mod file_coverage;
+pub mod fmt;
+pub mod hook;
 pub mod ignored_path;
+pub mod metrics;
+pub mod notification;
+pub mod pipeline_run;
+pub mod provenance;
+pub mod quality;
 pub mod repo_root;
+pub mod run_id;
+pub mod stage;
+pub mod stage_timing;
+pub mod state_snapshot;
+pub mod stats;
 pub mod tests;
+pub mod timestamp;
+pub mod watcher;
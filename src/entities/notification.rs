@@ -0,0 +1,100 @@
+use crate::result::NotifyPolicyErr;
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Desktop notification about the result of the pipeline.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn new<S: Into<String>, B: Into<String>>(summary: S, body: B) -> Self {
+        let summary = summary.into();
+        let body = body.into();
+        Self { summary, body }
+    }
+}
+
+/// Policy deciding when the notifications are sent.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum NotifyPolicy {
+    /// Notifications are not sent.
+    Off,
+    /// Only when the pipeline goes from passing to failing and back.
+    #[default]
+    Transitions,
+    /// On every failed stage and when the pipeline passes again.
+    Failures,
+}
+
+impl FromStr for NotifyPolicy {
+    type Err = NotifyPolicyErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(NotifyPolicy::Off),
+            "transitions" => Ok(NotifyPolicy::Transitions),
+            "failures" => Ok(NotifyPolicy::Failures),
+            _ => Err(NotifyPolicyErr::Unknown(s.to_string())),
+        }
+    }
+}
+
+impl Display for NotifyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                NotifyPolicy::Off => "off",
+                NotifyPolicy::Transitions => "transitions",
+                NotifyPolicy::Failures => "failures",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use anyhow::Result;
+
+    #[test]
+    fn notify_policy_can_be_parsed_from_its_name() -> Result<()> {
+        // given
+        init_tracing();
+        let policies = [
+            NotifyPolicy::Off,
+            NotifyPolicy::Transitions,
+            NotifyPolicy::Failures,
+        ];
+
+        for policy in policies {
+            // when
+            let parsed: NotifyPolicy = policy.to_string().parse()?;
+
+            // then
+            assert_eq!(parsed, policy);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_notify_policy_is_not_parsed() {
+        // given
+        init_tracing();
+
+        // then
+        assert!(matches!(
+            "always".parse::<NotifyPolicy>(),
+            Err(NotifyPolicyErr::Unknown(_))
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::entities::check::CheckState;
+use crate::entities::coverage::CoverageState;
+use crate::entities::quality::HalsteadSummary;
+use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
+use crate::entities::tests::TestsState;
+use crate::entities::timestamp::Timestamp;
+
+use serde::{Deserialize, Serialize};
+
+/// What started the pipeline run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    /// Change of the files or of the repo root.
+    Change,
+    /// Run requested via the API.
+    Manual,
+}
+
+/// Single execution of the pipeline.
+///
+/// Stages which were not executed during the run (for example tests, when the check failed) have
+/// no result.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PipelineRun {
+    pub id: RunId,
+    pub trigger: Trigger,
+    pub repo_root: RepoRoot,
+    pub commit: Option<String>,
+    pub started_at: Timestamp,
+    pub finished_at: Option<Timestamp>,
+    pub check: Option<CheckState>,
+    pub tests: Option<TestsState>,
+    pub coverage: Option<CoverageState>,
+    /// Halstead metrics of the code on which the pipeline was run.
+    #[serde(default)]
+    pub halstead: Option<HalsteadSummary>,
+}
+
+impl PipelineRun {
+    pub fn new(id: RunId, trigger: Trigger, repo_root: RepoRoot, commit: Option<String>) -> Self {
+        Self {
+            id,
+            trigger,
+            repo_root,
+            commit,
+            started_at: Timestamp::now(),
+            finished_at: None,
+            check: None,
+            tests: None,
+            coverage: None,
+            halstead: None,
+        }
+    }
+
+    pub fn finish(&mut self) {
+        self.finished_at = Some(Timestamp::now());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::entities::stage::Stage;
+use crate::entities::timestamp::Timestamp;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Identifies the content of the source tree: head commit and hash of uncommitted changes.
+///
+/// Both are missing when the repository is not a git repository. Hash of changes is missing when
+/// there are no uncommitted changes.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct Fingerprint {
+    pub commit: Option<String>,
+    pub dirty: Option<String>,
+}
+
+impl Fingerprint {
+    pub fn new(commit: Option<String>, dirty: Option<String>) -> Self {
+        Self { commit, dirty }
+    }
+
+    fn is_known(&self) -> bool {
+        self.commit.is_some() || self.dirty.is_some()
+    }
+}
+
+/// Fingerprint of the source tree taken when a change was detected or a run was requested.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct TreeSnapshot {
+    pub taken_at: Timestamp,
+    pub fingerprint: Fingerprint,
+}
+
+impl TreeSnapshot {
+    pub fn new(fingerprint: Fingerprint) -> Self {
+        let taken_at = Timestamp::now();
+        Self {
+            taken_at,
+            fingerprint,
+        }
+    }
+}
+
+/// Result whose source tree is tracked: of a built-in stage, the formatting check, the quality
+/// metrics or a custom stage. Custom stage can share the name with the formatting check, so it's
+/// kept apart.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum TrackedResult {
+    Stage(Stage),
+    Fmt,
+    Quality,
+    Custom(String),
+}
+
+impl Display for TrackedResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackedResult::Stage(stage) => write!(f, "{stage}"),
+            TrackedResult::Fmt => write!(f, "fmt"),
+            TrackedResult::Quality => write!(f, "quality"),
+            TrackedResult::Custom(name) => write!(f, "stages/{name}"),
+        }
+    }
+}
+
+/// Source tree on which the stage was run and when it finished.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct StageProvenance {
+    pub finished_at: Timestamp,
+    pub tree: TreeSnapshot,
+}
+
+impl StageProvenance {
+    pub fn new(tree: TreeSnapshot) -> Self {
+        let finished_at = Timestamp::now();
+        Self { finished_at, tree }
+    }
+
+    /// Checks if the result was produced from other tree than the `current` one.
+    ///
+    /// When the fingerprint of the tree is unknown, the result is stale if the tree was changed
+    /// after the stage started.
+    pub fn is_stale(&self, current: &TreeSnapshot) -> bool {
+        if current.fingerprint.is_known() {
+            self.tree.fingerprint != current.fingerprint
+        } else {
+            self.tree.taken_at < current.taken_at
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn result_of_other_tree_is_stale() {
+        // given
+        init_tracing();
+        let tree = TreeSnapshot::new(Fingerprint::new(Some("abc".into()), None));
+        let provenance = StageProvenance::new(tree);
+
+        // when
+        let current = TreeSnapshot::new(Fingerprint::new(Some("abc".into()), Some("123".into())));
+
+        // then
+        assert!(provenance.is_stale(&current));
+    }
+
+    #[test]
+    fn result_of_the_same_tree_is_not_stale_even_if_it_changed_in_between() {
+        // given
+        init_tracing();
+        let fingerprint = Fingerprint::new(Some("abc".into()), Some("123".into()));
+        let provenance = StageProvenance::new(TreeSnapshot::new(fingerprint.clone()));
+        thread::sleep(Duration::from_millis(5));
+
+        // when
+        let current = TreeSnapshot::new(fingerprint);
+
+        // then
+        assert!(!provenance.is_stale(&current));
+    }
+
+    #[test]
+    fn without_fingerprint_result_is_stale_when_tree_changed_after_it_started() {
+        // given
+        init_tracing();
+        let tree = TreeSnapshot::new(Fingerprint::default());
+        let provenance = StageProvenance::new(tree.clone());
+        thread::sleep(Duration::from_millis(5));
+
+        // when
+        let current = TreeSnapshot::new(Fingerprint::default());
+
+        // then
+        assert!(!provenance.is_stale(&tree));
+        assert!(provenance.is_stale(&current));
+    }
+}
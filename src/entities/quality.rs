@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+
+/// Counts of operators and operands in the tokens of the code, base of the Halstead metrics.
+///
+/// Volume is the size of the code, difficulty tells how hard it's to understand and effort is the
+/// effort needed to write or maintain it.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Halstead {
+    pub distinct_operators: u32,
+    pub distinct_operands: u32,
+    pub total_operators: u32,
+    pub total_operands: u32,
+}
+
+impl Halstead {
+    pub fn volume(&self) -> f32 {
+        let vocabulary = self.distinct_operators + self.distinct_operands;
+        let length = self.total_operators + self.total_operands;
+        if vocabulary == 0 {
+            return 0.0;
+        }
+        length as f32 * (vocabulary as f32).log2()
+    }
+
+    pub fn difficulty(&self) -> f32 {
+        if self.distinct_operands == 0 {
+            return 0.0;
+        }
+        self.distinct_operators as f32 / 2.0 * self.total_operands as f32
+            / self.distinct_operands as f32
+    }
+
+    pub fn effort(&self) -> f32 {
+        self.difficulty() * self.volume()
+    }
+}
+
+/// Halstead metrics of the whole repo: volume and effort of all the files together, difficulty
+/// is the average of the files weighted by their volume, so the effort is still the difficulty
+/// times the volume.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct HalsteadSummary {
+    pub volume: f32,
+    pub difficulty: f32,
+    pub effort: f32,
+}
+
+/// Maintainability index scaled to 0-100, the higher the better.
+///
+/// It's calculated from Halstead volume, cyclomatic complexity and lines of code as
+/// `(171 - 5.2 * ln(volume) - 0.23 * complexity - 16.2 * ln(loc)) * 100 / 171`.
+pub fn maintainability_index(volume: f32, cyclomatic: u32, loc: u32) -> f32 {
+    let ln = |val: f32| val.max(1.0).ln();
+    let raw = 171.0 - 5.2 * ln(volume) - 0.23 * cyclomatic as f32 - 16.2 * ln(loc as f32);
+    (raw * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct FunctionQuality {
+    /// Name of the function, methods are prefixed with the name of the type, eg. `Config::new`.
+    pub name: String,
+    pub line: u32,
+    pub loc: u32,
+    pub cyclomatic: u32,
+    pub maintainability: f32,
+    #[serde(default)]
+    pub halstead: Halstead,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct FileQuality {
+    /// Path of the file relative to the repo root.
+    pub path: String,
+    pub loc: u32,
+    /// Sum of the cyclomatic complexity of the functions in the file.
+    pub cyclomatic: u32,
+    pub maintainability: f32,
+    #[serde(default)]
+    pub halstead: Halstead,
+    pub functions: Vec<FunctionQuality>,
+}
+
+impl FileQuality {
+    /// Path of the module in the crate, eg. `entities::quality` for `src/entities/quality.rs`.
+    /// Crate roots (`main.rs`, `lib.rs`) are `crate`. Modules of the crates in a workspace are
+    /// prefixed with the directory of the crate, eg. `core::parser` for
+    /// `crates/core/src/parser.rs`, so they don't collide with each other.
+    pub fn module(&self) -> String {
+        let path = self.path.trim_end_matches(".rs");
+        let (krate, path) = match path.rsplit_once("src/") {
+            Some((dir, path)) => (dir.trim_end_matches('/').rsplit('/').next(), path),
+            None => (None, path),
+        };
+        let mut segments: Vec<_> = path.split('/').collect();
+        if matches!(segments.last(), Some(&"mod" | &"main" | &"lib")) {
+            segments.pop();
+        }
+        match krate.filter(|krate| !krate.is_empty()) {
+            Some(krate) => segments.insert(0, krate),
+            None if segments.is_empty() => return "crate".to_string(),
+            None => {}
+        }
+        segments.join("::")
+    }
+}
+
+/// Quality metrics of all the Rust files in the repo.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct QualityReport {
+    pub files: Vec<FileQuality>,
+}
+
+impl QualityReport {
+    pub fn new(mut files: Vec<FileQuality>) -> Self {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { files }
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = (&FileQuality, &FunctionQuality)> {
+        self.files
+            .iter()
+            .flat_map(|file| file.functions.iter().map(move |function| (file, function)))
+    }
+
+    pub fn avg_cyclomatic(&self) -> Option<f32> {
+        average(
+            self.functions()
+                .map(|(_, function)| function.cyclomatic as f32),
+        )
+    }
+
+    pub fn max_cyclomatic(&self) -> Option<u32> {
+        self.functions()
+            .map(|(_, function)| function.cyclomatic)
+            .max()
+    }
+
+    pub fn avg_maintainability(&self) -> Option<f32> {
+        average(self.files.iter().map(|file| file.maintainability))
+    }
+
+    /// Functions with the highest cyclomatic complexity, the most complex first.
+    pub fn most_complex_functions(&self, n: usize) -> Vec<(&FileQuality, &FunctionQuality)> {
+        let mut functions: Vec<_> = self.functions().collect();
+        functions.sort_by_key(|(_, function)| Reverse(function.cyclomatic));
+        functions.truncate(n);
+        functions
+    }
+
+    pub fn halstead(&self) -> HalsteadSummary {
+        let files = || self.files.iter().map(|file| file.halstead);
+        let volume: f32 = files().map(|halstead| halstead.volume()).sum();
+        let effort: f32 = files().map(|halstead| halstead.effort()).sum();
+        let difficulty = if volume > 0.0 { effort / volume } else { 0.0 };
+        HalsteadSummary {
+            volume,
+            difficulty,
+            effort,
+        }
+    }
+
+    /// Files with the lowest maintainability index, the least maintainable first.
+    pub fn least_maintainable_files(&self, n: usize) -> Vec<&FileQuality> {
+        let mut files: Vec<_> = self.files.iter().collect();
+        files.sort_by(|a, b| {
+            a.maintainability
+                .partial_cmp(&b.maintainability)
+                .unwrap_or(Ordering::Equal)
+        });
+        files.truncate(n);
+        files
+    }
+}
+
+fn average<I: Iterator<Item = f32>>(values: I) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), val| (sum + val, count + 1));
+    (count > 0).then(|| sum / count as f32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::testingtools::quality_analyzer::halstead;
+
+    #[test]
+    fn halstead_volume_is_calculated_from_length_and_vocabulary() {
+        // given
+        init_tracing();
+        let halstead = halstead();
+
+        // then
+        assert_eq!(halstead.volume(), 48.0);
+        assert_eq!(halstead.difficulty(), 3.0);
+        assert_eq!(halstead.effort(), 144.0);
+        assert_eq!(Halstead::default().volume(), 0.0);
+        assert_eq!(Halstead::default().difficulty(), 0.0);
+    }
+
+    #[test]
+    fn module_path_is_taken_from_file_path() {
+        // given
+        init_tracing();
+        let module = |path: &str| {
+            FileQuality {
+                path: path.into(),
+                ..FileQuality::default()
+            }
+            .module()
+        };
+
+        // then
+        assert_eq!(module("src/entities/quality.rs"), "entities::quality");
+        assert_eq!(module("src/entities/mod.rs"), "entities");
+        assert_eq!(module("src/main.rs"), "crate");
+        assert_eq!(module("crates/core/src/lib.rs"), "core");
+        assert_eq!(module("crates/cli/src/lib.rs"), "cli");
+        assert_eq!(module("crates/core/src/parser/mod.rs"), "core::parser");
+        assert_eq!(module("build.rs"), "build");
+    }
+
+    #[test]
+    fn halstead_of_the_repo_sums_volume_and_effort_of_files() {
+        // given
+        init_tracing();
+        let halstead = halstead();
+        let file = FileQuality {
+            halstead,
+            ..FileQuality::default()
+        };
+        let report = QualityReport::new(vec![file.clone(), file]);
+
+        // when
+        let summary = report.halstead();
+
+        // then
+        assert_eq!(
+            summary,
+            HalsteadSummary {
+                volume: 96.0,
+                difficulty: 3.0,
+                effort: 288.0,
+            }
+        );
+    }
+
+    #[test]
+    fn difficulty_of_the_repo_is_weighted_by_volume_of_files() {
+        // given
+        init_tracing();
+        let small = Halstead {
+            distinct_operators: 2,
+            distinct_operands: 2,
+            total_operators: 2,
+            total_operands: 2,
+        };
+        let files = [halstead(), small].map(|halstead| FileQuality {
+            halstead,
+            ..FileQuality::default()
+        });
+        let report = QualityReport::new(files.to_vec());
+
+        // when
+        let summary = report.halstead();
+
+        // then
+        assert_eq!(summary.volume, 56.0);
+        assert_eq!(summary.effort, 152.0);
+        assert_eq!(summary.difficulty, 152.0 / 56.0);
+    }
+
+    #[test]
+    fn maintainability_index_drops_with_size_and_complexity() {
+        // given
+        init_tracing();
+
+        // when
+        let small = maintainability_index(20.0, 1, 3);
+        let big = maintainability_index(2000.0, 15, 120);
+
+        // then
+        assert!(small > big);
+        assert_eq!(maintainability_index(0.0, 0, 0), 100.0);
+        assert_eq!(maintainability_index(f32::MAX, 1000, u32::MAX), 0.0);
+    }
+
+    #[test]
+    fn worst_offenders_are_listed_first() {
+        // given
+        init_tracing();
+        let report = QualityReport::new(vec![
+            file("src/main.rs", 80.0, &[("main", 1)]),
+            file("src/lib.rs", 40.0, &[("parse", 7), ("run", 3)]),
+        ]);
+
+        // when
+        let functions = report.most_complex_functions(2);
+        let files = report.least_maintainable_files(1);
+
+        // then
+        let names: Vec<_> = functions.iter().map(|(_, f)| f.name.as_str()).collect();
+        assert_eq!(names, vec!["parse", "run"]);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(report.max_cyclomatic(), Some(7));
+        assert_eq!(report.avg_maintainability(), Some(60.0));
+        assert_eq!(QualityReport::default().avg_cyclomatic(), None);
+    }
+
+    fn file(path: &str, maintainability: f32, functions: &[(&str, u32)]) -> FileQuality {
+        let functions: Vec<_> = functions
+            .iter()
+            .map(|(name, cyclomatic)| FunctionQuality {
+                name: name.to_string(),
+                cyclomatic: *cyclomatic,
+                ..FunctionQuality::default()
+            })
+            .collect();
+        FileQuality {
+            path: path.into(),
+            cyclomatic: functions.iter().map(|f| f.cyclomatic).sum(),
+            maintainability,
+            functions,
+            ..FileQuality::default()
+        }
+    }
+}
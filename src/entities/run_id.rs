@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Identifier of a single pipeline run.
+///
+/// It's derived from the time the run was requested, so identifiers created later are greater.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct RunId {
+    id: String,
+}
+
+impl RunId {
+    pub fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let id = format!("{nanos:x}");
+        Self { id }
+    }
+}
+
+impl Display for RunId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn consecutive_run_ids_are_different() {
+        // given
+        init_tracing();
+
+        // when
+        let first = RunId::new();
+        let second = RunId::new();
+
+        // then
+        assert_ne!(first, second);
+    }
+}
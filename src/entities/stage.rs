@@ -0,0 +1,90 @@
+use crate::result::StageNameErr;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Stage of the pipeline. A run can be started from any stage but the index refresh.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Check,
+    Tests,
+    Index,
+    Coverage,
+}
+
+impl Stage {
+    /// Index refresh is run only after the tests, so the run can't be started from it.
+    pub fn is_triggerable(&self) -> bool {
+        *self != Stage::Index
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Stage::Check => "check",
+                Stage::Tests => "tests",
+                Stage::Index => "index",
+                Stage::Coverage => "coverage",
+            }
+        )
+    }
+}
+
+impl FromStr for Stage {
+    type Err = StageNameErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "check" => Ok(Stage::Check),
+            "tests" => Ok(Stage::Tests),
+            "index" => Ok(Stage::Index),
+            "coverage" => Ok(Stage::Coverage),
+            _ => Err(StageNameErr::Unknown(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn stage_has_display_trait_implemented() {
+        // given
+        init_tracing();
+
+        // then
+        assert_eq!(Stage::Check.to_string(), "check");
+        assert_eq!(Stage::Tests.to_string(), "tests");
+        assert_eq!(Stage::Index.to_string(), "index");
+        assert_eq!(Stage::Coverage.to_string(), "coverage");
+    }
+
+    #[test]
+    fn stage_is_parsed_from_its_name() {
+        // given
+        init_tracing();
+
+        // then
+        assert_eq!("tests".parse::<Stage>().ok(), Some(Stage::Tests));
+        assert!("unknown".parse::<Stage>().is_err());
+    }
+
+    #[test]
+    fn run_can_not_be_started_from_index_refresh() {
+        // given
+        init_tracing();
+
+        // then
+        assert!(Stage::Check.is_triggerable());
+        assert!(!Stage::Index.is_triggerable());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::entities::timestamp::Timestamp;
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Number of the latest runs of the stage taken into account in duration statistics.
+const DURATIONS_WINDOW: usize = 20;
+
+/// When the last run of the stage started, finished and if it failed. Stage which is still
+/// running has no finish time.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct StageTiming {
+    pub started_at: Timestamp,
+    pub finished_at: Option<Timestamp>,
+    #[serde(default)]
+    pub failed: bool,
+}
+
+impl StageTiming {
+    pub fn start() -> Self {
+        Self {
+            started_at: Timestamp::now(),
+            finished_at: None,
+            failed: false,
+        }
+    }
+
+    pub fn finish(&mut self, passed: bool) {
+        self.finished_at = Some(Timestamp::now());
+        self.failed = !passed;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.finished_at.is_none()
+    }
+
+    /// Returns how long the stage was running, if it finished.
+    pub fn duration(&self) -> Option<Duration> {
+        self.finished_at
+            .map(|finished_at| finished_at.duration_since(self.started_at))
+    }
+}
+
+/// Durations of the latest runs of the stage.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DurationStats {
+    durations: VecDeque<Duration>,
+}
+
+impl DurationStats {
+    /// Adds duration of the finished run, forgetting the oldest one when the window is full.
+    pub fn record(&mut self, duration: Duration) {
+        if self.durations.len() == DURATIONS_WINDOW {
+            self.durations.pop_front();
+        }
+        self.durations.push_back(duration);
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.durations.len())
+            .ok()
+            .filter(|n| *n > 0)?;
+        Some(self.durations.iter().sum::<Duration>() / count)
+    }
+
+    /// Returns the 95th percentile, calculated with the nearest-rank method.
+    pub fn p95(&self) -> Option<Duration> {
+        let mut sorted: Vec<Duration> = self.durations.iter().copied().collect();
+        sorted.sort();
+        let rank = (sorted.len() * 95).div_ceil(100);
+        sorted.get(rank.checked_sub(1)?).copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    use std::thread;
+
+    #[test]
+    fn started_stage_is_running_until_it_finishes() {
+        // given
+        init_tracing();
+        let mut timing = StageTiming::start();
+        assert!(timing.is_running());
+        assert_eq!(timing.duration(), None);
+        thread::sleep(Duration::from_millis(5));
+
+        // when
+        timing.finish(true);
+
+        // then
+        assert!(!timing.is_running());
+        assert!(timing.duration() >= Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn duration_stats_are_calculated_from_latest_runs() {
+        // given
+        init_tracing();
+        let mut stats = DurationStats::default();
+        assert_eq!(stats.average(), None);
+        assert_eq!(stats.p95(), None);
+
+        // when
+        for secs in 1..=30 {
+            stats.record(Duration::from_secs(secs));
+        }
+
+        // then
+        assert_eq!(stats.average(), Some(Duration::from_millis(20_500)));
+        assert_eq!(stats.p95(), Some(Duration::from_secs(29)));
+    }
+}
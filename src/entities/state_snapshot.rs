@@ -0,0 +1,268 @@
+use crate::entities::check::CheckState;
+use crate::entities::coverage::CoverageState;
+use crate::entities::custom_stage::CustomStageState;
+use crate::entities::fmt::FmtState;
+use crate::entities::metrics::Metrics;
+use crate::entities::provenance::{StageProvenance, TrackedResult, TreeSnapshot};
+use crate::entities::repo_root::RepoRoot;
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::{DurationStats, StageTiming};
+use crate::entities::tests::TestsState;
+use crate::entities::watcher::WatcherState;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Overall health of the pipeline.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// All stages passed on the current source tree.
+    Green,
+    /// No stage failed, but some results are not known yet, are outdated, coverage regressed
+    /// to the level of a warning or the informational formatting check failed.
+    Yellow,
+    /// At least one stage failed.
+    Red,
+}
+
+/// State of all the stages read at once, so they are consistent with each other.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StateSnapshot {
+    pub repo_root: RepoRoot,
+    pub watcher: WatcherState,
+    pub check: CheckState,
+    pub fmt: FmtState,
+    pub tests: TestsState,
+    pub coverage: CoverageState,
+    pub source_tree: Option<TreeSnapshot>,
+    pub provenances: HashMap<TrackedResult, StageProvenance>,
+    pub timings: HashMap<Stage, StageTiming>,
+    pub durations: HashMap<Stage, DurationStats>,
+    pub metrics: Metrics,
+    pub custom_stages: HashMap<String, CustomStageState>,
+}
+
+impl StateSnapshot {
+    /// Returns the stage which is running now. When the pipeline was restarted, only the latest
+    /// started stage is taken into account.
+    pub fn running(&self) -> Option<Stage> {
+        self.timings
+            .iter()
+            .filter(|(_, timing)| timing.is_running())
+            .max_by_key(|(_, timing)| timing.started_at)
+            .map(|(stage, _)| *stage)
+    }
+
+    /// Result is stale when it was produced on other source tree than the current one. Result
+    /// which was not produced yet is not stale.
+    pub fn is_stale(&self, result: &TrackedResult) -> bool {
+        match (self.provenances.get(result), &self.source_tree) {
+            (Some(provenance), Some(tree)) => provenance.is_stale(tree),
+            _ => false,
+        }
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        let failed = self.check == CheckState::Failure
+            || self.fmt.is_blocking_failure()
+            || self.tests == TestsState::Failure
+            || self.coverage.is_failure()
+            || self
+                .custom_stages
+                .values()
+                .any(CustomStageState::is_failure);
+        if failed {
+            return Verdict::Red;
+        }
+        // NOTE: Pending formatting check is not waited for, it's not run at all when turned off.
+        // Pending coverage isn't either, it's not run after every change accordingly to the
+        // coverage trigger, while running coverage is caught as the running stage.
+        let pending = self.check == CheckState::Pending
+            || self.tests == TestsState::Pending
+            || self
+                .custom_stages
+                .values()
+                .any(|state| *state == CustomStageState::Pending);
+        // NOTE: Stage is skipped when an earlier stage failed, so its previous result can't be
+        // trusted.
+        let skipped = matches!(self.tests, TestsState::Skipped { .. })
+            || matches!(self.coverage, CoverageState::Skipped { .. })
+            || self
+                .custom_stages
+                .values()
+                .any(|state| matches!(state, CustomStageState::Skipped { .. }));
+        let regressed = matches!(self.coverage, CoverageState::Regressed { .. });
+        let unformatted = self.fmt.is_failure();
+        let stale = [Stage::Check, Stage::Tests, Stage::Coverage]
+            .into_iter()
+            .any(|stage| self.is_stale(&TrackedResult::Stage(stage)));
+        if pending || skipped || regressed || unformatted || stale || self.running().is_some() {
+            Verdict::Yellow
+        } else {
+            Verdict::Green
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::coverage::Severity;
+    use crate::entities::provenance::Fingerprint;
+
+    #[test]
+    fn all_stages_passed_on_current_tree_is_green() {
+        // given
+        init_tracing();
+        let snapshot = passed_snapshot();
+
+        // then
+        assert_eq!(snapshot.verdict(), Verdict::Green);
+    }
+
+    #[test]
+    fn any_failed_stage_is_red() {
+        // given
+        init_tracing();
+        let snapshots = [
+            StateSnapshot {
+                check: CheckState::Failure,
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                tests: TestsState::Failure,
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                fmt: FmtState::Failure {
+                    files: vec!["src/main.rs".into()],
+                    blocking: true,
+                },
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                coverage: CoverageState::BelowThreshold {
+                    actual: 10.0,
+                    required: 80.0,
+                    file: None,
+                },
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                custom_stages: HashMap::from([(
+                    "fmt".into(),
+                    CustomStageState::Failure { summary: None },
+                )]),
+                ..passed_snapshot()
+            },
+        ];
+
+        for snapshot in snapshots {
+            // then
+            assert_eq!(snapshot.verdict(), Verdict::Red);
+        }
+    }
+
+    #[test]
+    fn coverage_which_was_not_run_is_green() {
+        // given
+        init_tracing();
+        let snapshot = StateSnapshot {
+            coverage: CoverageState::Pending,
+            ..passed_snapshot()
+        };
+
+        // then
+        assert_eq!(snapshot.verdict(), Verdict::Green);
+    }
+
+    #[test]
+    fn running_coverage_is_yellow() {
+        // given
+        init_tracing();
+        let snapshot = StateSnapshot {
+            coverage: CoverageState::Pending,
+            timings: HashMap::from([(Stage::Coverage, StageTiming::start())]),
+            ..passed_snapshot()
+        };
+
+        // then
+        assert_eq!(snapshot.verdict(), Verdict::Yellow);
+    }
+
+    #[test]
+    fn pending_skipped_regressed_unformatted_or_stale_result_is_yellow() {
+        // given
+        init_tracing();
+        let current = TreeSnapshot::new(Fingerprint::new(Some("new".into()), None));
+        let old = TreeSnapshot::new(Fingerprint::new(Some("old".into()), None));
+        let snapshots = [
+            StateSnapshot {
+                tests: TestsState::Pending,
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                coverage: CoverageState::Skipped {
+                    reason: "tests failed".into(),
+                },
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                coverage: CoverageState::Regressed {
+                    actual: 70.0,
+                    baseline: 75.0,
+                    severity: Severity::Warning,
+                },
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                fmt: FmtState::Failure {
+                    files: vec!["src/main.rs".into()],
+                    blocking: false,
+                },
+                ..passed_snapshot()
+            },
+            StateSnapshot {
+                source_tree: Some(current),
+                provenances: HashMap::from([(
+                    TrackedResult::Stage(Stage::Tests),
+                    StageProvenance::new(old),
+                )]),
+                ..passed_snapshot()
+            },
+        ];
+
+        for snapshot in snapshots {
+            // then
+            assert_eq!(snapshot.verdict(), Verdict::Yellow);
+        }
+    }
+
+    #[test]
+    fn latest_started_unfinished_stage_is_running() {
+        // given
+        init_tracing();
+        let mut check = StageTiming::start();
+        check.finish(true);
+        let snapshot = StateSnapshot {
+            timings: HashMap::from([(Stage::Check, check), (Stage::Tests, StageTiming::start())]),
+            ..passed_snapshot()
+        };
+
+        // then
+        assert_eq!(snapshot.running(), Some(Stage::Tests));
+        assert_eq!(snapshot.verdict(), Verdict::Yellow);
+    }
+
+    fn passed_snapshot() -> StateSnapshot {
+        StateSnapshot {
+            check: CheckState::Success,
+            tests: TestsState::Success,
+            coverage: CoverageState::Success(80.0),
+            ..StateSnapshot::default()
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+
+/// Basic statistics of the Rust code in the repo.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProjectStats {
+    pub files: u32,
+    /// Lines with code, excluding blank and comment lines.
+    pub sloc: u32,
+    pub comment_lines: u32,
+    pub blank_lines: u32,
+    /// Tests listed by the tests index, there are none until the tests were listed.
+    pub tests: Option<u32>,
+    pub unsafe_blocks: u32,
+    /// Comments containing `TODO` or `FIXME`.
+    pub todos: u32,
+}
+
+impl ProjectStats {
+    /// Counts the lines of the single file. Line with any code is counted as code, also when it
+    /// has a comment. `//` and `/*` inside string and char literals don't start a comment.
+    pub fn count_lines(source: &str) -> Self {
+        let mut stats = Self {
+            files: 1,
+            ..Self::default()
+        };
+        let mut ctx = Context::Code;
+        for line in source.lines() {
+            let scanned = ScannedLine::scan(line, &mut ctx);
+            if scanned.code {
+                stats.sloc += 1;
+            } else if scanned.comment {
+                stats.comment_lines += 1;
+            } else {
+                stats.blank_lines += 1;
+            }
+            if scanned.todo {
+                stats.todos += 1;
+            }
+        }
+        stats
+    }
+}
+
+impl AddAssign for ProjectStats {
+    fn add_assign(&mut self, other: Self) {
+        self.files += other.files;
+        self.sloc += other.sloc;
+        self.comment_lines += other.comment_lines;
+        self.blank_lines += other.blank_lines;
+        self.unsafe_blocks += other.unsafe_blocks;
+        self.todos += other.todos;
+    }
+}
+
+/// Where the previous line ended, so the next line is scanned in the same context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Code,
+    /// Block comment with the nesting depth, block comments in Rust can be nested.
+    BlockComment(u32),
+    Str,
+    /// Raw string with the number of `#` which close it.
+    RawStr(usize),
+}
+
+#[derive(Debug, Default)]
+struct ScannedLine {
+    code: bool,
+    comment: bool,
+    todo: bool,
+}
+
+impl ScannedLine {
+    fn scan(line: &str, ctx: &mut Context) -> Self {
+        let chars: Vec<char> = line.chars().collect();
+        let mut scanned = Self {
+            code: matches!(ctx, Context::Str | Context::RawStr(_)),
+            comment: matches!(ctx, Context::BlockComment(_)),
+            todo: false,
+        };
+        let mut comment = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let (c, next) = (chars[i], chars.get(i + 1).copied());
+            match *ctx {
+                Context::BlockComment(depth) => match (c, next) {
+                    ('*', Some('/')) => {
+                        *ctx = if depth == 1 {
+                            Context::Code
+                        } else {
+                            Context::BlockComment(depth - 1)
+                        };
+                        i += 1;
+                    }
+                    ('/', Some('*')) => {
+                        *ctx = Context::BlockComment(depth + 1);
+                        i += 1;
+                    }
+                    _ => comment.push(c),
+                },
+                Context::Str => match c {
+                    '\\' => i += 1,
+                    '"' => *ctx = Context::Code,
+                    _ => {}
+                },
+                Context::RawStr(hashes) => {
+                    if c == '"'
+                        && chars[i + 1..].iter().take_while(|c| **c == '#').count() >= hashes
+                    {
+                        *ctx = Context::Code;
+                        i += hashes;
+                    }
+                }
+                Context::Code => match (c, next) {
+                    _ if c.is_whitespace() => {}
+                    ('/', Some('/')) => {
+                        scanned.comment = true;
+                        comment.extend(&chars[i + 2..]);
+                        break;
+                    }
+                    ('/', Some('*')) => {
+                        scanned.comment = true;
+                        *ctx = Context::BlockComment(1);
+                        i += 1;
+                    }
+                    _ => {
+                        scanned.code = true;
+                        i += Self::skip_code(&chars[i..], ctx) - 1;
+                    }
+                },
+            }
+            i += 1;
+        }
+        scanned.todo = comment.contains("TODO") || comment.contains("FIXME");
+        scanned
+    }
+
+    /// Skips the code token starting the chars, it returns the number of skipped chars.
+    /// The context is changed when the token starts a string.
+    fn skip_code(chars: &[char], ctx: &mut Context) -> usize {
+        let is_ident = |c: &char| c.is_alphanumeric() || *c == '_';
+        match chars[0] {
+            '"' => {
+                *ctx = Context::Str;
+                1
+            }
+            // NOTE: Quote starts either a char literal or a lifetime, eg. `'a'` or `'a`.
+            '\'' => match (chars.get(1), chars.get(2)) {
+                (Some('\\'), _) => chars
+                    .iter()
+                    .skip(3)
+                    .position(|c| *c == '\'')
+                    .map_or(chars.len(), |end| end + 4),
+                (Some(_), Some('\'')) => 3,
+                _ => 1,
+            },
+            c if is_ident(&c) => {
+                let len = chars.iter().take_while(|c| is_ident(c)).count();
+                let word: String = chars[..len].iter().collect();
+                let hashes = chars[len..].iter().take_while(|c| **c == '#').count();
+                let raw = matches!(word.as_str(), "r" | "br" | "cr");
+                if raw && chars.get(len + hashes) == Some(&'"') {
+                    *ctx = Context::RawStr(hashes);
+                    len + hashes + 1
+                } else {
+                    len
+                }
+            }
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn lines_are_counted_as_code_comments_or_blank() {
+        // given
+        init_tracing();
+        let source = r#"
+            //! Crate docs.
+
+            /* Block comment
+               TODO: split it */
+            fn main() {
+                let url = "http://localhost"; // FIXME: read from config
+                /// Nothing to do here.
+            }
+        "#;
+
+        // when
+        let stats = ProjectStats::count_lines(source);
+
+        // then
+        assert_eq!(
+            stats,
+            ProjectStats {
+                files: 1,
+                sloc: 3,
+                comment_lines: 4,
+                blank_lines: 3,
+                todos: 2,
+                ..ProjectStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn comment_markers_inside_literals_do_not_start_comments() {
+        // given
+        init_tracing();
+        let source = r##"
+            let url = "https://x/TODO";
+            let quote = '"'; // TODO: escape
+            let raw = r#"
+                // not a comment
+            "#;
+            let x = 1; /* FIXME: spans
+               lines */
+            /* outer /* nested */ still a comment */
+        "##;
+
+        // when
+        let stats = ProjectStats::count_lines(source);
+
+        // then
+        assert_eq!(
+            stats,
+            ProjectStats {
+                files: 1,
+                sloc: 6,
+                comment_lines: 2,
+                blank_lines: 2,
+                todos: 2,
+                ..ProjectStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn stats_of_files_are_summed() {
+        // given
+        init_tracing();
+        let mut stats = ProjectStats::count_lines("fn main() {}\n");
+
+        // when
+        stats += ProjectStats {
+            unsafe_blocks: 2,
+            ..ProjectStats::count_lines("// TODO\n\n")
+        };
+
+        // then
+        assert_eq!(
+            stats,
+            ProjectStats {
+                files: 2,
+                sloc: 1,
+                comment_lines: 1,
+                blank_lines: 1,
+                tests: None,
+                unsafe_blocks: 2,
+                todos: 1,
+            }
+        );
+    }
+}
@@ -7,6 +7,20 @@ pub enum TestsState {
     Pending,
     Failure,
     Success,
+    /// Tests were not run, because a stage before them failed.
+    Skipped {
+        reason: String,
+    },
+}
+
+impl TestsState {
+    /// Returns why the tests were not run, if they were skipped.
+    pub fn skip_reason(&self) -> Option<&str> {
+        match self {
+            TestsState::Skipped { reason } => Some(reason),
+            _ => None,
+        }
+    }
 }
 
 impl Display for TestsState {
@@ -18,6 +32,7 @@ impl Display for TestsState {
                 TestsState::Pending => "pending",
                 TestsState::Failure => "failure",
                 TestsState::Success => "success",
+                TestsState::Skipped { .. } => "skipped",
             }
         )
     }
@@ -56,5 +71,9 @@ mod test {
         assert_eq!(TestsState::Pending.to_string(), "pending");
         assert_eq!(TestsState::Failure.to_string(), "failure");
         assert_eq!(TestsState::Success.to_string(), "success");
+        let skipped = TestsState::Skipped {
+            reason: "check failed".into(),
+        };
+        assert_eq!(skipped.to_string(), "skipped");
     }
 }
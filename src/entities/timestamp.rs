@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Point in time, stored as milliseconds since the Unix epoch.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(transparent)]
+pub struct Timestamp {
+    millis: u64,
+}
+
+impl Timestamp {
+    pub fn now() -> Self {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let millis = u64::try_from(millis).unwrap_or(u64::MAX);
+        Self { millis }
+    }
+
+    /// Time elapsed from the `earlier` timestamp, zero when it's actually later.
+    pub fn duration_since(&self, earlier: Timestamp) -> Duration {
+        Duration::from_millis(self.millis.saturating_sub(earlier.millis))
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.millis)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn later_timestamp_is_greater() {
+        // given
+        init_tracing();
+        let earlier = Timestamp::now();
+
+        // when
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let later = Timestamp::now();
+
+        // then
+        assert!(later > earlier);
+    }
+}
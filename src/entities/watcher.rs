@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum WatcherState {
+    #[default]
+    Watching,
+    Paused,
+}
+
+impl Display for WatcherState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                WatcherState::Watching => "watching",
+                WatcherState::Paused => "paused",
+            }
+        )
+    }
+}
+
+/// Filesystem events received at once and how many of them were in the ignored paths.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct WatchedEvents {
+    pub received: u64,
+    pub ignored: u64,
+}
+
+impl WatchedEvents {
+    /// Any of the events is outside of the ignored paths.
+    pub fn has_change(&self) -> bool {
+        self.received > self.ignored
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn default_watcher_status_is_watching() {
+        // given
+        init_tracing();
+
+        // when
+        let status = WatcherState::default();
+
+        // then
+        assert_eq!(status, WatcherState::Watching);
+    }
+
+    #[test]
+    fn watcher_status_has_display_trait_implemented() {
+        // given
+        init_tracing();
+
+        // then
+        assert_eq!(WatcherState::Watching.to_string(), "watching");
+        assert_eq!(WatcherState::Paused.to_string(), "paused");
+    }
+}
@@ -1,10 +1,10 @@
-#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::module_name_repetitions, clippy::derivable_impls)]
 
 use crate::configuration::config::cfg;
 use crate::configuration::factories::Runtime;
 use crate::configuration::tracing::init_tracing;
 use crate::data_providers::server::start_server;
-use crate::startup::setup_shells;
+use crate::startup::{run_one_shot, setup_shells};
 
 use anyhow::Result;
 
@@ -21,7 +21,17 @@ mod testingtools;
 #[actix_web::main]
 async fn main() -> Result<()> {
     init_tracing();
-    start_server(setup_shells(Runtime::new(cfg()?)?)).await?;
+    let cfg = cfg()?;
+    let rt = Runtime::new(cfg.clone())?;
+    if cfg.one_shot {
+        let code = run_one_shot(rt)?;
+        if code != 0 {
+            std::process::exit(code);
+        }
+        return Ok(());
+    }
+    let history = rt.history.clone();
+    start_server(setup_shells(rt), history).await?;
 
     Ok(())
 }
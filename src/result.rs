@@ -1,4 +1,5 @@
 use crate::configuration::config::ConfigBuilderError;
+use crate::entities::stage::Stage;
 
 use actix_web::ResponseError;
 use thiserror::Error;
@@ -19,6 +20,9 @@ pub enum WatcherErr {
 
     #[error("Error when reading state.")]
     Read(#[from] StateReaderErr),
+
+    #[error("Error when writing to state.")]
+    Write(#[from] StateWriterErr),
 }
 
 #[derive(Debug, Error)]
@@ -45,6 +49,78 @@ pub enum CheckErr {
     Write(#[from] StateWriterErr),
 }
 
+#[derive(Debug, Error)]
+pub enum FmtErr {
+    #[error("Error when using bus.")]
+    Bus(#[from] BusErr),
+
+    #[error("Error when reading state.")]
+    Read(#[from] StateReaderErr),
+
+    #[error("Error when writing to state.")]
+    Write(#[from] StateWriterErr),
+}
+
+#[derive(Debug, Error)]
+pub enum QualityErr {
+    #[error("Error when using bus.")]
+    Bus(#[from] BusErr),
+
+    #[error("Error when reading state.")]
+    Read(#[from] StateReaderErr),
+
+    #[error("Error when writing to state.")]
+    Write(#[from] StateWriterErr),
+
+    #[error("Error when reading source files.")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum NotifierErr {
+    #[error("Error when using bus.")]
+    Bus(#[from] BusErr),
+
+    #[error("Error when reading state.")]
+    Read(#[from] StateReaderErr),
+
+    #[error("Error while executing notify command.")]
+    Cmd(#[from] CmdErr),
+
+    #[error("Notification was rejected: {0}.")]
+    Rejected(String),
+}
+
+#[derive(Debug, Error)]
+pub enum StageErr {
+    #[error("Error when using bus.")]
+    Bus(#[from] BusErr),
+
+    #[error("Error when reading state.")]
+    Read(#[from] StateReaderErr),
+
+    #[error("Error when writing to state.")]
+    Write(#[from] StateWriterErr),
+}
+
+#[derive(Debug, Error)]
+pub enum HookRunErr {
+    #[error("Error while running hook.")]
+    Io(#[from] std::io::Error),
+
+    #[error("Error while serializing event.")]
+    Payload(#[from] serde_json::Error),
+
+    #[error("Error while posting event.")]
+    Http(#[from] hyper::Error),
+
+    #[error("Hook did not finish in {0:?}.")]
+    Timeout(std::time::Duration),
+
+    #[error("Hook failed: {0}.")]
+    Failed(String),
+}
+
 #[derive(Debug, Error)]
 pub enum IndexErr {
     #[error("Error when using bus.")]
@@ -53,6 +129,9 @@ pub enum IndexErr {
     #[error("Error when reading state.")]
     Read(#[from] StateReaderErr),
 
+    #[error("Error when writing to state.")]
+    Write(#[from] StateWriterErr),
+
     #[error("Error while executing command.")]
     Cmd(#[from] CmdErr),
 }
@@ -70,8 +149,45 @@ pub enum CoverageErr {
 
     #[error("Error when writing to state.")]
     Write(#[from] StateWriterErr),
+
+    #[error("Error when reading coverage report.")]
+    Io(#[from] std::io::Error),
+
+    #[error("Error when parsing coverage report.")]
+    Report(#[from] ReportParseErr),
+}
+
+#[derive(Debug, Error)]
+pub enum HistoryErr {
+    #[error("Error when using bus.")]
+    Bus(#[from] BusErr),
+
+    #[error("Error when reading state.")]
+    Read(#[from] StateReaderErr),
+
+    #[error("Failed to access history file.")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to (de)serialize pipeline run.")]
+    Json(#[from] serde_json::Error),
 }
 
+#[derive(Debug, Error)]
+pub enum VcsErr {
+    #[error("Error while executing command.")]
+    Cmd(#[from] CmdErr),
+
+    #[error("Error while parsing diff.")]
+    Diff(#[from] DiffParseErr),
+}
+
+#[derive(Debug, Error, Clone)]
+pub enum DiffParseErr {
+    #[error("Hunk found before the file header.")]
+    NoFileHeader,
+}
+
+#[allow(unused)]
 #[derive(Debug, Error)]
 pub enum SinkErr {
     #[error("Error when using bus.")]
@@ -103,6 +219,12 @@ pub enum SetupErr {
 
     #[error("Failed to create Watcher.")]
     Watch(#[from] WatcherErr),
+
+    #[error("Failed to restore state from history.")]
+    History(#[from] HistoryErr),
+
+    #[error("Failed to write to state.")]
+    Write(#[from] StateWriterErr),
 }
 
 #[derive(Debug, Error)]
@@ -112,17 +234,26 @@ pub enum StateReaderErr {}
 pub enum StateWriterErr {
     #[error("Failed to send event.")]
     Bus(#[from] BusErr),
+
+    #[error("Run can't be started from {0} stage.")]
+    NotTriggerable(Stage),
 }
 
 #[derive(Debug, Error)]
 pub enum ServerErr {
     #[error("Failed to serve results.")]
     Generic(#[from] anyhow::Error),
+
+    #[error("Not found: {0}.")]
+    NotFound(String),
 }
 
 impl ResponseError for ServerErr {
     fn status_code(&self) -> hyper::StatusCode {
-        hyper::StatusCode::from_u16(500).unwrap()
+        match self {
+            ServerErr::Generic(_) => hyper::StatusCode::from_u16(500).unwrap(),
+            ServerErr::NotFound(_) => hyper::StatusCode::from_u16(404).unwrap(),
+        }
     }
 }
 
@@ -145,6 +276,123 @@ pub enum CfgErr {
 
     #[error("Failed to configure ignored paths.")]
     IgnoredPath(#[from] IgnoredPathErr),
+
+    #[error("Failed to configure coverage backend.")]
+    CoverageBackend(#[from] CoverageBackendErr),
+
+    #[error("Unknown format of coverage report: {0}.")]
+    CoverageReport(String),
+
+    #[error("Invalid minimum coverage: {0}.")]
+    CoverageMinimum(String),
+
+    #[error("Failed to configure coverage trigger.")]
+    CoverageTrigger(#[from] CoverageTriggerErr),
+
+    #[error("Failed to configure coverage regression threshold.")]
+    CoverageRegression(#[from] RegressionThresholdErr),
+
+    #[error("Failed to configure notifications.")]
+    NotifyPolicy(#[from] NotifyPolicyErr),
+
+    #[error("Failed to configure hooks.")]
+    Hook(#[from] HookErr),
+
+    #[error("Invalid hook timeout: {0}.")]
+    HookTimeout(String),
+
+    #[error("Failed to configure pipeline.")]
+    Pipeline(#[from] PipelineErr),
+
+    #[error("Invalid maximum of parallel stages: {0}.")]
+    MaxParallelStages(String),
+
+    #[error("Failed to configure stages continuing on failure.")]
+    ContinueOnFailure(#[from] StageNameErr),
+
+    #[error("Index refresh can't continue on failure.")]
+    ContinueAfterIndex,
+
+    #[error("Failed to configure formatting check.")]
+    FmtMode(#[from] FmtModeErr),
+
+    #[error("Failed to find home directory for the history.")]
+    HistoryPath,
+}
+
+#[derive(Debug, Error)]
+pub enum CoverageBackendErr {
+    #[error("Unknown coverage backend: {0}.")]
+    Unknown(String),
+}
+
+#[derive(Debug, Error)]
+pub enum RegressionThresholdErr {
+    #[error("Invalid coverage regression threshold: {0}.")]
+    Unknown(String),
+}
+
+#[derive(Debug, Error)]
+pub enum CoverageTriggerErr {
+    #[error("Unknown coverage trigger: {0}.")]
+    Unknown(String),
+}
+
+#[derive(Debug, Error)]
+pub enum PipelineErr {
+    #[error("Failed to read pipeline definition.")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse pipeline definition.")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Stage {0} is defined more than once.")]
+    DuplicateStage(String),
+
+    #[error("Invalid stage name: {0}, expected letters, digits, - or _.")]
+    InvalidName(String),
+
+    #[error("Stage name {0} is reserved for built-in stage.")]
+    ReservedName(String),
+
+    #[error("Stage {0} has no command.")]
+    EmptyCmd(String),
+
+    #[error("Invalid output parser: {0}.")]
+    InvalidParser(String),
+
+    #[error("Stage {stage} depends on unknown stage {dependency}.")]
+    UnknownDependency { stage: String, dependency: String },
+
+    #[error("Stage {0} depends on itself.")]
+    Cycle(String),
+}
+
+#[derive(Debug, Error)]
+pub enum HookErr {
+    #[error("Invalid hook: {0}, expected <event>=<action>.")]
+    Invalid(String),
+
+    #[error("Unknown event of the hook: {0}.")]
+    UnknownEvent(String),
+}
+
+#[derive(Debug, Error)]
+pub enum StageNameErr {
+    #[error("Unknown stage: {0}.")]
+    Unknown(String),
+}
+
+#[derive(Debug, Error)]
+pub enum FmtModeErr {
+    #[error("Unknown formatting check mode: {0}.")]
+    Unknown(String),
+}
+
+#[derive(Debug, Error)]
+pub enum NotifyPolicyErr {
+    #[error("Unknown notify policy: {0}.")]
+    Unknown(String),
 }
 
 #[derive(Debug, Error, Clone, Default)]
@@ -160,6 +408,18 @@ pub enum CoverageParseErr {
     InvalidValue(String),
 }
 
+#[derive(Debug, Error, Clone)]
+pub enum ReportParseErr {
+    #[error("Line hits reported before the source file.")]
+    NoSourceFile,
+
+    #[error("Invalid report entry: {0}.")]
+    InvalidEntry(String),
+
+    #[error("Invalid report: {0}.")]
+    InvalidReport(String),
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -177,4 +437,16 @@ mod test {
         // then
         assert_eq!(status, 500);
     }
+
+    #[test]
+    fn not_found_server_error_has_not_found_status() {
+        // given
+        let err = ServerErr::NotFound("src/main.rs".into());
+
+        // when
+        let status = err.status_code();
+
+        // then
+        assert_eq!(status, 404);
+    }
 }
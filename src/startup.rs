@@ -1,36 +1,91 @@
 use crate::configuration::factories::Runtime;
+use crate::entities::state_snapshot::Verdict;
+use crate::result::SetupErr;
 use crate::use_cases::services::check_shell::CheckShell;
 use crate::use_cases::services::coverage_shell::CoverageShell;
+use crate::use_cases::services::coverage_trigger_shell::CoverageTriggerShell;
+use crate::use_cases::services::custom_stages_shell::CustomStagesShell;
+use crate::use_cases::services::diff_coverage_shell::DiffCoverageShell;
+use crate::use_cases::services::fmt_shell::FmtShell;
+use crate::use_cases::services::history_shell::HistoryShell;
+use crate::use_cases::services::hooks_shell::HooksShell;
+use crate::use_cases::services::notifier_shell::NotifierShell;
+use crate::use_cases::services::one_shot_shell::OneShotShell;
+use crate::use_cases::services::provenance_shell::ProvenanceShell;
+use crate::use_cases::services::quality_shell::QualityShell;
+use crate::use_cases::services::stage_timing_shell::StageTimingShell;
 use crate::use_cases::services::tests_index_shell::TestsIndexShell;
 use crate::use_cases::services::tests_shell::TestsShell;
 use crate::use_cases::services::watcher_shell::ChangeWatcherShell;
 use crate::use_cases::state::State;
 
+/// Runs the pipeline once and returns the exit code: 1 when the verdict is red, 0 otherwise.
+pub fn run_one_shot(rt: Runtime) -> Result<i32, SetupErr> {
+    let one_shot_shell = OneShotShell::new(rt.bus.clone(), rt.cfg.clone());
+    let verdict = one_shot_shell.run(setup_shells(rt))?;
+    Ok(if verdict == Verdict::Red { 1 } else { 0 })
+}
+
+/// In one-shot mode, the shells which serve only the API or watch the repository are not run.
 #[allow(unused)]
 pub fn setup_shells(rt: Runtime) -> State {
     let Runtime {
+        cfg,
         bus,
         change_watcher,
         tests_index,
         test_runner,
         check_runner,
+        fmt_runner,
         coverage_runner,
+        stage_runner,
+        quality_analyzer,
+        history,
+        vcs,
+        notifier,
+        hook_runner,
         state,
     } = rt;
 
+    let one_shot = cfg.one_shot;
     let watcher_shell = ChangeWatcherShell::new(bus.clone());
-    let check_shell = CheckShell::new(bus.clone());
+    let check_shell = CheckShell::new(bus.clone(), cfg.clone());
+    let fmt_shell = FmtShell::new(bus.clone(), cfg.clone());
 
-    let tests_shell = TestsShell::new(bus.clone());
-    let index_shell = TestsIndexShell::new(bus.clone());
+    let tests_shell = TestsShell::new(bus.clone(), cfg.clone());
+    let index_shell = TestsIndexShell::new(bus.clone(), cfg.clone());
 
-    let coverage_shell = CoverageShell::new(bus.clone());
+    let coverage_trigger_shell = CoverageTriggerShell::new(bus.clone(), cfg.clone());
+    let coverage_shell = CoverageShell::new(bus.clone(), cfg.clone());
+    let diff_coverage_shell = DiffCoverageShell::new(bus.clone(), cfg.clone());
+    let custom_stages_shell = CustomStagesShell::new(bus.clone(), cfg.clone());
+    let quality_shell = QualityShell::new(bus.clone());
 
-    watcher_shell.run(change_watcher, state.reader());
+    let provenance_shell = ProvenanceShell::new(bus.clone(), cfg.clone());
+    let stage_timing_shell = StageTimingShell::new(bus.clone(), cfg.clone());
+    let history_shell = HistoryShell::new(bus.clone(), cfg.clone());
+    let notifier_shell = NotifierShell::new(bus.clone(), cfg.clone());
+    let hooks_shell = HooksShell::new(bus.clone(), cfg);
+
+    if let Some(change_watcher) = change_watcher {
+        watcher_shell.run(change_watcher, state.clone());
+    }
     index_shell.run(tests_index, state.clone());
     check_shell.run(check_runner, state.clone());
+    fmt_shell.run(fmt_runner, state.clone());
     tests_shell.run(test_runner, state.clone());
+    coverage_trigger_shell.run(state.clone());
     coverage_shell.run(coverage_runner, state.clone());
+    custom_stages_shell.run(stage_runner, state.clone());
+    quality_shell.run(quality_analyzer, state.clone());
+    if !one_shot {
+        diff_coverage_shell.run(vcs.clone(), state.clone());
+        provenance_shell.run(vcs.clone(), state.clone());
+        stage_timing_shell.run(state.clone());
+        notifier_shell.run(notifier, state.clone());
+    }
+    hooks_shell.run(hook_runner, state.clone());
+    history_shell.run(history, vcs, state.reader());
 
     state
 }
@@ -39,13 +94,102 @@ pub fn setup_shells(rt: Runtime) -> State {
 mod test {
     use super::*;
 
-    use crate::configuration::config::Config;
+    use crate::configuration::config::{cfg, Config};
+    use crate::data_providers::command::Cmd;
+    use crate::data_providers::coverage_parser::CoverageParser;
+    use crate::entities::custom_stage::{CustomStage, SuccessCriterion};
+    use crate::entities::fmt::FmtMode;
+    use crate::entities::repo_root::RepoRoot;
 
     use anyhow::Result;
+    use std::path::Path;
+    use tempfile::tempdir;
 
     #[test]
     fn setup_shells_works() -> Result<()> {
         setup_shells(Runtime::new(Config::default())?);
         Ok(())
     }
+
+    #[test]
+    fn one_shot_run_of_passing_pipeline_exits_with_zero() -> Result<()> {
+        // given
+        let repo = tempdir()?;
+        let cfg = one_shot_cfg(repo.path(), "true", "true")?;
+
+        // when
+        let code = run_one_shot(Runtime::with_repo_root(
+            cfg,
+            Some(RepoRoot::new(repo.path())),
+        )?)?;
+
+        // then
+        assert_eq!(code, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn one_shot_run_with_failed_check_exits_with_one() -> Result<()> {
+        // given
+        let repo = tempdir()?;
+        let cfg = one_shot_cfg(repo.path(), "false", "true")?;
+
+        // when
+        let code = run_one_shot(Runtime::with_repo_root(
+            cfg,
+            Some(RepoRoot::new(repo.path())),
+        )?)?;
+
+        // then
+        assert_eq!(code, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn one_shot_run_with_failed_custom_stage_exits_with_one() -> Result<()> {
+        // given
+        let repo = tempdir()?;
+        let cfg = one_shot_cfg(repo.path(), "true", "false")?;
+
+        // when
+        let code = run_one_shot(Runtime::with_repo_root(
+            cfg,
+            Some(RepoRoot::new(repo.path())),
+        )?)?;
+
+        // then
+        assert_eq!(code, 1);
+
+        Ok(())
+    }
+
+    fn one_shot_cfg(repo: &Path, check: &str, custom_stage: &str) -> Result<Config> {
+        Ok(Config {
+            check_cmd: Cmd::new(check, &[]),
+            tests_cmd: Cmd::new("true", &[]),
+            list_tests_cmd: Cmd::new("echo", &["test_a: test"]),
+            coverage_cmd: Cmd::with_parser(
+                "echo",
+                &["75.00% coverage, 3/4 lines covered"],
+                CoverageParser::make(),
+            ),
+            coverage_report: None,
+            fmt_mode: FmtMode::Off,
+            history_path: repo.join("history.jsonl"),
+            custom_stages: vec![CustomStage {
+                name: "lint".into(),
+                cmd: vec![custom_stage.into()],
+                depends_on: vec!["tests".into()],
+                success: SuccessCriterion::ExitCode,
+                parser: None,
+                own_target_dir: false,
+                continue_on_failure: false,
+            }],
+            hooks: Vec::new(),
+            one_shot: true,
+            ..cfg()?
+        })
+    }
 }
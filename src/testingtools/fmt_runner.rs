@@ -0,0 +1,63 @@
+use crate::entities::repo_root::RepoRoot;
+use crate::result::FmtErr;
+use crate::testingtools::{pipe, MutexExt, Spy, Tx};
+use crate::use_cases::fmt_runner::{FRunner, FmtRunStatus, FmtRunner};
+
+pub fn tracked(runner: FmtRunner) -> (FmtRunnerSpy, FmtRunner) {
+    TrackedFmtRunner::wrap(runner)
+}
+
+pub struct TrackedFmtRunner {
+    runner: FmtRunner,
+    tx: Tx,
+}
+
+impl TrackedFmtRunner {
+    fn wrap(runner: FmtRunner) -> (FmtRunnerSpy, FmtRunner) {
+        let (tx, spy) = pipe();
+
+        (FmtRunnerSpy::new(spy), Box::new(Self { runner, tx }))
+    }
+}
+
+impl FRunner for TrackedFmtRunner {
+    fn run(&self, repo_root: RepoRoot) -> Result<FmtRunStatus, FmtErr> {
+        let res = self.runner.run(repo_root);
+        self.tx.signal(());
+        res
+    }
+}
+
+pub struct FmtRunnerSpy {
+    spy: Spy,
+}
+
+impl FmtRunnerSpy {
+    fn new(spy: Spy) -> Self {
+        Self { spy }
+    }
+
+    pub fn run_called(&self) -> bool {
+        self.spy.method_called()
+    }
+}
+
+pub fn working(result: FmtRunStatus) -> FmtRunner {
+    WorkingFmtRunner::make(result)
+}
+
+pub struct WorkingFmtRunner {
+    result: FmtRunStatus,
+}
+
+impl WorkingFmtRunner {
+    fn make(result: FmtRunStatus) -> FmtRunner {
+        Box::new(Self { result })
+    }
+}
+
+impl FRunner for WorkingFmtRunner {
+    fn run(&self, _repo_root: RepoRoot) -> Result<FmtRunStatus, FmtErr> {
+        Ok(self.result.clone())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::entities::pipeline_run::PipelineRun;
+use crate::result::HistoryErr;
+use crate::testingtools::{pipe, MutexExt, Spy, Tx};
+use crate::use_cases::history::{History, HistoryQuery, RunHistory};
+
+use std::sync::Arc;
+
+pub fn tracked(history: History) -> (HistorySpy, History) {
+    TrackedHistory::wrap(history)
+}
+
+pub struct TrackedHistory {
+    history: History,
+    tx: Tx<PipelineRun>,
+}
+
+impl TrackedHistory {
+    fn wrap(history: History) -> (HistorySpy, History) {
+        let (tx, spy) = pipe();
+
+        (HistorySpy::new(spy), Arc::new(Self { history, tx }))
+    }
+}
+
+impl RunHistory for TrackedHistory {
+    fn append(&self, run: PipelineRun) -> Result<(), HistoryErr> {
+        let res = self.history.append(run.clone());
+        self.tx.signal(run);
+        res
+    }
+
+    fn runs(&self, query: &HistoryQuery) -> Result<Vec<PipelineRun>, HistoryErr> {
+        self.history.runs(query)
+    }
+}
+
+pub struct HistorySpy {
+    spy: Spy<PipelineRun>,
+}
+
+impl HistorySpy {
+    fn new(spy: Spy<PipelineRun>) -> Self {
+        Self { spy }
+    }
+
+    pub fn append_called(&self) -> bool {
+        self.spy.method_called()
+    }
+
+    pub fn append_called_with<F: Fn(&PipelineRun) -> bool>(&self, pred: F) -> bool {
+        self.spy.method_called_with_matching(pred)
+    }
+}
+
+pub fn working(runs: Vec<PipelineRun>) -> History {
+    WorkingHistory::make(runs)
+}
+
+pub struct WorkingHistory {
+    runs: Vec<PipelineRun>,
+}
+
+impl WorkingHistory {
+    fn make(runs: Vec<PipelineRun>) -> History {
+        Arc::new(Self { runs })
+    }
+}
+
+impl RunHistory for WorkingHistory {
+    fn append(&self, _run: PipelineRun) -> Result<(), HistoryErr> {
+        Ok(())
+    }
+
+    fn runs(&self, query: &HistoryQuery) -> Result<Vec<PipelineRun>, HistoryErr> {
+        Ok(self
+            .runs
+            .iter()
+            .filter(|run| query.matches(run))
+            .skip(query.offset)
+            .take(query.limit)
+            .cloned()
+            .collect())
+    }
+}
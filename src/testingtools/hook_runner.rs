@@ -0,0 +1,56 @@
+use crate::entities::hook::{HookAction, HookPayload};
+use crate::result::HookRunErr;
+use crate::testingtools::{pipe, MutexExt, Spy, Tx};
+use crate::use_cases::hook_runner::{HookRunner, RunHook};
+
+use std::sync::Arc;
+
+/// Hook runner which only records the hooks run, failing when `fail` is set.
+pub fn tracked(fail: bool) -> (HookRunnerSpy, HookRunner) {
+    TrackedHookRunner::make(fail)
+}
+
+pub struct TrackedHookRunner {
+    tx: Tx<(HookAction, HookPayload)>,
+    fail: bool,
+}
+
+impl TrackedHookRunner {
+    fn make(fail: bool) -> (HookRunnerSpy, HookRunner) {
+        let (tx, spy) = pipe();
+
+        (HookRunnerSpy::new(spy), Arc::new(Self { tx, fail }))
+    }
+}
+
+impl RunHook for TrackedHookRunner {
+    fn run(&self, action: &HookAction, payload: &HookPayload) -> Result<(), HookRunErr> {
+        self.tx.signal((action.clone(), payload.clone()));
+        if self.fail {
+            return Err(HookRunErr::Failed("failure".into()));
+        }
+        Ok(())
+    }
+}
+
+pub struct HookRunnerSpy {
+    spy: Spy<(HookAction, HookPayload)>,
+}
+
+impl HookRunnerSpy {
+    fn new(spy: Spy<(HookAction, HookPayload)>) -> Self {
+        Self { spy }
+    }
+
+    pub fn run_called_with_matching<F>(&self, pred: F) -> bool
+    where
+        F: Fn(&HookAction, &HookPayload) -> bool,
+    {
+        self.spy
+            .method_called_with_matching(|(action, payload)| pred(action, payload))
+    }
+
+    pub fn run_not_called(&self) -> bool {
+        !self.spy.method_called()
+    }
+}
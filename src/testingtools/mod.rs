@@ -5,11 +5,18 @@ use tracing::error;
 
 pub mod check_runner;
 pub mod coverage_runner;
+pub mod fmt_runner;
+pub mod history;
+pub mod hook_runner;
+pub mod notifier;
 pub mod output_parser;
+pub mod quality_analyzer;
+pub mod stage_runner;
 pub mod state;
 pub mod test_runner;
 pub mod tests_index;
 pub mod unit;
+pub mod vcs;
 
 pub fn pipe<T>() -> (Tx<T>, Spy<T>)
 where
@@ -52,6 +59,13 @@ where
             _ => false,
         }
     }
+
+    pub fn method_called_with_matching<F: Fn(&T) -> bool>(&self, pred: F) -> bool {
+        match self.rx.recv_timeout(Duration::from_secs(3)) {
+            Ok(res) => pred(&res),
+            _ => false,
+        }
+    }
 }
 
 pub trait MutexExt<T = ()> {
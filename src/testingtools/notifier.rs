@@ -0,0 +1,46 @@
+use crate::entities::notification::Notification;
+use crate::result::NotifierErr;
+use crate::testingtools::{pipe, MutexExt, Spy, Tx};
+use crate::use_cases::notifier::{Notifier, Notify};
+
+/// Local stand-in for the desktop notifications service, which records what was sent.
+pub fn tracked() -> (NotifierSpy, Notifier) {
+    TrackedNotifier::make()
+}
+
+pub struct TrackedNotifier {
+    tx: Tx<Notification>,
+}
+
+impl TrackedNotifier {
+    fn make() -> (NotifierSpy, Notifier) {
+        let (tx, spy) = pipe();
+
+        (NotifierSpy::new(spy), Box::new(Self { tx }))
+    }
+}
+
+impl Notify for TrackedNotifier {
+    fn notify(&self, notification: Notification) -> Result<(), NotifierErr> {
+        self.tx.signal(notification);
+        Ok(())
+    }
+}
+
+pub struct NotifierSpy {
+    spy: Spy<Notification>,
+}
+
+impl NotifierSpy {
+    fn new(spy: Spy<Notification>) -> Self {
+        Self { spy }
+    }
+
+    pub fn notify_called_with_matching<F: Fn(&Notification) -> bool>(&self, pred: F) -> bool {
+        self.spy.method_called_with_matching(pred)
+    }
+
+    pub fn notify_not_called(&self) -> bool {
+        !self.spy.method_called()
+    }
+}
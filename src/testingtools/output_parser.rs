@@ -1,9 +1,10 @@
+use crate::entities::coverage_summary::CoverageSummary;
 use crate::result::CoverageParseErr;
 use crate::use_cases::output_parser::{OutputParser, Parser};
 
 use std::sync::{Arc, Mutex};
 
-pub fn working(coverage: f32) -> Parser<f32, CoverageParseErr> {
+pub fn working(coverage: f32) -> Parser<CoverageSummary, CoverageParseErr> {
     WorkingOutputParser::make(coverage)
 }
 
@@ -12,34 +13,34 @@ pub struct WorkingOutputParser {
 }
 
 impl WorkingOutputParser {
-    fn make(coverage: f32) -> Parser<f32, CoverageParseErr> {
+    fn make(coverage: f32) -> Parser<CoverageSummary, CoverageParseErr> {
         Arc::new(Mutex::new(Self { coverage }))
     }
 }
 
 impl OutputParser for WorkingOutputParser {
-    type Output = f32;
+    type Output = CoverageSummary;
     type Error = CoverageParseErr;
 
     fn parse(&self, _output: String) -> Result<Self::Output, Self::Error> {
-        Ok(self.coverage)
+        Ok(CoverageSummary::new(self.coverage))
     }
 }
 
-pub fn failing() -> Parser<f32, CoverageParseErr> {
+pub fn failing() -> Parser<CoverageSummary, CoverageParseErr> {
     FailingOutputParser::make()
 }
 
 pub struct FailingOutputParser;
 
 impl FailingOutputParser {
-    fn make() -> Parser<f32, CoverageParseErr> {
+    fn make() -> Parser<CoverageSummary, CoverageParseErr> {
         Arc::new(Mutex::new(Self))
     }
 }
 
 impl OutputParser for FailingOutputParser {
-    type Output = f32;
+    type Output = CoverageSummary;
     type Error = CoverageParseErr;
 
     fn parse(&self, _output: String) -> Result<Self::Output, Self::Error> {
@@ -0,0 +1,97 @@
+use crate::entities::quality::Halstead;
+use crate::entities::repo_root::RepoRoot;
+use crate::result::QualityErr;
+use crate::testingtools::{pipe, MutexExt, Spy, Tx};
+use crate::use_cases::quality_analyzer::{CodeAnalysis, QAnalyzer, QualityAnalyzer};
+
+use std::io;
+
+/// Halstead metrics of a small piece of code: volume 48, difficulty 3 and effort 144.
+pub fn halstead() -> Halstead {
+    Halstead {
+        distinct_operators: 3,
+        distinct_operands: 5,
+        total_operators: 6,
+        total_operands: 10,
+    }
+}
+
+pub fn tracked(analyzer: QualityAnalyzer) -> (QualityAnalyzerSpy, QualityAnalyzer) {
+    TrackedQualityAnalyzer::wrap(analyzer)
+}
+
+pub struct TrackedQualityAnalyzer {
+    analyzer: QualityAnalyzer,
+    tx: Tx,
+}
+
+impl TrackedQualityAnalyzer {
+    fn wrap(analyzer: QualityAnalyzer) -> (QualityAnalyzerSpy, QualityAnalyzer) {
+        let (tx, spy) = pipe();
+
+        (
+            QualityAnalyzerSpy::new(spy),
+            Box::new(Self { analyzer, tx }),
+        )
+    }
+}
+
+impl QAnalyzer for TrackedQualityAnalyzer {
+    fn analyze(&self, repo_root: RepoRoot) -> Result<CodeAnalysis, QualityErr> {
+        let res = self.analyzer.analyze(repo_root);
+        self.tx.signal(());
+        res
+    }
+}
+
+pub struct QualityAnalyzerSpy {
+    spy: Spy,
+}
+
+impl QualityAnalyzerSpy {
+    fn new(spy: Spy) -> Self {
+        Self { spy }
+    }
+
+    pub fn analyze_called(&self) -> bool {
+        self.spy.method_called()
+    }
+}
+
+pub fn working(analysis: CodeAnalysis) -> QualityAnalyzer {
+    WorkingQualityAnalyzer::make(analysis)
+}
+
+pub struct WorkingQualityAnalyzer {
+    analysis: CodeAnalysis,
+}
+
+impl WorkingQualityAnalyzer {
+    fn make(analysis: CodeAnalysis) -> QualityAnalyzer {
+        Box::new(Self { analysis })
+    }
+}
+
+impl QAnalyzer for WorkingQualityAnalyzer {
+    fn analyze(&self, _repo_root: RepoRoot) -> Result<CodeAnalysis, QualityErr> {
+        Ok(self.analysis.clone())
+    }
+}
+
+pub fn failing() -> QualityAnalyzer {
+    FailingQualityAnalyzer::make()
+}
+
+pub struct FailingQualityAnalyzer;
+
+impl FailingQualityAnalyzer {
+    fn make() -> QualityAnalyzer {
+        Box::new(Self)
+    }
+}
+
+impl QAnalyzer for FailingQualityAnalyzer {
+    fn analyze(&self, _repo_root: RepoRoot) -> Result<CodeAnalysis, QualityErr> {
+        Err(QualityErr::Io(io::Error::other("Failure")))
+    }
+}
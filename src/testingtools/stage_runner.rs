@@ -0,0 +1,133 @@
+use crate::entities::custom_stage::{CustomStage, CustomStageState};
+use crate::entities::repo_root::RepoRoot;
+use crate::result::{BusErr, StageErr};
+use crate::testingtools::{pipe, MutexExt, Spy, Tx};
+use crate::use_cases::stage_runner::{SRunner, StageRunner};
+
+use anyhow::anyhow;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// Stage runner returning the given state for the named stages and success for the others.
+pub fn tracked(results: &[(&str, CustomStageState)]) -> (StageRunnerSpy, StageRunner) {
+    TrackedStageRunner::make(results)
+}
+
+pub struct TrackedStageRunner {
+    results: HashMap<String, CustomStageState>,
+    tx: Tx<String>,
+}
+
+impl TrackedStageRunner {
+    fn make(results: &[(&str, CustomStageState)]) -> (StageRunnerSpy, StageRunner) {
+        let results = results
+            .iter()
+            .map(|(name, state)| ((*name).to_string(), state.clone()))
+            .collect();
+        let (tx, spy) = pipe();
+
+        (StageRunnerSpy::new(spy), Arc::new(Self { results, tx }))
+    }
+}
+
+impl SRunner for TrackedStageRunner {
+    fn run(&self, stage: &CustomStage, _repo_root: RepoRoot) -> Result<CustomStageState, StageErr> {
+        self.tx.signal(stage.name.clone());
+        Ok(self
+            .results
+            .get(&stage.name)
+            .cloned()
+            .unwrap_or(CustomStageState::Success { summary: None }))
+    }
+}
+
+/// Stage runner which signals the start of the stage and fails to run it.
+pub fn failing() -> (StageRunnerSpy, StageRunner) {
+    FailingStageRunner::make()
+}
+
+pub struct FailingStageRunner {
+    tx: Tx<String>,
+}
+
+impl FailingStageRunner {
+    fn make() -> (StageRunnerSpy, StageRunner) {
+        let (tx, spy) = pipe();
+
+        (StageRunnerSpy::new(spy), Arc::new(Self { tx }))
+    }
+}
+
+impl SRunner for FailingStageRunner {
+    fn run(&self, stage: &CustomStage, _repo_root: RepoRoot) -> Result<CustomStageState, StageErr> {
+        self.tx.signal(stage.name.clone());
+        Err(BusErr::Generic(anyhow!("failure")).into())
+    }
+}
+
+/// Stage runner which signals the start of the stage and passes it only when the gate is opened.
+pub fn blocking() -> (StageRunnerSpy, StageGate, StageRunner) {
+    BlockingStageRunner::make()
+}
+
+pub struct BlockingStageRunner {
+    tx: Tx<String>,
+    gate: Mutex<Receiver<()>>,
+}
+
+impl BlockingStageRunner {
+    fn make() -> (StageRunnerSpy, StageGate, StageRunner) {
+        let (tx, spy) = pipe();
+        let (gate_tx, gate_rx) = channel();
+        let gate = Mutex::new(gate_rx);
+
+        (
+            StageRunnerSpy::new(spy),
+            StageGate { tx: gate_tx },
+            Arc::new(Self { tx, gate }),
+        )
+    }
+}
+
+impl SRunner for BlockingStageRunner {
+    fn run(&self, stage: &CustomStage, _repo_root: RepoRoot) -> Result<CustomStageState, StageErr> {
+        self.tx.signal(stage.name.clone());
+        // NOTE: When the gate is dropped at the end of the test, the stage just passes.
+        let _ = self.gate.lock().expect("poisoned mutex").recv();
+        Ok(CustomStageState::Success { summary: None })
+    }
+}
+
+pub struct StageGate {
+    tx: Sender<()>,
+}
+
+impl StageGate {
+    /// Lets one of the running stages finish.
+    pub fn open_once(&self) {
+        let _ = self.tx.send(());
+    }
+}
+
+pub struct StageRunnerSpy {
+    spy: Spy<String>,
+}
+
+impl StageRunnerSpy {
+    fn new(spy: Spy<String>) -> Self {
+        Self { spy }
+    }
+
+    pub fn run_called_with_val(&self, name: &str) -> bool {
+        self.spy.method_called_with_val(&name.to_string())
+    }
+
+    pub fn run_called_with_matching<F: Fn(&String) -> bool>(&self, pred: F) -> bool {
+        self.spy.method_called_with_matching(pred)
+    }
+
+    pub fn run_not_called(&self) -> bool {
+        !self.spy.method_called()
+    }
+}
@@ -1,7 +1,21 @@
 use crate::entities::check::CheckState;
 use crate::entities::coverage::CoverageState;
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::custom_stage::CustomStageState;
+use crate::entities::diff_coverage::DiffCoverage;
+use crate::entities::file_coverage::FileCoverage;
+use crate::entities::fmt::FmtState;
+use crate::entities::metrics::Metrics;
+use crate::entities::provenance::{StageProvenance, TrackedResult, TreeSnapshot};
+use crate::entities::quality::QualityReport;
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::{DurationStats, StageTiming};
+use crate::entities::state_snapshot::StateSnapshot;
+use crate::entities::stats::ProjectStats;
 use crate::entities::tests::TestsState;
+use crate::entities::watcher::{WatchedEvents, WatcherState};
 use crate::result::{StateReaderErr, StateWriterErr};
 use crate::testingtools::{pipe, MutexExt, Spy, Tx};
 use crate::use_cases::state::{
@@ -9,6 +23,7 @@ use crate::use_cases::state::{
 };
 
 use anyhow::Result;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub fn tracked(state: &State) -> (StateSpies, State) {
@@ -26,7 +41,21 @@ impl TrackedState {
 
         let (write_tests_status_tx, write_tests_status_spy) = pipe::<TestsState>();
         let (write_check_status_tx, write_check_status_spy) = pipe::<CheckState>();
+        let (write_fmt_status_tx, write_fmt_status_spy) = pipe::<FmtState>();
         let (write_coverage_status_tx, write_coverage_status_spy) = pipe::<CoverageState>();
+        let (write_coverage_summary_tx, write_coverage_summary_spy) = pipe::<CoverageSummary>();
+        let (write_coverage_files_tx, write_coverage_files_spy) = pipe::<Vec<FileCoverage>>();
+        let (write_diff_coverage_tx, write_diff_coverage_spy) = pipe::<DiffCoverage>();
+        let (write_quality_tx, write_quality_spy) = pipe::<QualityReport>();
+        let (write_stats_tx, write_stats_spy) = pipe::<ProjectStats>();
+        let (write_tests_count_tx, write_tests_count_spy) = pipe::<u32>();
+        let (write_source_tree_tx, write_source_tree_spy) = pipe::<TreeSnapshot>();
+        let (write_provenance_tx, write_provenance_spy) =
+            pipe::<(TrackedResult, StageProvenance)>();
+        let (write_timing_tx, write_timing_spy) = pipe::<(Stage, StageTiming)>();
+        let (write_watched_tx, write_watched_spy) = pipe::<WatchedEvents>();
+        let (write_failed_test_tx, write_failed_test_spy) = pipe::<Option<String>>();
+        let (write_custom_stage_tx, write_custom_stage_spy) = pipe::<(String, CustomStageState)>();
 
         let (write_repo_root_tx, write_repo_root_spy) = pipe::<RepoRoot>();
 
@@ -35,7 +64,20 @@ impl TrackedState {
                 read_status_spy,
                 write_tests_status_spy,
                 write_check_status_spy,
+                write_fmt_status_spy,
                 write_coverage_status_spy,
+                write_coverage_summary_spy,
+                write_coverage_files_spy,
+                write_diff_coverage_spy,
+                write_quality_spy,
+                write_stats_spy,
+                write_tests_count_spy,
+                write_source_tree_spy,
+                write_provenance_spy,
+                write_timing_spy,
+                write_watched_spy,
+                write_failed_test_spy,
+                write_custom_stage_spy,
                 write_repo_root_spy,
             ),
             Arc::new(Self {
@@ -44,7 +86,20 @@ impl TrackedState {
                     state.writer(),
                     write_tests_status_tx,
                     write_check_status_tx,
+                    write_fmt_status_tx,
                     write_coverage_status_tx,
+                    write_coverage_summary_tx,
+                    write_coverage_files_tx,
+                    write_diff_coverage_tx,
+                    write_quality_tx,
+                    write_stats_tx,
+                    write_tests_count_tx,
+                    write_source_tree_tx,
+                    write_provenance_tx,
+                    write_timing_tx,
+                    write_watched_tx,
+                    write_failed_test_tx,
+                    write_custom_stage_tx,
                     write_repo_root_tx,
                 ),
             }),
@@ -82,40 +137,120 @@ impl AppStateReader for TrackedStateRead {
         self.read.tests()
     }
 
+    fn failed_test(&self) -> Result<Option<String>, StateReaderErr> {
+        self.read.failed_test()
+    }
+
     fn check(&self) -> Result<CheckState, StateReaderErr> {
         self.read.check()
     }
 
+    fn fmt(&self) -> Result<FmtState, StateReaderErr> {
+        self.read.fmt()
+    }
+
     fn coverage(&self) -> Result<CoverageState, StateReaderErr> {
         self.read.coverage()
     }
 
+    fn coverage_summary(&self) -> Result<Option<CoverageSummary>, StateReaderErr> {
+        self.read.coverage_summary()
+    }
+
+    fn coverage_files(&self) -> Result<Vec<FileCoverage>, StateReaderErr> {
+        self.read.coverage_files()
+    }
+
+    fn diff_coverage(&self) -> Result<Option<DiffCoverage>, StateReaderErr> {
+        self.read.diff_coverage()
+    }
+
+    fn quality(&self) -> Result<Option<QualityReport>, StateReaderErr> {
+        self.read.quality()
+    }
+
+    fn stats(&self) -> Result<Option<ProjectStats>, StateReaderErr> {
+        self.read.stats()
+    }
+
+    fn custom_stages(&self) -> Result<HashMap<String, CustomStageState>, StateReaderErr> {
+        self.read.custom_stages()
+    }
+
     fn repo_root(&self) -> Result<RepoRoot, StateReaderErr> {
         self.read.repo_root()
     }
+
+    fn watcher(&self) -> Result<WatcherState, StateReaderErr> {
+        self.read.watcher()
+    }
+
+    fn snapshot(&self) -> Result<StateSnapshot, StateReaderErr> {
+        self.read.snapshot()
+    }
 }
 
 pub struct TrackedStateWrite {
     write: StateWriter,
     write_tests_state_tx: Tx<TestsState>,
     write_check_state_tx: Tx<CheckState>,
+    write_fmt_state_tx: Tx<FmtState>,
     write_coverage_state_tx: Tx<CoverageState>,
+    write_coverage_summary_tx: Tx<CoverageSummary>,
+    write_coverage_files_tx: Tx<Vec<FileCoverage>>,
+    write_diff_coverage_tx: Tx<DiffCoverage>,
+    write_quality_tx: Tx<QualityReport>,
+    write_stats_tx: Tx<ProjectStats>,
+    write_tests_count_tx: Tx<u32>,
+    write_source_tree_tx: Tx<TreeSnapshot>,
+    write_provenance_tx: Tx<(TrackedResult, StageProvenance)>,
+    write_timing_tx: Tx<(Stage, StageTiming)>,
+    write_watched_tx: Tx<WatchedEvents>,
+    write_failed_test_tx: Tx<Option<String>>,
+    write_custom_stage_tx: Tx<(String, CustomStageState)>,
     write_repo_root_tx: Tx<RepoRoot>,
 }
 
 impl TrackedStateWrite {
+    #[allow(clippy::too_many_arguments)]
     fn create(
         write: StateWriter,
         write_tests_state_tx: Tx<TestsState>,
         write_check_state_tx: Tx<CheckState>,
+        write_fmt_state_tx: Tx<FmtState>,
         write_coverage_state_tx: Tx<CoverageState>,
+        write_coverage_summary_tx: Tx<CoverageSummary>,
+        write_coverage_files_tx: Tx<Vec<FileCoverage>>,
+        write_diff_coverage_tx: Tx<DiffCoverage>,
+        write_quality_tx: Tx<QualityReport>,
+        write_stats_tx: Tx<ProjectStats>,
+        write_tests_count_tx: Tx<u32>,
+        write_source_tree_tx: Tx<TreeSnapshot>,
+        write_provenance_tx: Tx<(TrackedResult, StageProvenance)>,
+        write_timing_tx: Tx<(Stage, StageTiming)>,
+        write_watched_tx: Tx<WatchedEvents>,
+        write_failed_test_tx: Tx<Option<String>>,
+        write_custom_stage_tx: Tx<(String, CustomStageState)>,
         write_repo_root_tx: Tx<RepoRoot>,
     ) -> StateWriter {
         Arc::new(Self {
             write,
             write_tests_state_tx,
             write_check_state_tx,
+            write_fmt_state_tx,
             write_coverage_state_tx,
+            write_coverage_summary_tx,
+            write_coverage_files_tx,
+            write_diff_coverage_tx,
+            write_quality_tx,
+            write_stats_tx,
+            write_tests_count_tx,
+            write_source_tree_tx,
+            write_provenance_tx,
+            write_timing_tx,
+            write_watched_tx,
+            write_failed_test_tx,
+            write_custom_stage_tx,
             write_repo_root_tx,
         })
     }
@@ -128,23 +263,113 @@ impl AppStateWriter for TrackedStateWrite {
         res
     }
 
+    fn failed_test(&self, name: Option<String>) -> Result<(), StateWriterErr> {
+        let res = self.write.failed_test(name.clone());
+        self.write_failed_test_tx.signal(name);
+        res
+    }
+
     fn check(&self, status: CheckState) -> Result<(), StateWriterErr> {
         let res = self.write.check(status.clone());
         self.write_check_state_tx.signal(status);
         res
     }
 
+    fn fmt(&self, status: FmtState) -> Result<(), StateWriterErr> {
+        let res = self.write.fmt(status.clone());
+        self.write_fmt_state_tx.signal(status);
+        res
+    }
+
     fn coverage(&self, coverage: CoverageState) -> Result<(), StateWriterErr> {
         let res = self.write.coverage(coverage.clone());
         self.write_coverage_state_tx.signal(coverage);
         res
     }
 
+    fn coverage_summary(&self, summary: CoverageSummary) -> Result<(), StateWriterErr> {
+        let res = self.write.coverage_summary(summary);
+        self.write_coverage_summary_tx.signal(summary);
+        res
+    }
+
+    fn coverage_files(&self, files: Vec<FileCoverage>) -> Result<(), StateWriterErr> {
+        let res = self.write.coverage_files(files.clone());
+        self.write_coverage_files_tx.signal(files);
+        res
+    }
+
+    fn diff_coverage(&self, diff: DiffCoverage) -> Result<(), StateWriterErr> {
+        let res = self.write.diff_coverage(diff.clone());
+        self.write_diff_coverage_tx.signal(diff);
+        res
+    }
+
+    fn quality(&self, report: QualityReport) -> Result<(), StateWriterErr> {
+        let res = self.write.quality(report.clone());
+        self.write_quality_tx.signal(report);
+        res
+    }
+
+    fn stats(&self, stats: ProjectStats) -> Result<(), StateWriterErr> {
+        let res = self.write.stats(stats);
+        self.write_stats_tx.signal(stats);
+        res
+    }
+
+    fn tests_count(&self, count: u32) -> Result<(), StateWriterErr> {
+        let res = self.write.tests_count(count);
+        self.write_tests_count_tx.signal(count);
+        res
+    }
+
+    fn source_tree(&self, tree: TreeSnapshot) -> Result<(), StateWriterErr> {
+        let res = self.write.source_tree(tree.clone());
+        self.write_source_tree_tx.signal(tree);
+        res
+    }
+
+    fn provenance(
+        &self,
+        result: TrackedResult,
+        provenance: StageProvenance,
+    ) -> Result<(), StateWriterErr> {
+        let res = self.write.provenance(result.clone(), provenance.clone());
+        self.write_provenance_tx.signal((result, provenance));
+        res
+    }
+
+    fn timing(&self, stage: Stage, timing: StageTiming) -> Result<(), StateWriterErr> {
+        let res = self.write.timing(stage, timing);
+        self.write_timing_tx.signal((stage, timing));
+        res
+    }
+
+    fn custom_stage(&self, name: String, state: CustomStageState) -> Result<(), StateWriterErr> {
+        let res = self.write.custom_stage(name.clone(), state.clone());
+        self.write_custom_stage_tx.signal((name, state));
+        res
+    }
+
     fn repo_root(&self, repo_root: RepoRoot) -> Result<(), StateWriterErr> {
         let res = self.write.repo_root(repo_root.clone());
         self.write_repo_root_tx.signal(repo_root);
         res
     }
+
+    fn trigger(&self, stage: Stage) -> Result<RunId, StateWriterErr> {
+        self.write.trigger(stage)
+    }
+
+    fn watcher(&self, watcher: WatcherState) -> Result<(), StateWriterErr> {
+        self.write.watcher(watcher)
+    }
+
+    fn watched(&self, events: WatchedEvents) -> Result<(), StateWriterErr> {
+        let res = self.write.watched(events);
+        self.write_watched_tx.signal(events);
+        res
+    }
 }
 
 pub struct StateSpies {
@@ -153,23 +378,63 @@ pub struct StateSpies {
     write_tests_status_spy: Spy<TestsState>,
     #[allow(unused)]
     write_check_status_spy: Spy<CheckState>,
+    write_fmt_status_spy: Spy<FmtState>,
     write_coverage_status_spy: Spy<CoverageState>,
+    write_coverage_summary_spy: Spy<CoverageSummary>,
+    write_coverage_files_spy: Spy<Vec<FileCoverage>>,
+    write_diff_coverage_spy: Spy<DiffCoverage>,
+    write_quality_spy: Spy<QualityReport>,
+    write_stats_spy: Spy<ProjectStats>,
+    write_tests_count_spy: Spy<u32>,
+    write_source_tree_spy: Spy<TreeSnapshot>,
+    write_provenance_spy: Spy<(TrackedResult, StageProvenance)>,
+    write_timing_spy: Spy<(Stage, StageTiming)>,
+    write_watched_spy: Spy<WatchedEvents>,
+    write_failed_test_spy: Spy<Option<String>>,
+    write_custom_stage_spy: Spy<(String, CustomStageState)>,
     write_repo_root_spy: Spy<RepoRoot>,
 }
 
 impl StateSpies {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         read_status_spy: Spy,
         write_tests_status_spy: Spy<TestsState>,
         write_check_status_spy: Spy<CheckState>,
+        write_fmt_status_spy: Spy<FmtState>,
         write_coverage_status_spy: Spy<CoverageState>,
+        write_coverage_summary_spy: Spy<CoverageSummary>,
+        write_coverage_files_spy: Spy<Vec<FileCoverage>>,
+        write_diff_coverage_spy: Spy<DiffCoverage>,
+        write_quality_spy: Spy<QualityReport>,
+        write_stats_spy: Spy<ProjectStats>,
+        write_tests_count_spy: Spy<u32>,
+        write_source_tree_spy: Spy<TreeSnapshot>,
+        write_provenance_spy: Spy<(TrackedResult, StageProvenance)>,
+        write_timing_spy: Spy<(Stage, StageTiming)>,
+        write_watched_spy: Spy<WatchedEvents>,
+        write_failed_test_spy: Spy<Option<String>>,
+        write_custom_stage_spy: Spy<(String, CustomStageState)>,
         write_repo_root_spy: Spy<RepoRoot>,
     ) -> Self {
         Self {
             read_status_spy,
             write_tests_status_spy,
             write_check_status_spy,
+            write_fmt_status_spy,
             write_coverage_status_spy,
+            write_coverage_summary_spy,
+            write_coverage_files_spy,
+            write_diff_coverage_spy,
+            write_quality_spy,
+            write_stats_spy,
+            write_tests_count_spy,
+            write_source_tree_spy,
+            write_provenance_spy,
+            write_timing_spy,
+            write_watched_spy,
+            write_failed_test_spy,
+            write_custom_stage_spy,
             write_repo_root_spy,
         }
     }
@@ -187,11 +452,83 @@ impl StateSpies {
         self.write_check_status_spy.method_called_with_val(status)
     }
 
+    pub fn fmt_state_called_with_val(&self, status: &FmtState) -> bool {
+        self.write_fmt_status_spy.method_called_with_val(status)
+    }
+
     pub fn coverage_state_called_with_val(&self, status: &CoverageState) -> bool {
         self.write_coverage_status_spy
             .method_called_with_val(status)
     }
 
+    pub fn coverage_summary_called_with_val(&self, summary: &CoverageSummary) -> bool {
+        self.write_coverage_summary_spy
+            .method_called_with_val(summary)
+    }
+
+    pub fn coverage_files_called_with_val(&self, files: &Vec<FileCoverage>) -> bool {
+        self.write_coverage_files_spy.method_called_with_val(files)
+    }
+
+    pub fn diff_coverage_called_with_val(&self, diff: &DiffCoverage) -> bool {
+        self.write_diff_coverage_spy.method_called_with_val(diff)
+    }
+
+    pub fn diff_coverage_called(&self) -> bool {
+        self.write_diff_coverage_spy.method_called()
+    }
+
+    pub fn quality_called_with_matching<F: Fn(&QualityReport) -> bool>(&self, pred: F) -> bool {
+        self.write_quality_spy.method_called_with_matching(pred)
+    }
+
+    pub fn stats_called_with_val(&self, stats: &ProjectStats) -> bool {
+        self.write_stats_spy.method_called_with_val(stats)
+    }
+
+    pub fn stats_called(&self) -> bool {
+        self.write_stats_spy.method_called()
+    }
+
+    pub fn tests_count_called_with_val(&self, count: u32) -> bool {
+        self.write_tests_count_spy.method_called_with_val(&count)
+    }
+
+    pub fn source_tree_called_with_matching<F: Fn(&TreeSnapshot) -> bool>(&self, pred: F) -> bool {
+        self.write_source_tree_spy.method_called_with_matching(pred)
+    }
+
+    pub fn provenance_called_with_matching<F>(&self, pred: F) -> bool
+    where
+        F: Fn(&(TrackedResult, StageProvenance)) -> bool,
+    {
+        self.write_provenance_spy.method_called_with_matching(pred)
+    }
+
+    pub fn timing_called_with_matching<F>(&self, pred: F) -> bool
+    where
+        F: Fn(&(Stage, StageTiming)) -> bool,
+    {
+        self.write_timing_spy.method_called_with_matching(pred)
+    }
+
+    pub fn watched_called_with_val(&self, events: &WatchedEvents) -> bool {
+        self.write_watched_spy.method_called_with_val(events)
+    }
+
+    pub fn watched_called(&self) -> bool {
+        self.write_watched_spy.method_called()
+    }
+
+    pub fn failed_test_called_with_val(&self, name: &Option<String>) -> bool {
+        self.write_failed_test_spy.method_called_with_val(name)
+    }
+
+    pub fn custom_stage_called_with_val(&self, name: &str, state: &CustomStageState) -> bool {
+        self.write_custom_stage_spy
+            .method_called_with_val(&(name.to_string(), state.clone()))
+    }
+
     #[allow(unused)]
     pub fn repo_root_called_with_val(&self, repo_root: &RepoRoot) -> bool {
         self.write_repo_root_spy.method_called_with_val(repo_root)
@@ -212,18 +549,46 @@ pub fn working() -> State {
 
 pub struct StateValues {
     pub check_state: CheckState,
+    pub fmt_state: FmtState,
     pub tests_state: TestsState,
+    pub failed_test: Option<String>,
     pub cov_state: CoverageState,
+    pub coverage_summary: Option<CoverageSummary>,
+    pub coverage_files: Vec<FileCoverage>,
+    pub diff_coverage: Option<DiffCoverage>,
+    pub quality: Option<QualityReport>,
+    pub stats: Option<ProjectStats>,
+    pub source_tree: Option<TreeSnapshot>,
+    pub provenances: HashMap<TrackedResult, StageProvenance>,
+    pub timings: HashMap<Stage, StageTiming>,
+    pub durations: HashMap<Stage, DurationStats>,
+    pub metrics: Metrics,
+    pub custom_stages: HashMap<String, CustomStageState>,
     pub repo_root: RepoRoot,
+    pub watcher_state: WatcherState,
 }
 
 impl Default for StateValues {
     fn default() -> Self {
         Self {
             check_state: CheckState::Success,
+            fmt_state: FmtState::Success,
             tests_state: TestsState::Success,
+            failed_test: None,
             cov_state: CoverageState::Success(20.0),
+            coverage_summary: None,
+            coverage_files: Vec::new(),
+            diff_coverage: None,
+            quality: None,
+            stats: None,
+            source_tree: None,
+            provenances: HashMap::new(),
+            timings: HashMap::new(),
+            durations: HashMap::new(),
+            metrics: Metrics::default(),
+            custom_stages: HashMap::new(),
             repo_root: RepoRoot::default(),
+            watcher_state: WatcherState::Watching,
         }
     }
 }
@@ -267,17 +632,70 @@ impl AppStateReader for WorkingStateRead {
         Ok(self.values.check_state.clone())
     }
 
+    fn fmt(&self) -> Result<FmtState, StateReaderErr> {
+        Ok(self.values.fmt_state.clone())
+    }
+
     fn tests(&self) -> Result<TestsState, StateReaderErr> {
         Ok(self.values.tests_state.clone())
     }
 
+    fn failed_test(&self) -> Result<Option<String>, StateReaderErr> {
+        Ok(self.values.failed_test.clone())
+    }
+
     fn coverage(&self) -> Result<CoverageState, StateReaderErr> {
         Ok(self.values.cov_state.clone())
     }
 
+    fn coverage_summary(&self) -> Result<Option<CoverageSummary>, StateReaderErr> {
+        Ok(self.values.coverage_summary)
+    }
+
+    fn coverage_files(&self) -> Result<Vec<FileCoverage>, StateReaderErr> {
+        Ok(self.values.coverage_files.clone())
+    }
+
+    fn diff_coverage(&self) -> Result<Option<DiffCoverage>, StateReaderErr> {
+        Ok(self.values.diff_coverage.clone())
+    }
+
+    fn quality(&self) -> Result<Option<QualityReport>, StateReaderErr> {
+        Ok(self.values.quality.clone())
+    }
+
+    fn stats(&self) -> Result<Option<ProjectStats>, StateReaderErr> {
+        Ok(self.values.stats)
+    }
+
+    fn custom_stages(&self) -> Result<HashMap<String, CustomStageState>, StateReaderErr> {
+        Ok(self.values.custom_stages.clone())
+    }
+
     fn repo_root(&self) -> Result<RepoRoot, StateReaderErr> {
         Ok(self.values.repo_root.clone())
     }
+
+    fn watcher(&self) -> Result<WatcherState, StateReaderErr> {
+        Ok(self.values.watcher_state.clone())
+    }
+
+    fn snapshot(&self) -> Result<StateSnapshot, StateReaderErr> {
+        Ok(StateSnapshot {
+            repo_root: self.values.repo_root.clone(),
+            watcher: self.values.watcher_state.clone(),
+            check: self.values.check_state.clone(),
+            fmt: self.values.fmt_state.clone(),
+            tests: self.values.tests_state.clone(),
+            coverage: self.values.cov_state.clone(),
+            source_tree: self.values.source_tree.clone(),
+            provenances: self.values.provenances.clone(),
+            timings: self.values.timings.clone(),
+            durations: self.values.durations.clone(),
+            metrics: self.values.metrics.clone(),
+            custom_stages: self.values.custom_stages.clone(),
+        })
+    }
 }
 
 struct WorkingStateWrite;
@@ -293,15 +711,79 @@ impl AppStateWriter for WorkingStateWrite {
         Ok(())
     }
 
+    fn failed_test(&self, _name: Option<String>) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
     fn check(&self, _status: CheckState) -> Result<(), StateWriterErr> {
         Ok(())
     }
 
+    fn fmt(&self, _status: FmtState) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
     fn coverage(&self, _coverage: CoverageState) -> Result<(), StateWriterErr> {
         Ok(())
     }
 
+    fn coverage_summary(&self, _summary: CoverageSummary) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn coverage_files(&self, _files: Vec<FileCoverage>) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn diff_coverage(&self, _diff: DiffCoverage) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn quality(&self, _report: QualityReport) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn stats(&self, _stats: ProjectStats) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn tests_count(&self, _count: u32) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn source_tree(&self, _tree: TreeSnapshot) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn provenance(
+        &self,
+        _result: TrackedResult,
+        _provenance: StageProvenance,
+    ) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn timing(&self, _stage: Stage, _timing: StageTiming) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn custom_stage(&self, _name: String, _state: CustomStageState) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
     fn repo_root(&self, _repo_root: RepoRoot) -> Result<(), StateWriterErr> {
         Ok(())
     }
+
+    fn trigger(&self, _stage: Stage) -> Result<RunId, StateWriterErr> {
+        Ok(RunId::new())
+    }
+
+    fn watcher(&self, _watcher: WatcherState) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
+
+    fn watched(&self, _events: WatchedEvents) -> Result<(), StateWriterErr> {
+        Ok(())
+    }
 }
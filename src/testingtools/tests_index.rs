@@ -29,6 +29,10 @@ impl TIndex for TrackedTestsIndex {
         self.tx.signal(());
         res
     }
+
+    fn tests_count(&self) -> Option<u32> {
+        self.index.tests_count()
+    }
 }
 
 pub struct TestsIndexSpy {
@@ -46,16 +50,21 @@ impl TestsIndexSpy {
 }
 
 pub fn working(result: IndexStatus) -> TestsIndex {
-    WorkingTestsIndex::make(result)
+    WorkingTestsIndex::make(result, None)
+}
+
+pub fn working_with_tests(result: IndexStatus, tests: u32) -> TestsIndex {
+    WorkingTestsIndex::make(result, Some(tests))
 }
 
 pub struct WorkingTestsIndex {
     result: IndexStatus,
+    tests: Option<u32>,
 }
 
 impl WorkingTestsIndex {
-    fn make(result: IndexStatus) -> TestsIndex {
-        Box::new(Self { result })
+    fn make(result: IndexStatus, tests: Option<u32>) -> TestsIndex {
+        Box::new(Self { result, tests })
     }
 }
 
@@ -63,6 +72,10 @@ impl TIndex for WorkingTestsIndex {
     fn refresh(&self, _repo_root: RepoRoot) -> Result<IndexStatus, IndexErr> {
         Ok(self.result.clone())
     }
+
+    fn tests_count(&self) -> Option<u32> {
+        self.tests
+    }
 }
 
 pub fn failing() -> TestsIndex {
@@ -81,4 +94,8 @@ impl TIndex for FailingTestsIndex {
     fn refresh(&self, _repo_root: RepoRoot) -> Result<IndexStatus, IndexErr> {
         Err(IndexErr::Bus(BusErr::Generic(anyhow!("Failure"))))
     }
+
+    fn tests_count(&self) -> Option<u32> {
+        None
+    }
 }
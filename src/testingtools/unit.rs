@@ -1,5 +1,7 @@
 use crate::configuration::factories::event_bus;
+use crate::entities::quality::HalsteadSummary;
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
 use crate::use_cases::bus::{BusEvent, EventBus, EventPublisher, EventSubscriber};
 use crate::use_cases::change_watcher::ChangeWatcher;
 
@@ -57,21 +59,101 @@ impl TestShim {
         Ok(())
     }
 
+    pub fn simulate_run_requested(&self, run_id: RunId) -> Result<()> {
+        self.publ.send(BusEvent::RunRequested(run_id))?;
+        Ok(())
+    }
+
+    pub fn simulate_check_failed(&self) -> Result<()> {
+        self.publ.send(BusEvent::CheckFailed)?;
+        Ok(())
+    }
+
+    pub fn simulate_check_warned(&self) -> Result<()> {
+        self.publ.send(BusEvent::CheckWarned)?;
+        Ok(())
+    }
+
+    pub fn simulate_fmt_passed(&self) -> Result<()> {
+        self.publ.send(BusEvent::FmtPassed)?;
+        Ok(())
+    }
+
+    pub fn simulate_fmt_failed(&self) -> Result<()> {
+        self.publ.send(BusEvent::FmtFailed)?;
+        Ok(())
+    }
+
     pub fn simulate_check_passed(&self) -> Result<()> {
         self.publ.send(BusEvent::CheckPassed)?;
         Ok(())
     }
 
+    pub fn simulate_tests_requested(&self) -> Result<()> {
+        self.publ.send(BusEvent::TestsRequested)?;
+        Ok(())
+    }
+
     pub fn simulate_tests_passed(&self) -> Result<()> {
         self.publ.send(BusEvent::TestsPassed)?;
         Ok(())
     }
 
+    pub fn simulate_tests_failed(&self) -> Result<()> {
+        self.publ.send(BusEvent::TestsFailed)?;
+        Ok(())
+    }
+
+    pub fn simulate_coverage_passed(&self) -> Result<()> {
+        self.publ.send(BusEvent::CoveragePassed)?;
+        Ok(())
+    }
+
+    pub fn simulate_coverage_failed(&self) -> Result<()> {
+        self.publ.send(BusEvent::CoverageFailed)?;
+        Ok(())
+    }
+
+    pub fn simulate_stage_passed(&self, name: &str) -> Result<()> {
+        self.publ.send(BusEvent::StagePassed(name.into()))?;
+        Ok(())
+    }
+
+    pub fn simulate_stage_failed(&self, name: &str) -> Result<()> {
+        self.publ.send(BusEvent::StageFailed(name.into()))?;
+        Ok(())
+    }
+
     pub fn simulate_tests_changed(&self) -> Result<()> {
         self.publ.send(BusEvent::TestsSetChanged)?;
         Ok(())
     }
 
+    pub fn simulate_index_failed(&self) -> Result<()> {
+        self.publ.send(BusEvent::IndexFailed)?;
+        Ok(())
+    }
+
+    pub fn simulate_tests_not_changed(&self) -> Result<()> {
+        self.publ.send(BusEvent::TestsSetNotChanged)?;
+        Ok(())
+    }
+
+    pub fn simulate_coverage_requested(&self) -> Result<()> {
+        self.publ.send(BusEvent::CoverageRequested)?;
+        Ok(())
+    }
+
+    pub fn simulate_quality_calculated(&self, halstead: Option<HalsteadSummary>) -> Result<()> {
+        self.publ.send(BusEvent::QualityCalculated(halstead))?;
+        Ok(())
+    }
+
+    pub fn simulate_coverage_skipped(&self) -> Result<()> {
+        self.publ.send(BusEvent::CoverageSkipped)?;
+        Ok(())
+    }
+
     pub fn bus(&self) -> EventBus {
         self.bus.clone()
     }
@@ -180,8 +262,8 @@ pub fn run_watcher(watcher: ChangeWatcher, repo_root: RepoRoot) -> (Controller,
     let (repo_root_tx, repo_root_rx) = channel();
     let handle = thread::spawn(move || -> Result<()> {
         loop {
-            let repo_root = repo_root_rx.recv()?;
-            watcher.wait_for_change(repo_root)?;
+            let repo_root: RepoRoot = repo_root_rx.recv()?;
+            while !watcher.wait_for_events(repo_root.clone())?.has_change() {}
             detector_tx.send(())?;
         }
     });
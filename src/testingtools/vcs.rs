@@ -0,0 +1,67 @@
+use crate::entities::diff_coverage::ChangedLines;
+use crate::entities::provenance::Fingerprint;
+use crate::entities::repo_root::RepoRoot;
+use crate::result::{CmdErr, VcsErr};
+use crate::use_cases::vcs::{Vcs, VersionControl};
+
+use std::io;
+use std::sync::Arc;
+
+pub fn working(head: Option<String>) -> VersionControl {
+    WorkingVcs::make(head, Vec::new())
+}
+
+pub fn working_with_changes(changes: Vec<ChangedLines>) -> VersionControl {
+    WorkingVcs::make(None, changes)
+}
+
+pub struct WorkingVcs {
+    head: Option<String>,
+    changes: Vec<ChangedLines>,
+}
+
+impl WorkingVcs {
+    fn make(head: Option<String>, changes: Vec<ChangedLines>) -> VersionControl {
+        Arc::new(Self { head, changes })
+    }
+}
+
+impl Vcs for WorkingVcs {
+    fn head(&self, _repo_root: RepoRoot) -> Result<Option<String>, VcsErr> {
+        Ok(self.head.clone())
+    }
+
+    fn changes(&self, _repo_root: RepoRoot) -> Result<Vec<ChangedLines>, VcsErr> {
+        Ok(self.changes.clone())
+    }
+
+    fn fingerprint(&self, _repo_root: RepoRoot) -> Result<Fingerprint, VcsErr> {
+        Ok(Fingerprint::new(self.head.clone(), None))
+    }
+}
+
+pub fn failing() -> VersionControl {
+    FailingVcs::make()
+}
+
+pub struct FailingVcs;
+
+impl FailingVcs {
+    fn make() -> VersionControl {
+        Arc::new(Self)
+    }
+}
+
+impl Vcs for FailingVcs {
+    fn head(&self, _repo_root: RepoRoot) -> Result<Option<String>, VcsErr> {
+        Err(VcsErr::Cmd(CmdErr::Io(io::Error::other("Failure"))))
+    }
+
+    fn changes(&self, _repo_root: RepoRoot) -> Result<Vec<ChangedLines>, VcsErr> {
+        Err(VcsErr::Cmd(CmdErr::Io(io::Error::other("Failure"))))
+    }
+
+    fn fingerprint(&self, _repo_root: RepoRoot) -> Result<Fingerprint, VcsErr> {
+        Err(VcsErr::Cmd(CmdErr::Io(io::Error::other("Failure"))))
+    }
+}
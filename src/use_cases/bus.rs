@@ -1,3 +1,6 @@
+use crate::entities::quality::HalsteadSummary;
+use crate::entities::run_id::RunId;
+use crate::entities::stage::Stage;
 use crate::result::BusErr;
 
 use std::fmt::Debug;
@@ -22,14 +25,135 @@ pub trait Subscriber: Sync + Send {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BusEvent {
+    RunRequested(RunId),
     ChangeDetected,
 
     CheckPassed,
     CheckFailed,
+    /// Check failed only on the warnings denied as errors and the pipeline continues past it.
+    CheckWarned,
 
+    FmtPassed,
+    FmtFailed,
+
+    /// Tests were requested via the API, without running the check first.
+    TestsRequested,
     TestsPassed,
     TestsFailed,
 
     TestsSetChanged,
     TestsSetNotChanged,
+    /// Tests index couldn't be refreshed, so it's not known whether the tests set changed.
+    IndexFailed,
+
+    CoverageRequested,
+    CoverageSkipped,
+    CoveragePassed,
+    CoverageFailed,
+
+    WatcherPaused,
+    WatcherResumed,
+
+    /// Quality of the code was calculated after the change, with the Halstead metrics of the
+    /// whole repo. There are no metrics when the code couldn't be analyzed.
+    QualityCalculated(Option<HalsteadSummary>),
+
+    /// Custom stage with the given name passed.
+    StagePassed(String),
+    /// Custom stage with the given name failed.
+    StageFailed(String),
+}
+
+impl BusEvent {
+    /// Stage which starts after this event.
+    pub fn started_stage(&self) -> Option<Stage> {
+        match self {
+            BusEvent::ChangeDetected => Some(Stage::Check),
+            BusEvent::CheckPassed | BusEvent::CheckWarned | BusEvent::TestsRequested => {
+                Some(Stage::Tests)
+            }
+            BusEvent::CoverageRequested => Some(Stage::Coverage),
+            _ => None,
+        }
+    }
+
+    /// Stage which finished with this event.
+    pub fn finished_stage(&self) -> Option<Stage> {
+        match self {
+            BusEvent::CheckPassed | BusEvent::CheckFailed | BusEvent::CheckWarned => {
+                Some(Stage::Check)
+            }
+            BusEvent::TestsPassed | BusEvent::TestsFailed => Some(Stage::Tests),
+            BusEvent::CoveragePassed | BusEvent::CoverageFailed => Some(Stage::Coverage),
+            _ => None,
+        }
+    }
+
+    /// Name of the custom stage which finished with this event.
+    pub fn custom_stage(&self) -> Option<&str> {
+        match self {
+            BusEvent::StagePassed(name) | BusEvent::StageFailed(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Name of the event, used to configure hooks.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BusEvent::RunRequested(_) => "run-requested",
+            BusEvent::ChangeDetected => "change-detected",
+            BusEvent::CheckPassed => "check-passed",
+            BusEvent::CheckFailed => "check-failed",
+            BusEvent::CheckWarned => "check-warned",
+            BusEvent::FmtPassed => "fmt-passed",
+            BusEvent::FmtFailed => "fmt-failed",
+            BusEvent::TestsRequested => "tests-requested",
+            BusEvent::TestsPassed => "tests-passed",
+            BusEvent::TestsFailed => "tests-failed",
+            BusEvent::TestsSetChanged => "tests-set-changed",
+            BusEvent::TestsSetNotChanged => "tests-set-not-changed",
+            BusEvent::IndexFailed => "index-failed",
+            BusEvent::CoverageRequested => "coverage-requested",
+            BusEvent::CoverageSkipped => "coverage-skipped",
+            BusEvent::CoveragePassed => "coverage-passed",
+            BusEvent::CoverageFailed => "coverage-failed",
+            BusEvent::WatcherPaused => "watcher-paused",
+            BusEvent::WatcherResumed => "watcher-resumed",
+            BusEvent::QualityCalculated(_) => "quality-calculated",
+            BusEvent::StagePassed(_) => "stage-passed",
+            BusEvent::StageFailed(_) => "stage-failed",
+        }
+    }
+
+    pub fn is_name(name: &str) -> bool {
+        Self::examples().iter().any(|event| event.name() == name)
+    }
+
+    /// One event of every kind, data of the event is a placeholder.
+    fn examples() -> [BusEvent; 22] {
+        [
+            BusEvent::RunRequested(RunId::new()),
+            BusEvent::ChangeDetected,
+            BusEvent::CheckPassed,
+            BusEvent::CheckFailed,
+            BusEvent::CheckWarned,
+            BusEvent::FmtPassed,
+            BusEvent::FmtFailed,
+            BusEvent::TestsRequested,
+            BusEvent::TestsPassed,
+            BusEvent::TestsFailed,
+            BusEvent::TestsSetChanged,
+            BusEvent::TestsSetNotChanged,
+            BusEvent::IndexFailed,
+            BusEvent::CoverageRequested,
+            BusEvent::CoverageSkipped,
+            BusEvent::CoveragePassed,
+            BusEvent::CoverageFailed,
+            BusEvent::WatcherPaused,
+            BusEvent::WatcherResumed,
+            BusEvent::QualityCalculated(None),
+            BusEvent::StagePassed(String::new()),
+            BusEvent::StageFailed(String::new()),
+        ]
+    }
 }
@@ -1,8 +1,9 @@
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::watcher::WatchedEvents;
 use crate::result::WatcherErr;
 
 pub type ChangeWatcher = Box<dyn Watcher>;
 
 pub trait Watcher: Send {
-    fn wait_for_change(&self, path: RepoRoot) -> Result<(), WatcherErr>;
+    fn wait_for_events(&self, path: RepoRoot) -> Result<WatchedEvents, WatcherErr>;
 }
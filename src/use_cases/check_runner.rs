@@ -12,4 +12,6 @@ pub trait CRunner: Send {
 pub enum CheckRunStatus {
     Success,
     Failure,
+    /// Check failed only because of the warnings denied as errors, eg. with `-D warnings`.
+    Warnings,
 }
@@ -1,3 +1,5 @@
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::file_coverage::FileCoverage;
 use crate::entities::repo_root::RepoRoot;
 use crate::result::CoverageErr;
 
@@ -10,6 +12,6 @@ pub trait CovRunner: Send {
 #[allow(unused)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum CoverageRunStatus {
-    Success(f32),
+    Success(CoverageSummary, Vec<FileCoverage>),
     Failure,
 }
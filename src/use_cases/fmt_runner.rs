@@ -0,0 +1,15 @@
+use crate::entities::repo_root::RepoRoot;
+use crate::result::FmtErr;
+
+pub type FmtRunner = Box<dyn FRunner>;
+
+pub trait FRunner: Send {
+    fn run(&self, repo_root: RepoRoot) -> Result<FmtRunStatus, FmtErr>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FmtRunStatus {
+    Success,
+    /// Files which are not formatted, relative to the repo root.
+    Failure(Vec<String>),
+}
@@ -0,0 +1,36 @@
+use crate::entities::pipeline_run::PipelineRun;
+use crate::entities::repo_root::RepoRoot;
+use crate::result::HistoryErr;
+
+use std::sync::Arc;
+
+pub type History = Arc<dyn RunHistory>;
+
+pub trait RunHistory: Send + Sync {
+    fn append(&self, run: PipelineRun) -> Result<(), HistoryErr>;
+    fn runs(&self, query: &HistoryQuery) -> Result<Vec<PipelineRun>, HistoryErr>;
+}
+
+/// Selects the page of the pipeline runs, optionally only for a single repository.
+///
+/// Runs are ordered from the newest to the oldest one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryQuery {
+    pub repo_root: Option<RepoRoot>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl HistoryQuery {
+    pub fn all() -> Self {
+        Self {
+            repo_root: None,
+            offset: 0,
+            limit: usize::MAX,
+        }
+    }
+
+    pub fn matches(&self, run: &PipelineRun) -> bool {
+        self.repo_root.as_ref().is_none_or(|r| run.repo_root == *r)
+    }
+}
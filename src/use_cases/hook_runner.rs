@@ -0,0 +1,10 @@
+use crate::entities::hook::{HookAction, HookPayload};
+use crate::result::HookRunErr;
+
+use std::sync::Arc;
+
+pub type HookRunner = Arc<dyn RunHook>;
+
+pub trait RunHook: Send + Sync {
+    fn run(&self, action: &HookAction, payload: &HookPayload) -> Result<(), HookRunErr>;
+}
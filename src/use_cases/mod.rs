@@ -2,8 +2,16 @@ pub mod bus;
 pub mod change_watcher;
 pub mod check_runner;
 pub mod coverage_runner;
+pub mod fmt_runner;
+pub mod history;
+pub mod hook_runner;
+pub mod notifier;
 pub mod output_parser;
+pub mod pipeline;
+pub mod quality_analyzer;
 pub mod services;
+pub mod stage_runner;
 pub mod state;
 pub mod test_runner;
 pub mod tests_index;
+pub mod vcs;
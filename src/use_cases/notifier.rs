@@ -0,0 +1,9 @@
+use crate::entities::notification::Notification;
+use crate::result::NotifierErr;
+
+pub type Notifier = Box<dyn Notify>;
+
+/// Sink delivering notifications to the user.
+pub trait Notify: Send {
+    fn notify(&self, notification: Notification) -> Result<(), NotifierErr>;
+}
@@ -0,0 +1,125 @@
+use crate::configuration::config::Config;
+use crate::entities::fmt::FmtMode;
+use crate::entities::stage::Stage;
+use crate::use_cases::bus::BusEvent;
+
+/// Order of the built-in stages, accordingly to `Config::fmt_mode` and
+/// `Config::continue_on_failure`.
+///
+/// The shells which follow the built-in stages share it, so they agree on which event starts
+/// the stage and which failure ends the run.
+#[derive(Debug, Default, Clone)]
+pub struct Pipeline {
+    fmt_mode: FmtMode,
+    continue_on_failure: Vec<Stage>,
+}
+
+impl Pipeline {
+    pub fn new(cfg: &Config) -> Self {
+        let fmt_mode = cfg.fmt_mode;
+        let continue_on_failure = cfg.continue_on_failure.clone();
+        Self {
+            fmt_mode,
+            continue_on_failure,
+        }
+    }
+
+    /// Whether the tests are run only after the formatting check passed.
+    pub fn fmt_blocking(&self) -> bool {
+        self.fmt_mode == FmtMode::Blocking
+    }
+
+    /// Whether the later stages are run after the stage failed.
+    pub fn continues_after(&self, failed: Stage) -> bool {
+        self.continue_on_failure.contains(&failed)
+    }
+
+    /// Whether the formatting check is started with the event.
+    pub fn starts_fmt(&self, event: &BusEvent) -> bool {
+        match self.fmt_mode {
+            FmtMode::Off => false,
+            FmtMode::Informational => *event == BusEvent::ChangeDetected,
+            FmtMode::Blocking => self.check_done(event),
+        }
+    }
+
+    /// Whether the tests are started with the event. Tests requested via the API are started
+    /// right away.
+    pub fn starts_tests(&self, event: &BusEvent) -> bool {
+        if *event == BusEvent::TestsRequested {
+            true
+        } else if self.fmt_blocking() {
+            *event == BusEvent::FmtPassed
+        } else {
+            self.check_done(event)
+        }
+    }
+
+    /// Name of the stage whose failure ends the run, so the later stages are not run.
+    pub fn stopped_by(&self, event: &BusEvent) -> Option<String> {
+        match event {
+            BusEvent::CheckFailed | BusEvent::TestsFailed => event
+                .finished_stage()
+                .filter(|failed| !self.continues_after(*failed))
+                .map(|failed| failed.to_string()),
+            BusEvent::FmtFailed if self.fmt_blocking() => Some("fmt".into()),
+            _ => None,
+        }
+    }
+
+    /// Whether the check finished and the run goes on after it.
+    fn check_done(&self, event: &BusEvent) -> bool {
+        match event {
+            BusEvent::CheckPassed | BusEvent::CheckWarned => true,
+            BusEvent::CheckFailed => self.continues_after(Stage::Check),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+
+    #[test]
+    fn with_blocking_fmt_tests_are_started_only_after_fmt_passed() {
+        // given
+        init_tracing();
+        let pipeline = Pipeline::new(&Config {
+            fmt_mode: FmtMode::Blocking,
+            ..Config::default()
+        });
+
+        // then
+        assert!(pipeline.starts_fmt(&BusEvent::CheckPassed));
+        assert!(!pipeline.starts_tests(&BusEvent::CheckPassed));
+        assert!(pipeline.starts_tests(&BusEvent::FmtPassed));
+        assert!(pipeline.starts_tests(&BusEvent::TestsRequested));
+        assert!(!pipeline.starts_fmt(&BusEvent::TestsRequested));
+        assert_eq!(
+            pipeline.stopped_by(&BusEvent::FmtFailed),
+            Some("fmt".into())
+        );
+    }
+
+    #[test]
+    fn failed_stage_stops_the_run_unless_it_continues_on_failure() {
+        // given
+        init_tracing();
+        let pipeline = Pipeline::new(&Config {
+            continue_on_failure: vec![Stage::Check],
+            ..Config::default()
+        });
+
+        // then
+        assert!(pipeline.starts_tests(&BusEvent::CheckFailed));
+        assert_eq!(pipeline.stopped_by(&BusEvent::CheckFailed), None);
+        assert_eq!(
+            pipeline.stopped_by(&BusEvent::TestsFailed),
+            Some("tests".into())
+        );
+        assert_eq!(pipeline.stopped_by(&BusEvent::FmtFailed), None);
+    }
+}
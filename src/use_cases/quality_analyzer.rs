@@ -0,0 +1,17 @@
+use crate::entities::quality::QualityReport;
+use crate::entities::repo_root::RepoRoot;
+use crate::entities::stats::ProjectStats;
+use crate::result::QualityErr;
+
+pub type QualityAnalyzer = Box<dyn QAnalyzer>;
+
+pub trait QAnalyzer: Send {
+    fn analyze(&self, repo_root: RepoRoot) -> Result<CodeAnalysis, QualityErr>;
+}
+
+/// Quality metrics and statistics of the code, both collected in a single pass over the files.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CodeAnalysis {
+    pub quality: QualityReport,
+    pub stats: ProjectStats,
+}
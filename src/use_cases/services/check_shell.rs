@@ -1,3 +1,4 @@
+use crate::configuration::config::Config;
 use crate::entities::check::CheckState;
 use crate::result::CheckErr;
 use crate::use_cases::bus::{BusEvent, EventBus};
@@ -20,19 +21,27 @@ type Result<T> = std::result::Result<T, CheckErr>;
 /// It publishes following events:
 /// - `BusEvent::CheckPassed` - when change was detected and check passed as well
 /// - `BusEvent::CheckFailed` - when change is detected, but check failed
+/// - `BusEvent::CheckWarned` - when check failed only on the warnings denied as errors and
+///   `Config::continue_on_warnings` is set
 pub struct CheckShell {
     bus: EventBus,
+    continue_on_warnings: bool,
 }
 
 impl CheckShell {
-    pub fn new(bus: EventBus) -> Self {
-        Self { bus }
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let continue_on_warnings = cfg.continue_on_warnings;
+        Self {
+            bus,
+            continue_on_warnings,
+        }
     }
 
     #[instrument(skip(self, cr, st))]
     pub fn run(self, cr: CheckRunner, st: State) {
         let sub = self.bus.subscriber();
         let publ = self.bus.publisher();
+        let continue_on_warnings = self.continue_on_warnings;
         thread::spawn(move || -> Result<()> {
             let sw = st.writer();
             loop {
@@ -43,7 +52,16 @@ impl CheckShell {
 
                 debug!("running check");
                 sw.check(CheckState::Pending)?;
-                let Ok(CheckRunStatus::Success) = cr.run(st.reader().repo_root()?) else {
+                let status = cr.run(st.reader().repo_root()?);
+                if let Ok(CheckRunStatus::Warnings) = status {
+                    if continue_on_warnings {
+                        debug!("check failed on warnings only");
+                        sw.check(CheckState::Failure)?;
+                        publ.send(BusEvent::CheckWarned)?;
+                        continue;
+                    }
+                }
+                let Ok(CheckRunStatus::Success) = status else {
                     debug!("check failed");
                     sw.check(CheckState::Failure)?;
                     publ.send(BusEvent::CheckFailed)?;
@@ -76,7 +94,7 @@ mod test {
         let (check_runner_spy, check_runner) = tracked(working(CheckRunStatus::Success));
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, noop_state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, noop_state);
 
         // when
         shim.simulate_change()?;
@@ -94,7 +112,7 @@ mod test {
         let check_runner = working(CheckRunStatus::Success);
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, noop_state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, noop_state);
 
         // when
         shim.simulate_change()?;
@@ -113,7 +131,7 @@ mod test {
         let check_runner = working(CheckRunStatus::Success);
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, state);
 
         // when
         shim.simulate_change()?;
@@ -133,7 +151,52 @@ mod test {
         let check_runner = working(CheckRunStatus::Failure);
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, noop_state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, noop_state);
+
+        // when
+        shim.simulate_change()?;
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CheckFailed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_check_fails_on_warnings_only_and_continues_then_check_warned_is_sent() -> Result<()> {
+        // given
+        init_tracing();
+        let check_runner = working(CheckRunStatus::Warnings);
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            continue_on_warnings: true,
+            ..Config::default()
+        };
+        CheckShell::new(shim.bus(), cfg).run(check_runner, state);
+
+        // when
+        shim.simulate_change()?;
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CheckWarned)?);
+        assert!(spy.check_state_called_with_val(&CheckState::Pending));
+        assert!(spy.check_state_called_with_val(&CheckState::Failure));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_check_fails_on_warnings_only_and_does_not_continue_then_check_failed_is_sent(
+    ) -> Result<()> {
+        // given
+        init_tracing();
+        let check_runner = working(CheckRunStatus::Warnings);
+        let noop_state = state::noop();
+        let shim = create_test_shim()?;
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, noop_state);
 
         // when
         shim.simulate_change()?;
@@ -152,7 +215,7 @@ mod test {
         let check_runner = working(CheckRunStatus::Failure);
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, state);
 
         // when
         shim.simulate_change()?;
@@ -172,7 +235,7 @@ mod test {
         let check_runner = failing();
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, noop_state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, noop_state);
 
         // when
         shim.simulate_change()?;
@@ -191,7 +254,7 @@ mod test {
         let check_runner = failing();
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        CheckShell::new(shim.bus()).run(check_runner, state);
+        CheckShell::new(shim.bus(), Config::default()).run(check_runner, state);
 
         // when
         shim.simulate_change()?;
@@ -1,7 +1,11 @@
-use crate::entities::coverage::CoverageState;
+use crate::configuration::config::Config;
+use crate::entities::coverage::{
+    CoverageBaseline, CoverageState, MinimumCoverage, RegressionThreshold,
+};
 use crate::result::CoverageErr;
 use crate::use_cases::bus::{BusEvent, EventBus};
 use crate::use_cases::coverage_runner::{CoverageRunStatus, CoverageRunner};
+use crate::use_cases::pipeline::Pipeline;
 use crate::use_cases::state::State;
 
 use std::thread;
@@ -9,70 +13,165 @@ use tracing::{debug, instrument, trace};
 
 type Result<T> = std::result::Result<T, CoverageErr>;
 
-/// When tests set changed, it runs code coverage, updates coverage state and publishes result of
-/// the coverage.
+/// When coverage is requested, it runs code coverage, updates coverage state and publishes result
+/// of the coverage.
 ///
-/// `CoverageShell` first waits for the event describing the tests set.
-/// If tests set did not change, nothing happens.
-/// If tests set changed, `CoverageShell` sets the coverage state as `CoverageState::Pending`, then
-/// runs the tests coverage.
-/// Coverage state is updated accordingly to the result of the coverage.
+/// `CoverageShell` waits for `BusEvent::CoverageRequested`, which is published accordingly to the
+/// coverage trigger policy (see `CoverageTriggerShell`) or when coverage is run via the API.
+/// Then it sets the coverage state as `CoverageState::Pending` and runs the tests coverage.
+/// Coverage state is updated accordingly to the result of the coverage. When coverage succeeds,
+/// coverage summary and per-file coverage are updated as well.
+/// When regression threshold is configured, the calculated coverage is compared against the best
+/// of the recent results (`CoverageBaseline`) and marked as `CoverageState::Regressed` if it
+/// dropped too much.
+/// When the coverage of the project or of any file is lower than the configured minimum, it's
+/// marked as `CoverageState::BelowThreshold` instead.
+/// When the check or the tests failed, the coverage state is set as `CoverageState::Skipped`,
+/// unless the failed stage is listed in `Config::continue_on_failure`. The same happens when the
+/// blocking formatting check failed. When coverage is skipped after the tests passed again
+/// (`BusEvent::CoverageSkipped`), the state from before the failed stages is restored, as its
+/// result still holds.
 ///
-/// It's the end of the pipeline, no event is published.
+/// It publishes following events:
+/// - `BusEvent::CoveragePassed` - when coverage was calculated
+/// - `BusEvent::CoverageFailed` - when coverage failed, regressed with
+///   `Severity::Failure` or is below the minimum
 pub struct CoverageShell {
     bus: EventBus,
+    regression: Option<RegressionThreshold>,
+    minimum: MinimumCoverage,
+    pipeline: Pipeline,
 }
 
 impl CoverageShell {
-    pub fn new(bus: EventBus) -> Self {
-        Self { bus }
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let pipeline = Pipeline::new(&cfg);
+        let regression = cfg.coverage_regression;
+        let minimum = cfg.coverage_minimum;
+        Self {
+            bus,
+            regression,
+            minimum,
+            pipeline,
+        }
     }
 
     #[instrument(skip(self, cr, st))]
     pub fn run(self, cr: CoverageRunner, st: State) {
         let sub = self.bus.subscriber();
+        let publ = self.bus.publisher();
+        let regression = self.regression;
+        let minimum = self.minimum;
+        let pipeline = self.pipeline;
         thread::spawn(move || -> Result<()> {
             let sw = st.writer();
+            let mut baseline = CoverageBaseline::default();
+            let mut before_skip = None;
             loop {
-                let Ok(BusEvent::TestsSetChanged) = sub.recv() else {
-                    trace!("no change detected");
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
                     continue;
                 };
+                match event {
+                    BusEvent::CoverageRequested => {}
+                    BusEvent::CheckFailed | BusEvent::FmtFailed | BusEvent::TestsFailed => {
+                        if let Some(failed) = pipeline.stopped_by(&event) {
+                            debug!("{failed} failed, skipping coverage");
+                            let curr = st.reader().coverage()?;
+                            // NOTE: The baseline is kept, so the next coverage is still compared.
+                            baseline.seed(curr.baseline());
+                            before_skip = before_skip.or(unskipped(curr));
+                            sw.coverage(CoverageState::Skipped {
+                                reason: format!("{failed} failed"),
+                            })?;
+                        }
+                        continue;
+                    }
+                    BusEvent::CoverageSkipped => {
+                        if let Some(state) = before_skip.take() {
+                            debug!("coverage skipped after passed tests, restoring previous state");
+                            sw.coverage(state)?;
+                        }
+                        continue;
+                    }
+                    _ => {
+                        trace!("coverage not requested");
+                        continue;
+                    }
+                }
 
                 debug!("running coverage");
+                before_skip = None;
+                baseline.seed(st.reader().coverage()?.baseline());
                 sw.coverage(CoverageState::Pending)?;
-                let Ok(CoverageRunStatus::Success(val)) = cr.run(st.reader().repo_root()?) else {
+                let repo_root = st.reader().repo_root()?;
+                let Ok(CoverageRunStatus::Success(summary, files)) = cr.run(repo_root) else {
                     debug!("coverage failed");
                     sw.coverage(CoverageState::Failure)?;
+                    publ.send(BusEvent::CoverageFailed)?;
                     continue;
                 };
 
-                debug!("coverage calculated: {val}");
-                sw.coverage(CoverageState::Success(val))?;
+                let val = summary.lines;
+                debug!("coverage calculated: {summary:?}");
+                let state = match (regression, baseline.value()) {
+                    (Some(threshold), Some(baseline)) => threshold.evaluate(val, baseline),
+                    _ => CoverageState::Success(val),
+                };
+                baseline.add(val);
+                let state = minimum.evaluate(val, &files).unwrap_or(state);
+                let failed = state.is_failure();
+                sw.coverage_files(files)?;
+                sw.coverage_summary(summary)?;
+                sw.coverage(state)?;
+                if failed {
+                    debug!("coverage regressed or below the minimum");
+                    publ.send(BusEvent::CoverageFailed)?;
+                } else {
+                    publ.send(BusEvent::CoveragePassed)?;
+                }
             }
         });
     }
 }
 
+/// Coverage state which can be restored after the stages before the coverage pass again.
+fn unskipped(state: CoverageState) -> Option<CoverageState> {
+    match state {
+        CoverageState::Skipped { .. } => None,
+        state => Some(state),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     use crate::configuration::tracing::init_tracing;
+    use crate::entities::coverage::{FileMinimum, Severity};
+    use crate::entities::coverage_summary::CoverageSummary;
+    use crate::entities::file_coverage::FileCoverage;
+    use crate::entities::fmt::FmtMode;
+    use crate::entities::stage::Stage;
     use crate::testingtools::coverage_runner::{failing, tracked, working};
-    use crate::testingtools::state;
+    use crate::testingtools::state::{self, StateValues};
     use crate::testingtools::unit::create_test_shim;
 
     use anyhow::Result;
+    use glob::Pattern;
+    use std::collections::BTreeMap;
 
     #[test]
     fn coverage_is_not_started_when_change_is_detected() -> Result<()> {
         // given
         init_tracing();
-        let (cov_runner_spy, cov_runner) = tracked(working(CoverageRunStatus::Success(20.0)));
+        let (cov_runner_spy, cov_runner) = tracked(working(CoverageRunStatus::Success(
+            CoverageSummary::new(20.0),
+            Vec::new(),
+        )));
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        CoverageShell::new(shim.bus()).run(cov_runner, noop_state);
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, noop_state);
 
         // when
         shim.simulate_change()?;
@@ -84,16 +183,19 @@ mod test {
     }
 
     #[test]
-    fn coverage_is_started_when_tests_changed() -> Result<()> {
+    fn coverage_is_started_when_requested() -> Result<()> {
         // given
         init_tracing();
-        let (cov_runner_spy, cov_runner) = tracked(working(CoverageRunStatus::Success(20.0)));
+        let (cov_runner_spy, cov_runner) = tracked(working(CoverageRunStatus::Success(
+            CoverageSummary::new(20.0),
+            Vec::new(),
+        )));
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        CoverageShell::new(shim.bus()).run(cov_runner, noop_state);
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, noop_state);
 
         // when
-        shim.simulate_tests_changed()?;
+        shim.simulate_coverage_requested()?;
 
         // then
         assert!(cov_runner_spy.run_called());
@@ -101,18 +203,62 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn when_coverage_pass_there_is_correct_event_on_the_bus() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(20.0),
+            Vec::new(),
+        ));
+        let noop_state = state::noop();
+        let shim = create_test_shim()?;
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, noop_state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoveragePassed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_fail_there_is_correct_event_on_the_bus() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Failure);
+        let noop_state = state::noop();
+        let shim = create_test_shim()?;
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, noop_state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageFailed)?);
+
+        Ok(())
+    }
+
     #[test]
     fn when_coverage_pass_state_is_set_to_pending_then_failure() -> Result<()> {
         // given
         init_tracing();
-        let cov_runner = working(CoverageRunStatus::Success(20.0));
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(20.0),
+            Vec::new(),
+        ));
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        CoverageShell::new(shim.bus()).run(cov_runner, state);
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, state);
 
         // when
-        shim.simulate_tests_changed()?;
-        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
 
         // then
         assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
@@ -128,11 +274,11 @@ mod test {
         let coverage_runner = working(CoverageRunStatus::Failure);
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        CoverageShell::new(shim.bus()).run(coverage_runner, state);
+        CoverageShell::new(shim.bus(), Config::default()).run(coverage_runner, state);
 
         // when
-        shim.simulate_tests_changed()?;
-        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
 
         // then
         assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
@@ -148,11 +294,11 @@ mod test {
         let coverage_runner = failing();
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        CoverageShell::new(shim.bus()).run(coverage_runner, state);
+        CoverageShell::new(shim.bus(), Config::default()).run(coverage_runner, state);
 
         // when
-        shim.simulate_tests_changed()?;
-        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
 
         // then
         assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
@@ -160,4 +306,347 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn when_coverage_drops_below_failure_threshold_it_is_marked_as_regressed() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_threshold(RegressionThreshold::new(5.0, Severity::Failure));
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+        assert!(
+            spy.coverage_state_called_with_val(&CoverageState::Regressed {
+                actual: 70.0,
+                baseline: 80.0,
+                severity: Severity::Failure,
+            })
+        );
+        assert!(shim.event_on_bus(&BusEvent::CoverageFailed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_is_skipped_when_tests_failed_and_baseline_is_kept() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_threshold(RegressionThreshold::new(5.0, Severity::Failure));
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_tests_failed()?;
+        shim.simulate_coverage_requested()?;
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Skipped {
+            reason: "tests failed".into()
+        }));
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+        assert!(
+            spy.coverage_state_called_with_val(&CoverageState::Regressed {
+                actual: 70.0,
+                baseline: 80.0,
+                severity: Severity::Failure,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_state_is_restored_when_coverage_is_skipped_after_tests_passed_again() -> Result<()>
+    {
+        // given
+        init_tracing();
+        let (cov_runner_spy, cov_runner) = tracked(working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        )));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, state);
+        shim.simulate_tests_failed()?;
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Skipped {
+            reason: "tests failed".into()
+        }));
+
+        // when
+        shim.simulate_tests_passed()?;
+        shim.simulate_coverage_skipped()?;
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Success(80.0)));
+        assert!(!cov_runner_spy.run_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_is_not_skipped_when_tests_continue_on_failure() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            continue_on_failure: vec![Stage::Tests],
+            ..Config::default()
+        };
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_tests_failed()?;
+        shim.simulate_coverage_requested()?;
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_is_skipped_when_blocking_formatting_check_failed() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            fmt_mode: FmtMode::Blocking,
+            ..Config::default()
+        };
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_fmt_failed()?;
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Skipped {
+            reason: "fmt failed".into()
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_drops_below_warning_threshold_coverage_still_passes() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_threshold(RegressionThreshold::new(5.0, Severity::Warning));
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+        assert!(
+            spy.coverage_state_called_with_val(&CoverageState::Regressed {
+                actual: 70.0,
+                baseline: 80.0,
+                severity: Severity::Warning,
+            })
+        );
+        assert!(shim.event_on_bus(&BusEvent::CoveragePassed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_drop_is_within_threshold_coverage_is_successful() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(78.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state_with_coverage(80.0));
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_threshold(RegressionThreshold::new(5.0, Severity::Failure));
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Success(78.0)));
+        assert!(shim.event_on_bus(&BusEvent::CoveragePassed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_pass_coverage_of_files_is_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let files = vec![FileCoverage::new(
+            "src/lib.rs",
+            BTreeMap::from([(1, 1), (2, 0)]),
+        )];
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(50.0),
+            files.clone(),
+        ));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_files_called_with_val(&files));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_pass_coverage_summary_is_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let summary = CoverageSummary {
+            branches: Some(40.0),
+            functions: Some(90.0),
+            ..CoverageSummary::new(60.0)
+        };
+        let cov_runner = working(CoverageRunStatus::Success(summary, Vec::new()));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        CoverageShell::new(shim.bus(), Config::default()).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_summary_called_with_val(&summary));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_file_coverage_is_below_minimum_coverage_fails() -> Result<()> {
+        // given
+        init_tracing();
+        let files = vec![FileCoverage::new(
+            "src/core/lib.rs",
+            BTreeMap::from([(1, 1), (2, 0), (3, 0), (4, 0)]),
+        )];
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(90.0),
+            files,
+        ));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            coverage_minimum: MinimumCoverage {
+                global: Some(80.0),
+                files: vec![FileMinimum::new(Pattern::new("src/core/*")?, 50.0)],
+            },
+            ..Config::default()
+        };
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+        assert!(
+            spy.coverage_state_called_with_val(&CoverageState::BelowThreshold {
+                actual: 25.0,
+                required: 50.0,
+                file: Some("src/core/lib.rs".into()),
+            })
+        );
+        assert!(shim.event_on_bus(&BusEvent::CoverageFailed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_is_below_global_minimum_coverage_fails() -> Result<()> {
+        // given
+        init_tracing();
+        let cov_runner = working(CoverageRunStatus::Success(
+            CoverageSummary::new(70.0),
+            Vec::new(),
+        ));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            coverage_minimum: MinimumCoverage {
+                global: Some(80.0),
+                files: Vec::new(),
+            },
+            ..Config::default()
+        };
+        CoverageShell::new(shim.bus(), cfg).run(cov_runner, state);
+
+        // when
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // then
+        assert!(spy.coverage_state_called_with_val(&CoverageState::Pending));
+        assert!(
+            spy.coverage_state_called_with_val(&CoverageState::BelowThreshold {
+                actual: 70.0,
+                required: 80.0,
+                file: None,
+            })
+        );
+        assert!(shim.event_on_bus(&BusEvent::CoverageFailed)?);
+
+        Ok(())
+    }
+
+    fn state_with_coverage(coverage: f32) -> State {
+        state::working_with(StateValues {
+            cov_state: CoverageState::Success(coverage),
+            ..StateValues::default()
+        })
+    }
+
+    fn cfg_with_threshold(threshold: RegressionThreshold) -> Config {
+        Config {
+            coverage_regression: Some(threshold),
+            ..Config::default()
+        }
+    }
 }
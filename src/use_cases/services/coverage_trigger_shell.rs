@@ -0,0 +1,401 @@
+use crate::configuration::config::Config;
+use crate::entities::coverage_trigger::CoverageTrigger;
+use crate::result::CoverageErr;
+use crate::use_cases::bus::{BusEvent, EventBus, EventPublisher};
+use crate::use_cases::state::{State, StateReader};
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, trace};
+
+type Result<T> = std::result::Result<T, CoverageErr>;
+
+/// When tests passed, it decides whether code coverage should be run, accordingly to the
+/// `CoverageTrigger` policy.
+///
+/// `CoverageTriggerShell` waits for the result of the tests index refresh:
+/// - `CoverageTrigger::TestsSetChanged` - coverage is requested only when tests set changed
+/// - `CoverageTrigger::TestsPassed` - coverage is always requested
+/// - `CoverageTrigger::SourceChanged` - coverage is requested when tests set changed or content
+///   of any file in the coverage scope changed since the last coverage
+/// - `CoverageTrigger::Idle` - coverage is requested when no change was detected for the given
+///   time, otherwise it's skipped when the change is detected
+/// - `CoverageTrigger::Periodic` - coverage is requested when the given time elapsed since the
+///   last coverage
+///
+/// It publishes following events:
+/// - `BusEvent::CoverageRequested` - when coverage should be run
+/// - `BusEvent::CoverageSkipped` - when coverage is not run for the passed tests
+pub struct CoverageTriggerShell {
+    bus: EventBus,
+    trigger: CoverageTrigger,
+}
+
+impl CoverageTriggerShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let trigger = cfg.coverage_trigger;
+        Self { bus, trigger }
+    }
+
+    #[instrument(skip(self, st))]
+    pub fn run(self, st: State) {
+        let sub = self.bus.subscriber();
+        let publ = self.bus.publisher();
+        let trigger = self.trigger;
+        thread::spawn(move || -> Result<()> {
+            let idle_timer = IdleTimer::new(publ.clone());
+            let mut last_coverage: Option<Instant> = None;
+            let mut covered_scope: Option<u64> = None;
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                let tests_set_changed = match event {
+                    BusEvent::ChangeDetected | BusEvent::RunRequested(_) => {
+                        if idle_timer.cancel() {
+                            debug!("change detected before idle time, coverage skipped");
+                            publ.send(BusEvent::CoverageSkipped)?;
+                        }
+                        continue;
+                    }
+                    BusEvent::CoverageRequested => {
+                        last_coverage = Some(Instant::now());
+                        if trigger == CoverageTrigger::SourceChanged {
+                            covered_scope = scope_hash(&st.reader())?;
+                        }
+                        continue;
+                    }
+                    BusEvent::TestsSetChanged => true,
+                    BusEvent::TestsSetNotChanged => false,
+                    _ => {
+                        trace!("event not related to coverage trigger");
+                        continue;
+                    }
+                };
+
+                let requested = match trigger {
+                    CoverageTrigger::TestsSetChanged => tests_set_changed,
+                    CoverageTrigger::TestsPassed => true,
+                    CoverageTrigger::SourceChanged => {
+                        tests_set_changed
+                            || covered_scope.is_none()
+                            || scope_hash(&st.reader())? != covered_scope
+                    }
+                    CoverageTrigger::Idle(idle) => {
+                        debug!("coverage scheduled after {idle:?} of idle time");
+                        idle_timer.schedule(idle);
+                        continue;
+                    }
+                    CoverageTrigger::Periodic(period) => {
+                        last_coverage.is_none_or(|last| last.elapsed() >= period)
+                    }
+                };
+                if requested {
+                    debug!("coverage requested");
+                    publ.send(BusEvent::CoverageRequested)?;
+                } else {
+                    debug!("coverage skipped");
+                    publ.send(BusEvent::CoverageSkipped)?;
+                }
+            }
+        });
+    }
+}
+
+/// Hashes the content of every file with known coverage. It's `None` when the coverage of files
+/// is not known, so every change is treated as in the scope.
+///
+/// NOTE: The hash is only compared within the same process, so `DefaultHasher` is good enough.
+fn scope_hash(st: &StateReader) -> Result<Option<u64>> {
+    let files = st.coverage_files()?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+    let repo_root = st.repo_root()?;
+    let mut hasher = DefaultHasher::new();
+    for file in files {
+        file.path.hash(&mut hasher);
+        // NOTE: removed file is treated as empty
+        fs::read(repo_root.as_ref().join(&file.path))
+            .unwrap_or_default()
+            .hash(&mut hasher);
+    }
+    Ok(Some(hasher.finish()))
+}
+
+/// Publishes `BusEvent::CoverageRequested` when it's not cancelled for the given time.
+struct IdleTimer {
+    publ: EventPublisher,
+    generation: Arc<Mutex<Generation>>,
+}
+
+/// The latest scheduled request and whether it's still waiting for the idle time.
+#[derive(Default)]
+struct Generation {
+    latest: u64,
+    pending: bool,
+}
+
+impl IdleTimer {
+    fn new(publ: EventPublisher) -> Self {
+        let generation = Arc::new(Mutex::new(Generation::default()));
+        Self { publ, generation }
+    }
+
+    fn schedule(&self, idle: Duration) {
+        let scheduled = {
+            let mut generation = self.generation.lock().expect("poisoned mutex");
+            generation.latest += 1;
+            generation.pending = true;
+            generation.latest
+        };
+        let generation = self.generation.clone();
+        let publ = self.publ.clone();
+        thread::spawn(move || -> Result<()> {
+            thread::sleep(idle);
+            // NOTE: the lock is held while publishing, so the request can't be cancelled after
+            // it was decided to publish it
+            let mut generation = generation.lock().expect("poisoned mutex");
+            if generation.pending && generation.latest == scheduled {
+                generation.pending = false;
+                debug!("coverage requested after idle time");
+                publ.send(BusEvent::CoverageRequested)?;
+            }
+            Ok(())
+        });
+    }
+
+    /// Cancels the scheduled request. Returns `true` when there was a request waiting for the
+    /// idle time.
+    fn cancel(&self) -> bool {
+        let mut generation = self.generation.lock().expect("poisoned mutex");
+        generation.latest += 1;
+        std::mem::take(&mut generation.pending)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::file_coverage::FileCoverage;
+    use crate::testingtools::state::{self, StateValues};
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn by_default_coverage_is_requested_only_when_tests_set_changed() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        CoverageTriggerShell::new(shim.bus(), Config::default()).run(state::noop());
+
+        // when
+        shim.simulate_tests_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageRequested)?);
+
+        // when
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageSkipped)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_tests_passed_trigger_coverage_is_always_requested() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_trigger(CoverageTrigger::TestsPassed);
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state::noop());
+
+        // when
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageRequested)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_source_changed_trigger_coverage_is_requested_when_file_in_scope_changed() -> Result<()>
+    {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let state = state::working_with(StateValues {
+            repo_root: shim.repo_root(),
+            coverage_files: vec![FileCoverage::new("lib.rs", BTreeMap::from([(1, 1)]))],
+            ..StateValues::default()
+        });
+        let cfg = cfg_with_trigger(CoverageTrigger::SourceChanged);
+        fs::write(shim.repo_file("lib.rs"), "fn lib() {}")?;
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state);
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+        assert!(shim.event_on_bus(&BusEvent::CoverageSkipped)?);
+
+        // when
+        fs::write(shim.repo_file("lib.rs"), "fn lib() { todo!() }")?;
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageRequested)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_source_changed_trigger_coverage_is_skipped_when_scope_not_changed() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        fs::write(shim.repo_file("lib.rs"), "fn lib() {}")?;
+        let state = state::working_with(StateValues {
+            repo_root: shim.repo_root(),
+            coverage_files: vec![FileCoverage::new("lib.rs", BTreeMap::from([(1, 1)]))],
+            ..StateValues::default()
+        });
+        let cfg = cfg_with_trigger(CoverageTrigger::SourceChanged);
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state);
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // when
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageSkipped)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_idle_trigger_coverage_is_requested_after_idle_time() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_trigger(CoverageTrigger::Idle(Duration::ZERO));
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state::noop());
+
+        // when
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageRequested)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_idle_trigger_coverage_is_skipped_when_change_is_detected() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_trigger(CoverageTrigger::Idle(Duration::from_secs(3600)));
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state::noop());
+
+        // when
+        shim.simulate_tests_changed()?;
+        shim.simulate_change()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetChanged
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageSkipped)?);
+        assert!(shim.no_event_on_bus()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_idle_trigger_change_without_scheduled_coverage_is_ignored() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_trigger(CoverageTrigger::Idle(Duration::from_secs(3600)));
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state::noop());
+
+        // when
+        shim.simulate_change()?;
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+
+        // then
+        assert!(shim.no_event_on_bus()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_periodic_trigger_coverage_is_requested_when_period_elapsed() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_trigger(CoverageTrigger::Periodic(Duration::ZERO));
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state::noop());
+        shim.simulate_coverage_requested()?;
+        shim.ignore_event()?; // ignore BusEvent::CoverageRequested
+
+        // when
+        shim.simulate_tests_not_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetNotChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageRequested)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_periodic_trigger_coverage_is_skipped_within_period() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = cfg_with_trigger(CoverageTrigger::Periodic(Duration::from_secs(3600)));
+        CoverageTriggerShell::new(shim.bus(), cfg).run(state::noop());
+
+        // when
+        shim.simulate_tests_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageRequested)?);
+
+        // when
+        shim.simulate_tests_changed()?;
+        shim.ignore_event()?; // ignore BusEvent::TestsSetChanged
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::CoverageSkipped)?);
+
+        Ok(())
+    }
+
+    fn cfg_with_trigger(trigger: CoverageTrigger) -> Config {
+        Config {
+            coverage_trigger: trigger,
+            ..Config::default()
+        }
+    }
+}
@@ -0,0 +1,727 @@
+use crate::configuration::config::Config;
+use crate::entities::custom_stage::{CustomStage, CustomStageState, BUILT_IN_STAGES};
+use crate::result::StageErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::pipeline::Pipeline;
+use crate::use_cases::stage_runner::StageRunner;
+use crate::use_cases::state::{State, StateWriter};
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, StageErr>;
+
+/// Schedules the stages defined by the user in `Config::custom_stages`.
+///
+/// When a change is detected, all custom stages are set as `CustomStageState::Pending` and the
+/// stages without dependencies are started. Every stage is started once all the stages it depends
+/// on passed in the current run, be it built-in stages (`BusEvent::CheckPassed`,
+/// `BusEvent::TestsPassed`, `BusEvent::CoveragePassed`) or custom ones. Failed stage is treated
+/// as passed by its dependents when it continues on failure (`Config::continue_on_failure` for
+/// built-in stages, `CustomStage::continue_on_failure` for custom ones). Coverage skipped after
+/// the tests passed (`BusEvent::CoverageSkipped`) is treated as passed too, as its previous
+/// result still holds, and so is the check failed only on warnings (`BusEvent::CheckWarned`).
+///
+/// Stage which depends on a failed stage, or on a stage not run because of it, is not run and it's
+/// set as `CustomStageState::Skipped` with the failed stage as the reason. When the blocking
+/// formatting check failed, the stages depending on the tests or the coverage are skipped too.
+///
+/// All the stages which are ready are run in parallel, each in its own thread, but no more than
+/// `Config::max_parallel_stages` at once. The rest waits until some of the running stages finish.
+/// Results of the stages started before the latest change was detected are dropped. Such stages
+/// keep their slots until they finish and they are not started again before that, so the same
+/// stage never runs twice at once.
+///
+/// When the run is requested (`BusEvent::RunRequested`), the stages are not set as pending and
+/// nothing is run until the requested stage starts. The stages without dependencies are not run
+/// then, so the stages are run only when their dependencies are reached by the run. The stages
+/// which are not run keep their previous results.
+///
+/// It publishes following events:
+/// - `BusEvent::StagePassed` - when the custom stage passed
+/// - `BusEvent::StageFailed` - when the custom stage failed
+pub struct CustomStagesShell {
+    bus: EventBus,
+    stages: Vec<CustomStage>,
+    max_parallel: usize,
+    pipeline: Pipeline,
+}
+
+impl CustomStagesShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let pipeline = Pipeline::new(&cfg);
+        let stages = cfg.custom_stages;
+        let max_parallel = cfg.max_parallel_stages.max(1);
+        Self {
+            bus,
+            stages,
+            max_parallel,
+            pipeline,
+        }
+    }
+
+    #[instrument(skip(self, runner, st))]
+    pub fn run(self, runner: StageRunner, st: State) {
+        if self.stages.is_empty() {
+            debug!("no custom stages defined");
+            return;
+        }
+
+        let sub = self.bus.subscriber();
+        let publ = self.bus.publisher();
+        let stages = self.stages;
+        let max_parallel = self.max_parallel;
+        let pipeline = self.pipeline;
+        let (tx, rx) = channel();
+        let events_tx = tx.clone();
+        thread::spawn(move || -> Result<()> {
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                if events_tx.send(Msg::Event(event)).is_err() {
+                    return Ok(());
+                }
+            }
+        });
+        thread::spawn(move || -> Result<()> {
+            let sw = st.writer();
+            let mut generation: u64 = 0;
+            let mut passed: HashSet<String> = HashSet::new();
+            let mut blocked: HashMap<String, String> = HashMap::new();
+            let mut started: HashSet<String> = HashSet::new();
+            let mut running: HashSet<String> = HashSet::new();
+            loop {
+                let Ok(msg) = rx.recv() else {
+                    return Ok(());
+                };
+                let event = match msg {
+                    Msg::Finished {
+                        name,
+                        generation: run,
+                        state,
+                    } => {
+                        running.remove(&name);
+                        if run != generation {
+                            debug!("dropping result of {name} from the previous run");
+                        } else if state.is_failure() {
+                            sw.custom_stage(name.clone(), state)?;
+                            publ.send(BusEvent::StageFailed(name.clone()))?;
+                            let continues = stages
+                                .iter()
+                                .any(|stage| stage.name == name && stage.continue_on_failure);
+                            if continues {
+                                passed.insert(name);
+                            } else {
+                                blocked.insert(name.clone(), format!("{name} failed"));
+                            }
+                        } else {
+                            sw.custom_stage(name.clone(), state)?;
+                            publ.send(BusEvent::StagePassed(name.clone()))?;
+                            passed.insert(name);
+                        }
+                        None
+                    }
+                    Msg::Event(event) => Some(event),
+                };
+                match event {
+                    None => {}
+                    Some(BusEvent::RunRequested(_)) => {
+                        debug!("run requested, stages without dependencies are not run");
+                        generation += 1;
+                        passed.clear();
+                        blocked.clear();
+                        started.clear();
+                        for stage in stages.iter().filter(|stage| stage.depends_on.is_empty()) {
+                            started.insert(stage.name.clone());
+                        }
+                        continue;
+                    }
+                    Some(BusEvent::ChangeDetected) => {
+                        debug!("new run, custom stages are pending");
+                        generation += 1;
+                        passed.clear();
+                        blocked.clear();
+                        started.clear();
+                        for stage in &stages {
+                            sw.custom_stage(stage.name.clone(), CustomStageState::Pending)?;
+                        }
+                    }
+                    Some(
+                        event @ (BusEvent::CheckPassed
+                        | BusEvent::CheckWarned
+                        | BusEvent::TestsPassed
+                        | BusEvent::CoveragePassed),
+                    ) => {
+                        if let Some(stage) = event.finished_stage() {
+                            passed.insert(stage.to_string());
+                        }
+                    }
+                    Some(
+                        event @ (BusEvent::CheckFailed
+                        | BusEvent::TestsFailed
+                        | BusEvent::CoverageFailed),
+                    ) => {
+                        let Some(stage) = event.finished_stage() else {
+                            continue;
+                        };
+                        let failed = stage.to_string();
+                        if pipeline.continues_after(stage) {
+                            passed.insert(failed);
+                        } else {
+                            // NOTE: Built-in stages run one after another, so the later ones are
+                            // not run either.
+                            for name in BUILT_IN_STAGES.iter().skip_while(|name| **name != failed) {
+                                blocked.insert(name.to_string(), format!("{failed} failed"));
+                            }
+                        }
+                    }
+                    // NOTE: Coverage skipped by the idle trigger is published after the next
+                    // change was detected, before the tests of the new run passed.
+                    Some(BusEvent::CoverageSkipped) if passed.contains("tests") => {
+                        passed.insert("coverage".into());
+                    }
+                    Some(BusEvent::FmtFailed) if pipeline.fmt_blocking() => {
+                        for name in BUILT_IN_STAGES.iter().skip_while(|name| **name != "tests") {
+                            blocked.insert(name.to_string(), "fmt failed".into());
+                        }
+                    }
+                    Some(event) => {
+                        trace!("ignoring {event:?}");
+                        continue;
+                    }
+                }
+
+                skip_blocked(&stages, &mut started, &mut blocked, &sw)?;
+                for stage in &stages {
+                    if running.len() >= max_parallel {
+                        trace!("all {max_parallel} slots are taken");
+                        break;
+                    }
+                    // NOTE: Stage still running in the previous run is awaited, so the same
+                    // stage never runs twice at once.
+                    let ready = !running.contains(&stage.name)
+                        && stage.depends_on.iter().all(|dep| passed.contains(dep));
+                    if !ready || !started.insert(stage.name.clone()) {
+                        continue;
+                    }
+                    running.insert(stage.name.clone());
+                    let job = StageJob {
+                        stage: stage.clone(),
+                        runner: runner.clone(),
+                        st: st.clone(),
+                        generation,
+                        tx: tx.clone(),
+                    };
+                    job.spawn();
+                }
+            }
+        });
+    }
+}
+
+/// Message handled by the scheduler: event from the bus or result of the stage run by the job.
+enum Msg {
+    Event(BusEvent),
+    Finished {
+        name: String,
+        generation: u64,
+        state: CustomStageState,
+    },
+}
+
+/// Sets the stages depending on the blocked stages as skipped, until no more stages are blocked.
+/// Skipped stages keep the reason of the stage which blocked them.
+fn skip_blocked(
+    stages: &[CustomStage],
+    started: &mut HashSet<String>,
+    blocked: &mut HashMap<String, String>,
+    sw: &StateWriter,
+) -> Result<()> {
+    loop {
+        let skipped: Vec<(String, String)> = stages
+            .iter()
+            .filter(|stage| !started.contains(&stage.name))
+            .filter_map(|stage| {
+                let reason = stage.depends_on.iter().find_map(|dep| blocked.get(dep))?;
+                Some((stage.name.clone(), reason.clone()))
+            })
+            .collect();
+        if skipped.is_empty() {
+            return Ok(());
+        }
+        for (name, reason) in skipped {
+            debug!("skipping custom stage {name}: {reason}");
+            started.insert(name.clone());
+            blocked.insert(name.clone(), reason.clone());
+            sw.custom_stage(name, CustomStageState::Skipped { reason })?;
+        }
+    }
+}
+
+/// Single run of the custom stage, in its own thread.
+///
+/// It always reports back to the scheduler, even when the stage couldn't be run, so its slot is
+/// freed. Result is written and published by the scheduler, only when it belongs to the current
+/// run.
+struct StageJob {
+    stage: CustomStage,
+    runner: StageRunner,
+    st: State,
+    generation: u64,
+    tx: Sender<Msg>,
+}
+
+impl StageJob {
+    fn spawn(self) {
+        thread::spawn(move || {
+            let name = self.stage.name.clone();
+            debug!("running custom stage {name}");
+            let state = self.run().unwrap_or_else(|e| {
+                error!("failed to run custom stage {name}: {e:?}");
+                CustomStageState::Failure { summary: None }
+            });
+            let finished = Msg::Finished {
+                name,
+                generation: self.generation,
+                state,
+            };
+            if self.tx.send(finished).is_err() {
+                debug!("scheduler is gone");
+            }
+        });
+    }
+
+    fn run(&self) -> Result<CustomStageState> {
+        let repo_root = self.st.reader().repo_root()?;
+        self.runner.run(&self.stage, repo_root)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::custom_stage::SuccessCriterion;
+    use crate::entities::fmt::FmtMode;
+    use crate::entities::run_id::RunId;
+    use crate::entities::stage::Stage;
+    use crate::testingtools::stage_runner::{blocking, failing, tracked};
+    use crate::testingtools::state;
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+
+    #[test]
+    fn stages_without_dependencies_are_run_when_change_is_detected() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let (state_spy, st) = state::tracked(&state::working());
+        let shell = CustomStagesShell::new(shim.bus(), cfg(vec![stage("fmt", &[])])?);
+        shell.run(runner, st);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(state_spy.custom_stage_called_with_val("fmt", &CustomStageState::Pending));
+        assert!(spy.run_called_with_val("fmt"));
+        assert!(state_spy
+            .custom_stage_called_with_val("fmt", &CustomStageState::Success { summary: None }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_is_run_after_its_dependencies_passed() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let stages = vec![stage("clippy", &["check"]), stage("doc", &["clippy"])];
+        let shell = CustomStagesShell::new(shim.bus(), cfg(stages)?);
+        shell.run(runner, state::working());
+        shim.simulate_change()?;
+        assert!(spy.run_not_called());
+
+        // when
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(spy.run_called_with_val("clippy"));
+        assert!(spy.run_called_with_val("doc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_depending_on_failed_stage_is_skipped() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let failure = CustomStageState::Failure { summary: None };
+        let (spy, runner) = tracked(&[("clippy", failure.clone())]);
+        let (state_spy, st) = state::tracked(&state::working());
+        let stages = vec![stage("clippy", &[]), stage("doc", &["clippy"])];
+        let shell = CustomStagesShell::new(shim.bus(), cfg(stages)?);
+        shell.run(runner, st);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.run_called_with_val("clippy"));
+        let pending = CustomStageState::Pending;
+        assert!(state_spy.custom_stage_called_with_val("clippy", &pending));
+        assert!(state_spy.custom_stage_called_with_val("doc", &pending));
+        assert!(state_spy.custom_stage_called_with_val("clippy", &failure));
+        assert!(state_spy.custom_stage_called_with_val(
+            "doc",
+            &CustomStageState::Skipped {
+                reason: "clippy failed".into()
+            }
+        ));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stages_after_failed_check_are_skipped_with_the_check_as_reason() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let (state_spy, st) = state::tracked(&state::working());
+        let stages = vec![stage("doc", &["tests"]), stage("publish", &["doc"])];
+        let shell = CustomStagesShell::new(shim.bus(), cfg(stages)?);
+        shell.run(runner, st);
+        shim.simulate_change()?;
+
+        // when
+        shim.simulate_check_failed()?;
+
+        // then
+        let skipped = CustomStageState::Skipped {
+            reason: "check failed".into(),
+        };
+        assert!(state_spy.custom_stage_called_with_val("doc", &CustomStageState::Pending));
+        assert!(state_spy.custom_stage_called_with_val("publish", &CustomStageState::Pending));
+        assert!(state_spy.custom_stage_called_with_val("doc", &skipped));
+        assert!(state_spy.custom_stage_called_with_val("publish", &skipped));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stages_after_tests_are_skipped_when_blocking_formatting_check_failed() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let (state_spy, st) = state::tracked(&state::working());
+        let cfg = ConfigBuilder::default()
+            .custom_stages(vec![stage("doc", &["tests"])])
+            .fmt_mode(FmtMode::Blocking)
+            .build()?;
+        let shell = CustomStagesShell::new(shim.bus(), cfg);
+        shell.run(runner, st);
+        shim.simulate_change()?;
+
+        // when
+        shim.simulate_check_passed()?;
+        shim.simulate_fmt_failed()?;
+
+        // then
+        assert!(state_spy.custom_stage_called_with_val("doc", &CustomStageState::Pending));
+        assert!(state_spy.custom_stage_called_with_val(
+            "doc",
+            &CustomStageState::Skipped {
+                reason: "fmt failed".into()
+            }
+        ));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stages_after_failed_stage_continuing_on_failure_are_run() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let failure = CustomStageState::Failure { summary: None };
+        let (spy, runner) = tracked(&[("audit", failure)]);
+        let stages = vec![
+            CustomStage {
+                continue_on_failure: true,
+                ..stage("audit", &["check"])
+            },
+            stage("doc", &["audit"]),
+        ];
+        let cfg = ConfigBuilder::default()
+            .custom_stages(stages)
+            .continue_on_failure(vec![Stage::Check])
+            .build()?;
+        let shell = CustomStagesShell::new(shim.bus(), cfg);
+        shell.run(runner, state::working());
+        shim.simulate_change()?;
+
+        // when
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.run_called_with_val("audit"));
+        assert!(spy.run_called_with_val("doc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn result_of_custom_stage_is_published() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let failure = CustomStageState::Failure { summary: None };
+        let (_spy, runner) = tracked(&[("fmt", failure)]);
+        let shell = CustomStagesShell::new(shim.bus(), cfg(vec![stage("fmt", &[])])?);
+        shell.run(runner, state::working());
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        shim.ignore_event()?; // ChangeDetected
+        assert!(shim.event_on_bus(&BusEvent::StageFailed("fmt".into()))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ready_stages_are_run_in_parallel_up_to_the_limit() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, gate, runner) = blocking();
+        let stages = vec![stage("fmt", &[]), stage("deny", &[]), stage("doc", &[])];
+        let cfg = ConfigBuilder::default()
+            .custom_stages(stages)
+            .max_parallel_stages(2)
+            .build()?;
+        let shell = CustomStagesShell::new(shim.bus(), cfg);
+        shell.run(runner, state::working());
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        let first_two = |name: &String| name == "fmt" || name == "deny";
+        assert!(spy.run_called_with_matching(first_two));
+        assert!(spy.run_called_with_matching(first_two));
+        assert!(spy.run_not_called());
+        gate.open_once();
+        assert!(spy.run_called_with_val("doc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn results_of_previous_run_are_dropped() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, gate, runner) = blocking();
+        let (state_spy, st) = state::tracked(&state::working());
+        let shell = CustomStagesShell::new(shim.bus(), cfg(vec![stage("fmt", &[])])?);
+        shell.run(runner, st);
+        shim.simulate_change()?;
+        assert!(state_spy.custom_stage_called_with_val("fmt", &CustomStageState::Pending));
+        assert!(spy.run_called_with_val("fmt"));
+
+        // when
+        shim.simulate_change()?;
+        assert!(state_spy.custom_stage_called_with_val("fmt", &CustomStageState::Pending));
+        gate.open_once();
+
+        // then
+        let success = CustomStageState::Success { summary: None };
+        assert!(spy.run_called_with_val("fmt"));
+        assert!(!state_spy.custom_stage_called_with_val("fmt", &success));
+        gate.open_once();
+        assert!(state_spy.custom_stage_called_with_val("fmt", &success));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_running_in_previous_run_is_awaited() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, gate, runner) = blocking();
+        let stages = vec![stage("fmt", &[]), stage("deny", &[])];
+        let cfg = ConfigBuilder::default()
+            .custom_stages(stages)
+            .max_parallel_stages(2)
+            .build()?;
+        let shell = CustomStagesShell::new(shim.bus(), cfg);
+        shell.run(runner, state::working());
+        shim.simulate_change()?;
+        let any = |name: &String| name == "fmt" || name == "deny";
+        assert!(spy.run_called_with_matching(any));
+        assert!(spy.run_called_with_matching(any));
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.run_not_called());
+        gate.open_once();
+        assert!(spy.run_called_with_matching(any));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_which_could_not_be_run_frees_its_slot() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = failing();
+        let (state_spy, st) = state::tracked(&state::working());
+        let stages = vec![stage("fmt", &["check"]), stage("deny", &["check"])];
+        let cfg = ConfigBuilder::default()
+            .custom_stages(stages)
+            .max_parallel_stages(1)
+            .build()?;
+        let shell = CustomStagesShell::new(shim.bus(), cfg);
+        shell.run(runner, st);
+
+        // when
+        shim.simulate_check_passed()?;
+
+        // then
+        let failure = CustomStageState::Failure { summary: None };
+        assert!(spy.run_called_with_val("fmt"));
+        assert!(spy.run_called_with_val("deny"));
+        assert!(state_spy.custom_stage_called_with_val("fmt", &failure));
+        assert!(state_spy.custom_stage_called_with_val("deny", &failure));
+
+        Ok(())
+    }
+
+    #[test]
+    fn requested_run_does_not_run_stages_without_dependencies() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let (state_spy, st) = state::tracked(&state::working());
+        let stages = vec![stage("fmt", &[]), stage("doc", &["check"])];
+        let shell = CustomStagesShell::new(shim.bus(), cfg(stages)?);
+        shell.run(runner, st);
+
+        // when
+        shim.simulate_run_requested(RunId::new())?;
+
+        // then
+        assert!(spy.run_not_called());
+        assert!(!state_spy.custom_stage_called_with_val("doc", &CustomStageState::Pending));
+
+        // when
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(spy.run_called_with_val("doc"));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn requested_run_from_check_runs_every_stage_once() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let shell = CustomStagesShell::new(shim.bus(), cfg(vec![stage("fmt", &[])])?);
+        shell.run(runner, state::working());
+
+        // when
+        shim.simulate_run_requested(RunId::new())?;
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.run_called_with_val("fmt"));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_depending_on_skipped_coverage_is_run() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let shell = CustomStagesShell::new(shim.bus(), cfg(vec![stage("doc", &["coverage"])])?);
+        shell.run(runner, state::working());
+        shim.simulate_change()?;
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_passed()?;
+
+        // when
+        shim.simulate_coverage_skipped()?;
+
+        // then
+        assert!(spy.run_called_with_val("doc"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_skipped_before_tests_passed_is_ignored() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(&[]);
+        let shell = CustomStagesShell::new(shim.bus(), cfg(vec![stage("doc", &["coverage"])])?);
+        shell.run(runner, state::working());
+        shim.simulate_change()?;
+
+        // when
+        shim.simulate_coverage_skipped()?;
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_passed()?;
+
+        // then
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    fn stage(name: &str, depends_on: &[&str]) -> CustomStage {
+        CustomStage {
+            name: name.into(),
+            cmd: vec!["true".into()],
+            depends_on: depends_on.iter().map(ToString::to_string).collect(),
+            success: SuccessCriterion::ExitCode,
+            parser: None,
+            own_target_dir: false,
+            continue_on_failure: false,
+        }
+    }
+
+    fn cfg(stages: Vec<CustomStage>) -> Result<Config> {
+        Ok(ConfigBuilder::default().custom_stages(stages).build()?)
+    }
+}
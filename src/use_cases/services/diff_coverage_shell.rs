@@ -0,0 +1,135 @@
+use crate::configuration::config::Config;
+use crate::entities::diff_coverage::DiffCoverage;
+use crate::result::CoverageErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::state::State;
+use crate::use_cases::vcs::VersionControl;
+
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, CoverageErr>;
+
+/// When coverage passed, it calculates coverage of lines changed compared to the base commit and
+/// updates diff coverage state.
+///
+/// Changed lines are read from the version control system, coverage of every line is taken from
+/// the state. If changed lines can't be read, diff coverage state is not updated.
+pub struct DiffCoverageShell {
+    bus: EventBus,
+    base: String,
+}
+
+impl DiffCoverageShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let base = cfg.diff_base;
+        Self { bus, base }
+    }
+
+    #[instrument(skip(self, vcs, st))]
+    pub fn run(self, vcs: VersionControl, st: State) {
+        let sub = self.bus.subscriber();
+        let base = self.base;
+        thread::spawn(move || -> Result<()> {
+            loop {
+                let Ok(BusEvent::CoveragePassed) = sub.recv() else {
+                    trace!("coverage not calculated");
+                    continue;
+                };
+
+                let repo_root = st.reader().repo_root()?;
+                let changes = match vcs.changes(repo_root) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        error!("failed to read changed lines: {e:?}");
+                        continue;
+                    }
+                };
+                let files = st.reader().coverage_files()?;
+                let diff = DiffCoverage::calculate(base.clone(), &changes, &files);
+                debug!("diff coverage calculated: {}/{}", diff.covered, diff.total);
+                st.writer().diff_coverage(diff)?;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::diff_coverage::{ChangedLines, FileDiffCoverage};
+    use crate::entities::file_coverage::{FileCoverage, LineRange};
+    use crate::testingtools::state::{self, StateValues};
+    use crate::testingtools::unit::create_test_shim;
+    use crate::testingtools::vcs::{failing, working_with_changes};
+
+    use anyhow::Result;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn when_coverage_pass_diff_coverage_is_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let vcs = working_with_changes(vec![ChangedLines::new(
+            "src/lib.rs",
+            BTreeSet::from([2, 3]),
+        )]);
+        let (spy, state) = state::tracked(&state_with_files());
+        let shim = create_test_shim()?;
+        DiffCoverageShell::new(shim.bus(), cfg_with_base("main")).run(vcs, state);
+
+        // when
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(spy.diff_coverage_called_with_val(&DiffCoverage {
+            base: "main".into(),
+            covered: 1,
+            total: 2,
+            files: vec![FileDiffCoverage {
+                path: "src/lib.rs".into(),
+                covered: 1,
+                total: 2,
+                uncovered: vec![LineRange::new(3, 3)],
+            }],
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_changes_can_not_be_read_diff_coverage_is_not_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state_with_files());
+        let shim = create_test_shim()?;
+        DiffCoverageShell::new(shim.bus(), cfg_with_base("HEAD")).run(failing(), state);
+
+        // when
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(!spy.diff_coverage_called());
+
+        Ok(())
+    }
+
+    fn state_with_files() -> State {
+        state::working_with(StateValues {
+            coverage_files: vec![FileCoverage::new(
+                "src/lib.rs",
+                BTreeMap::from([(1, 0), (2, 1), (3, 0)]),
+            )],
+            ..StateValues::default()
+        })
+    }
+
+    fn cfg_with_base(base: &str) -> Config {
+        Config {
+            diff_base: base.to_string(),
+            ..Config::default()
+        }
+    }
+}
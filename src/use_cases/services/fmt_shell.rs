@@ -0,0 +1,194 @@
+use crate::configuration::config::Config;
+use crate::entities::fmt::{FmtMode, FmtState};
+use crate::result::FmtErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::fmt_runner::{FmtRunStatus, FmtRunner};
+use crate::use_cases::pipeline::Pipeline;
+use crate::use_cases::state::State;
+
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, FmtErr>;
+
+/// Checks the formatting of the code, updates fmt state and publishes result of the check.
+///
+/// When it's run depends on the `Config::fmt_mode`:
+/// - `FmtMode::Off` - never (default)
+/// - `FmtMode::Informational` - when change is detected, next to the check stage
+/// - `FmtMode::Blocking` - when check passed (or failed, but it's listed in
+///   `Config::continue_on_failure`), the tests are run only when the formatting check passed
+///
+/// `FmtShell` sets the fmt state as `FmtState::Pending`, then runs the formatting check. When it
+/// fails, the state holds the files which are not formatted and whether the failure blocks the
+/// tests.
+///
+/// It publishes following events:
+/// - `BusEvent::FmtPassed` - when all files are formatted
+/// - `BusEvent::FmtFailed` - when some files are not formatted or the check failed to run
+pub struct FmtShell {
+    bus: EventBus,
+    mode: FmtMode,
+    pipeline: Pipeline,
+}
+
+impl FmtShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let mode = cfg.fmt_mode;
+        let pipeline = Pipeline::new(&cfg);
+        Self {
+            bus,
+            mode,
+            pipeline,
+        }
+    }
+
+    #[instrument(skip(self, fr, st))]
+    pub fn run(self, fr: FmtRunner, st: State) {
+        if self.mode == FmtMode::Off {
+            debug!("formatting check is turned off");
+            return;
+        }
+
+        let sub = self.bus.subscriber();
+        let publ = self.bus.publisher();
+        let pipeline = self.pipeline;
+        thread::spawn(move || -> Result<()> {
+            let sw = st.writer();
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                if !pipeline.starts_fmt(&event) {
+                    trace!("formatting check not needed");
+                    continue;
+                }
+
+                debug!("checking formatting");
+                sw.fmt(FmtState::Pending)?;
+                let status = fr.run(st.reader().repo_root()?).unwrap_or_else(|e| {
+                    error!("failed to check formatting: {e:?}");
+                    FmtRunStatus::Failure(Vec::new())
+                });
+                let FmtRunStatus::Failure(files) = status else {
+                    debug!("formatting check passed");
+                    sw.fmt(FmtState::Success)?;
+                    publ.send(BusEvent::FmtPassed)?;
+                    continue;
+                };
+
+                debug!("formatting check failed");
+                sw.fmt(FmtState::Failure {
+                    files,
+                    blocking: pipeline.fmt_blocking(),
+                })?;
+                publ.send(BusEvent::FmtFailed)?;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::testingtools::fmt_runner::{tracked, working};
+    use crate::testingtools::state;
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+
+    #[test]
+    fn formatting_is_checked_when_change_is_detected() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, fmt_runner) = tracked(working(FmtRunStatus::Success));
+        let shim = create_test_shim()?;
+        let cfg = ConfigBuilder::default()
+            .fmt_mode(FmtMode::Informational)
+            .build()?;
+        FmtShell::new(shim.bus(), cfg).run(fmt_runner, state::noop());
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.run_called());
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        assert!(shim.event_on_bus(&BusEvent::FmtPassed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_formatting_check_fails_unformatted_files_are_saved() -> Result<()> {
+        // given
+        init_tracing();
+        let files = vec!["src/main.rs".to_string()];
+        let fmt_runner = working(FmtRunStatus::Failure(files.clone()));
+        let (spy, st) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        let cfg = ConfigBuilder::default()
+            .fmt_mode(FmtMode::Informational)
+            .build()?;
+        FmtShell::new(shim.bus(), cfg).run(fmt_runner, st);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.fmt_state_called_with_val(&FmtState::Pending));
+        assert!(spy.fmt_state_called_with_val(&FmtState::Failure {
+            files,
+            blocking: false
+        }));
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        assert!(shim.event_on_bus(&BusEvent::FmtFailed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn blocking_formatting_check_is_run_after_check_passed() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, fmt_runner) = tracked(working(FmtRunStatus::Success));
+        let shim = create_test_shim()?;
+        let cfg = ConfigBuilder::default()
+            .fmt_mode(FmtMode::Blocking)
+            .build()?;
+        FmtShell::new(shim.bus(), cfg).run(fmt_runner, state::noop());
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_check_passed()?;
+
+        // then
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        shim.ignore_event()?; // ignore BusEvent::CheckPassed
+        assert!(spy.run_called());
+        assert!(shim.event_on_bus(&BusEvent::FmtPassed)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn formatting_is_not_checked_by_default() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, fmt_runner) = tracked(working(FmtRunStatus::Success));
+        let shim = create_test_shim()?;
+        FmtShell::new(shim.bus(), Config::default()).run(fmt_runner, state::noop());
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(!spy.run_called());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,592 @@
+use crate::configuration::config::Config;
+use crate::entities::check::CheckState;
+use crate::entities::pipeline_run::{PipelineRun, Trigger};
+use crate::entities::quality::HalsteadSummary;
+use crate::entities::run_id::RunId;
+use crate::entities::tests::TestsState;
+use crate::result::HistoryErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::history::History;
+use crate::use_cases::pipeline::Pipeline;
+use crate::use_cases::state::StateReader;
+use crate::use_cases::vcs::VersionControl;
+
+use std::collections::VecDeque;
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, HistoryErr>;
+
+/// Records every pipeline run in the history.
+///
+/// The run starts with `BusEvent::ChangeDetected` when the files or the repo root changed. When
+/// it's triggered via the API, `BusEvent::RunRequested` carries its id and the run starts with
+/// the event of the requested stage (`BusEvent::ChangeDetected`, `BusEvent::TestsRequested` or
+/// `BusEvent::CoverageRequested`). `HistoryShell` collects
+/// results of the stages from the events published by other shells and appends the run to the
+/// history when the last stage of the run is done:
+/// - `BusEvent::CheckFailed` or `BusEvent::TestsFailed` - later stages are not run, unless the
+///   failed stage is listed in `Config::continue_on_failure`
+/// - `BusEvent::FmtFailed` - tests are not run when the formatting check is blocking
+/// - `BusEvent::CoverageSkipped` - coverage is not run after the tests passed
+/// - `BusEvent::CoveragePassed` or `BusEvent::CoverageFailed` - it's the end of the pipeline
+///
+/// If the next run starts before the previous one is done, the previous one is recorded as is.
+///
+/// Every run is recorded with the Halstead metrics of the code, calculated after the change which
+/// started it (`BusEvent::QualityCalculated`), to show how the effort to maintain the code changes
+/// over time. The quality is calculated next to the other stages, so the run which is done before
+/// its metrics are known is recorded once they are. The runs which were not started by a change,
+/// eg. the tests run via the API, are recorded without the metrics.
+pub struct HistoryShell {
+    bus: EventBus,
+    pipeline: Pipeline,
+}
+
+impl HistoryShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let pipeline = Pipeline::new(&cfg);
+        Self { bus, pipeline }
+    }
+
+    #[instrument(skip(self, history, vcs, st))]
+    pub fn run(self, history: History, vcs: VersionControl, st: StateReader) {
+        let sub = self.bus.subscriber();
+        let pipeline = self.pipeline;
+        thread::spawn(move || -> Result<()> {
+            let mut recorder = RunRecorder::new(history, vcs, st, pipeline);
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                recorder.handle(event)?;
+            }
+        });
+    }
+}
+
+struct RunRecorder {
+    history: History,
+    vcs: VersionControl,
+    st: StateReader,
+    pipeline: Pipeline,
+    curr_run: Option<PipelineRun>,
+    /// Run requested via the API, which starts with the next stage.
+    requested_run: Option<RunId>,
+    /// Runs whose change is being analyzed, in the order of the changes.
+    analyzed_runs: VecDeque<RunId>,
+    /// Runs which are done, but wait for the metrics of their change.
+    done_runs: Vec<PipelineRun>,
+}
+
+impl RunRecorder {
+    fn new(history: History, vcs: VersionControl, st: StateReader, pipeline: Pipeline) -> Self {
+        Self {
+            history,
+            vcs,
+            st,
+            pipeline,
+            curr_run: None,
+            requested_run: None,
+            analyzed_runs: VecDeque::new(),
+            done_runs: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, event: BusEvent) -> Result<()> {
+        match event {
+            BusEvent::RunRequested(id) => self.requested_run = Some(id),
+            BusEvent::ChangeDetected => {
+                match self.requested_run.take() {
+                    Some(id) => self.start(id, Trigger::Manual)?,
+                    None => self.start(RunId::new(), Trigger::Change)?,
+                }
+                self.analyze_curr_run();
+            }
+            // NOTE: Coverage is also requested after the tests, in the middle of the run.
+            BusEvent::TestsRequested | BusEvent::CoverageRequested => {
+                if let Some(id) = self.requested_run.take() {
+                    self.start(id, Trigger::Manual)?;
+                }
+            }
+            BusEvent::CheckPassed => self.update(|run| run.check = Some(CheckState::Success)),
+            BusEvent::CheckWarned => self.update(|run| run.check = Some(CheckState::Failure)),
+            BusEvent::CheckFailed => {
+                self.update(|run| run.check = Some(CheckState::Failure));
+                self.finish_if_stopped(&event);
+            }
+            BusEvent::FmtFailed => self.finish_if_stopped(&event),
+            BusEvent::TestsPassed => self.update(|run| run.tests = Some(TestsState::Success)),
+            BusEvent::TestsFailed => {
+                self.update(|run| run.tests = Some(TestsState::Failure));
+                self.finish_if_stopped(&event);
+            }
+            // NOTE: Coverage skipped because of the change belongs to the previous run.
+            BusEvent::CoverageSkipped if self.tests_passed() => self.finish(),
+            BusEvent::CoveragePassed | BusEvent::CoverageFailed => {
+                let coverage = self.st.coverage()?;
+                self.update(|run| run.coverage = Some(coverage));
+                self.finish();
+            }
+            BusEvent::QualityCalculated(halstead) => self.quality_calculated(halstead),
+            _ => trace!("event not related to the pipeline run"),
+        }
+
+        Ok(())
+    }
+
+    fn start(&mut self, id: RunId, trigger: Trigger) -> Result<()> {
+        self.finish();
+        let repo_root = self.st.repo_root()?;
+        let commit = self.vcs.head(repo_root.clone()).unwrap_or_else(|e| {
+            error!("failed to read head commit: {e:?}");
+            None
+        });
+        debug!("run {id} started");
+        self.curr_run = Some(PipelineRun::new(id, trigger, repo_root, commit));
+        Ok(())
+    }
+
+    fn update<F: FnOnce(&mut PipelineRun)>(&mut self, update_fn: F) {
+        match self.curr_run.as_mut() {
+            Some(run) => update_fn(run),
+            None => trace!("no run in progress"),
+        }
+    }
+
+    fn tests_passed(&self) -> bool {
+        self.curr_run
+            .as_ref()
+            .is_some_and(|run| run.tests == Some(TestsState::Success))
+    }
+
+    /// Finishes the run after the failed stage, unless the pipeline continues past it.
+    fn finish_if_stopped(&mut self, event: &BusEvent) {
+        if self.pipeline.stopped_by(event).is_none() {
+            trace!("pipeline continues after {event:?}");
+            return;
+        }
+        self.finish();
+    }
+
+    /// Quality of the code changed by the current run is being calculated.
+    fn analyze_curr_run(&mut self) {
+        if let Some(run) = &self.curr_run {
+            self.analyzed_runs.push_back(run.id.clone());
+        }
+    }
+
+    /// Sets the metrics of the run whose change was analyzed first and records the run if it was
+    /// waiting for them.
+    fn quality_calculated(&mut self, halstead: Option<HalsteadSummary>) {
+        let Some(id) = self.analyzed_runs.pop_front() else {
+            trace!("quality of no run calculated");
+            return;
+        };
+        if let Some(run) = self.curr_run.as_mut().filter(|run| run.id == id) {
+            run.halstead = halstead;
+            return;
+        }
+        if let Some(idx) = self.done_runs.iter().position(|run| run.id == id) {
+            let mut run = self.done_runs.remove(idx);
+            run.halstead = halstead;
+            self.record(run);
+        }
+    }
+
+    fn finish(&mut self) {
+        let Some(mut run) = self.curr_run.take() else {
+            trace!("no run in progress");
+            return;
+        };
+        run.finish();
+        debug!("run {} finished", run.id);
+        if self.analyzed_runs.contains(&run.id) {
+            trace!("run {} waits for the quality of the code", run.id);
+            self.done_runs.push(run);
+            return;
+        }
+        self.record(run);
+    }
+
+    fn record(&self, run: PipelineRun) {
+        if let Err(e) = self.history.append(run) {
+            error!("failed to record the run: {e:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::coverage::CoverageState;
+    use crate::entities::fmt::FmtMode;
+    use crate::entities::quality::{FileQuality, QualityReport};
+    use crate::entities::stage::Stage;
+    use crate::testingtools::history::{tracked, working};
+    use crate::testingtools::quality_analyzer::halstead;
+    use crate::testingtools::state::{self, StateValues};
+    use crate::testingtools::unit::create_test_shim;
+    use crate::testingtools::vcs;
+
+    use anyhow::Result;
+
+    #[test]
+    fn run_is_recorded_when_check_fails() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.trigger == Trigger::Change
+            && run.check == Some(CheckState::Failure)
+            && run.tests.is_none()
+            && run.coverage.is_none()
+            && run.finished_at.is_some()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_continuing_past_failed_check_is_recorded_after_tests() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        let cfg = ConfigBuilder::default()
+            .continue_on_failure(vec![Stage::Check])
+            .build()?;
+        HistoryShell::new(shim.bus(), cfg).run(history, vcs::working(None), st.reader());
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_failed()?;
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy
+            .append_called_with(|run| run.check == Some(CheckState::Failure)
+                && run.tests == Some(TestsState::Failure)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_recorded_when_blocking_formatting_check_fails() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        let cfg = ConfigBuilder::default()
+            .fmt_mode(FmtMode::Blocking)
+            .build()?;
+        HistoryShell::new(shim.bus(), cfg).run(history, vcs::working(None), st.reader());
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_passed()?;
+        shim.simulate_fmt_failed()?;
+
+        // then
+        assert!(
+            spy.append_called_with(|run| run.check == Some(CheckState::Success)
+                && run.tests.is_none()
+                && run.finished_at.is_some())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_recorded_with_halstead_metrics_of_its_change() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let previous = QualityReport::new(vec![FileQuality::default()]);
+        let st = state::working_with(StateValues {
+            quality: Some(previous),
+            ..StateValues::default()
+        });
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+        let report = QualityReport::new(vec![FileQuality {
+            halstead: halstead(),
+            ..FileQuality::default()
+        }]);
+        shim.simulate_change()?;
+        shim.simulate_check_failed()?;
+        assert!(!spy.append_called());
+
+        // when
+        shim.simulate_quality_calculated(Some(report.halstead()))?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.halstead == Some(report.halstead())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_not_started_by_change_is_recorded_without_halstead_metrics() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::working_with(StateValues {
+            quality: Some(QualityReport::new(vec![FileQuality::default()])),
+            ..StateValues::default()
+        });
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+
+        // when
+        shim.simulate_run_requested(RunId::new())?;
+        shim.simulate_tests_requested()?;
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.halstead.is_none()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn whole_pipeline_run_is_recorded_after_coverage() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_passed()?;
+        shim.simulate_tests_changed()?;
+        shim.simulate_coverage_requested()?;
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(
+            spy.append_called_with(|run| run.check == Some(CheckState::Success)
+                && run.tests == Some(TestsState::Success)
+                && run.coverage == Some(CoverageState::Success(20.0)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_recorded_when_coverage_is_skipped() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_passed()?;
+        shim.simulate_tests_not_changed()?;
+        shim.simulate_coverage_skipped()?;
+
+        // then
+        assert!(
+            spy.append_called_with(|run| run.tests == Some(TestsState::Success)
+                && run.coverage.is_none()
+                && run.finished_at.is_some())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_skipped_after_next_change_does_not_finish_next_run() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_passed()?;
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_coverage_skipped()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.tests == Some(TestsState::Success)));
+        assert!(!spy.append_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_not_recorded_before_it_is_done() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(!spy.append_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn manual_run_is_recorded_with_requested_id() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+        let run_id = RunId::new();
+
+        // when
+        shim.simulate_run_requested(run_id.clone())?;
+        shim.simulate_tests_requested()?;
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.id == run_id
+            && run.trigger == Trigger::Manual
+            && run.check.is_none()
+            && run.tests == Some(TestsState::Failure)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn manual_check_run_is_recorded_with_requested_id() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(
+            history,
+            vcs::working(None),
+            st.reader(),
+        );
+        let run_id = RunId::new();
+
+        // when
+        shim.simulate_run_requested(run_id.clone())?;
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.id == run_id
+            && run.trigger == Trigger::Manual
+            && run.check == Some(CheckState::Failure)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn head_commit_is_recorded() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        let vcs = vcs::working(Some("abc".into()));
+        HistoryShell::new(shim.bus(), Config::default()).run(history, vcs, st.reader());
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.commit == Some("abc".into())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_recorded_without_commit_when_reading_it_fails() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, history) = tracked(working(Vec::new()));
+        let shim = create_test_shim()?;
+        let st = state::noop();
+        HistoryShell::new(shim.bus(), Config::default()).run(history, vcs::failing(), st.reader());
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.append_called_with(|run| run.commit.is_none()));
+
+        Ok(())
+    }
+}
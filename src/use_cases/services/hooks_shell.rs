@@ -0,0 +1,221 @@
+use crate::configuration::config::Config;
+use crate::entities::hook::{Hook, HookPayload};
+use crate::result::RunnerErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::hook_runner::HookRunner;
+use crate::use_cases::state::State;
+
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, RunnerErr>;
+
+/// Runs the hooks configured in `Config::hooks` for every published event.
+///
+/// Hooks of the event are run in the background, one after another in the configured order, so
+/// slow hooks don't hold back the events which come next. Failure of a hook (or exceeding its
+/// timeout) is only logged and doesn't stop the other hooks.
+///
+/// Data of the event is read when it's published: the custom stage name, the requested run id,
+/// the coverage value on coverage results and the first failing test when tests failed.
+pub struct HooksShell {
+    bus: EventBus,
+    hooks: Vec<Hook>,
+}
+
+impl HooksShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let hooks = cfg.hooks;
+        Self { bus, hooks }
+    }
+
+    #[instrument(skip(self, runner, st))]
+    pub fn run(self, runner: HookRunner, st: State) {
+        if self.hooks.is_empty() {
+            debug!("no hooks configured");
+            return;
+        }
+
+        let sub = self.bus.subscriber();
+        let hooks = self.hooks;
+        thread::spawn(move || -> Result<()> {
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                let name = event.name();
+                let event_hooks: Vec<Hook> = hooks
+                    .iter()
+                    .filter(|hook| hook.event == name)
+                    .cloned()
+                    .collect();
+                if event_hooks.is_empty() {
+                    continue;
+                }
+
+                let payload = payload(&event, &st)?;
+                let runner = runner.clone();
+                thread::spawn(move || {
+                    for hook in event_hooks {
+                        debug!("running hook: {hook:?}");
+                        if let Err(e) = runner.run(&hook.action, &payload) {
+                            error!("hook {hook:?} failed: {e:?}");
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+fn payload(event: &BusEvent, st: &State) -> Result<HookPayload> {
+    let reader = st.reader();
+    let mut payload = HookPayload::new(event.name(), reader.repo_root()?);
+    payload.stage = event.custom_stage().map(ToString::to_string);
+    match event {
+        BusEvent::RunRequested(run_id) => payload.run_id = Some(run_id.clone()),
+        BusEvent::CoveragePassed | BusEvent::CoverageFailed => {
+            payload.coverage = reader.coverage()?.value();
+        }
+        BusEvent::TestsFailed => payload.failed_test = reader.failed_test()?,
+        _ => {}
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::coverage::CoverageState;
+    use crate::entities::hook::HookAction;
+    use crate::result::HookRunErr;
+    use crate::testingtools::hook_runner::tracked;
+    use crate::testingtools::state::{self, StateValues};
+    use crate::testingtools::unit::create_test_shim;
+    use crate::use_cases::hook_runner::RunHook;
+
+    use anyhow::Result;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn hooks_of_published_event_are_run() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(false);
+        let hooks = vec![
+            "tests-failed=./first.sh".parse()?,
+            "tests-passed=./other.sh".parse()?,
+            "tests-failed=http://localhost/".parse()?,
+        ];
+        let shell = HooksShell::new(shim.bus(), cfg(hooks)?);
+        shell.run(runner, state::working());
+
+        // when
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.run_called_with_matching(|action, payload| {
+            *action == HookAction::Command("./first.sh".into()) && payload.event == "tests-failed"
+        }));
+        assert!(spy.run_called_with_matching(|action, _| {
+            *action == HookAction::Http("http://localhost/".into())
+        }));
+        assert!(spy.run_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_hook_does_not_stop_running_hooks() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(true);
+        let hooks = vec!["check-failed=./hook.sh".parse()?];
+        let shell = HooksShell::new(shim.bus(), cfg(hooks)?);
+        shell.run(runner, state::working());
+
+        // when
+        shim.simulate_check_failed()?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.run_called_with_matching(|_, payload| payload.event == "check-failed"));
+        assert!(spy.run_called_with_matching(|_, payload| payload.event == "check-failed"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_value_is_passed_to_hook() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(false);
+        let hooks = vec!["coverage-passed=./hook.sh".parse()?];
+        let st = state::working_with(StateValues {
+            cov_state: CoverageState::Success(75.0),
+            ..StateValues::default()
+        });
+        let shell = HooksShell::new(shim.bus(), cfg(hooks)?);
+        shell.run(runner, st);
+
+        // when
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(spy.run_called_with_matching(|_, payload| {
+            payload.event == "coverage-passed" && payload.coverage == Some(75.0)
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn slow_hook_does_not_hold_back_next_events() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, runner) = tracked(false);
+        let hooks = vec!["check-failed=./hook.sh".parse()?];
+        let shell = HooksShell::new(shim.bus(), cfg(hooks)?);
+        shell.run(blocked(runner), state::working());
+
+        // when
+        shim.simulate_check_failed()?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.run_called_with_matching(|_, payload| payload.event == "check-failed"));
+        assert!(spy.run_called_with_matching(|_, payload| payload.event == "check-failed"));
+
+        Ok(())
+    }
+
+    fn cfg(hooks: Vec<Hook>) -> Result<Config> {
+        Ok(ConfigBuilder::default().hooks(hooks).build()?)
+    }
+
+    /// Runner which doesn't finish for longer than the spy waits for the next hook.
+    struct SlowHookRunner {
+        runner: HookRunner,
+    }
+
+    impl RunHook for SlowHookRunner {
+        fn run(&self, action: &HookAction, payload: &HookPayload) -> Result<(), HookRunErr> {
+            self.runner.run(action, payload)?;
+            thread::sleep(Duration::from_secs(10));
+            Ok(())
+        }
+    }
+
+    fn blocked(runner: HookRunner) -> HookRunner {
+        Arc::new(SlowHookRunner { runner })
+    }
+}
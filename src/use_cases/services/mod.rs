@@ -1,5 +1,16 @@
 pub mod check_shell;
 pub mod coverage_shell;
+pub mod coverage_trigger_shell;
+pub mod custom_stages_shell;
+pub mod diff_coverage_shell;
+pub mod fmt_shell;
+pub mod history_shell;
+pub mod hooks_shell;
+pub mod notifier_shell;
+pub mod one_shot_shell;
+pub mod provenance_shell;
+pub mod quality_shell;
+pub mod stage_timing_shell;
 pub mod tests_index_shell;
 pub mod tests_shell;
 pub mod watcher_shell;
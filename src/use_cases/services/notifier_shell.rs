@@ -0,0 +1,259 @@
+use crate::configuration::config::Config;
+use crate::entities::notification::{Notification, NotifyPolicy};
+use crate::entities::stage::Stage;
+use crate::result::NotifierErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::notifier::Notifier;
+use crate::use_cases::state::State;
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, NotifierErr>;
+
+/// Sends desktop notifications when stages fail and when the pipeline passes again.
+///
+/// The pipeline fails when any of the check, formatting check, tests, coverage or custom stages
+/// failed and it passes again once all failed stages passed. What is sent depends on the `Config::notify_policy`:
+/// - `NotifyPolicy::Off` - nothing
+/// - `NotifyPolicy::Transitions` - only when the pipeline starts failing or passes again
+/// - `NotifyPolicy::Failures` - on every failed stage and when the pipeline passes again
+///
+/// The notification names the failed stage and, when tests failed, the first failing test.
+/// Notifications which can't be delivered are only logged.
+pub struct NotifierShell {
+    bus: EventBus,
+    policy: NotifyPolicy,
+}
+
+impl NotifierShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let policy = cfg.notify_policy;
+        Self { bus, policy }
+    }
+
+    #[instrument(skip(self, notifier, st))]
+    pub fn run(self, notifier: Notifier, st: State) {
+        if self.policy == NotifyPolicy::Off {
+            debug!("notifications are turned off");
+            return;
+        }
+
+        let sub = self.bus.subscriber();
+        let policy = self.policy;
+        thread::spawn(move || -> Result<()> {
+            let mut failing: HashSet<Failable> = HashSet::new();
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                let Some((stage, passed)) = result(event) else {
+                    trace!("not a stage result");
+                    continue;
+                };
+
+                let was_failing = !failing.is_empty();
+                let notification = if !passed {
+                    let notification = failure(&stage, &st)?;
+                    failing.insert(stage);
+                    if was_failing && policy == NotifyPolicy::Transitions {
+                        continue;
+                    }
+                    notification
+                } else {
+                    failing.remove(&stage);
+                    if !was_failing || !failing.is_empty() {
+                        continue;
+                    }
+                    Notification::new("Chester: pipeline passed", "All stages pass again.")
+                };
+
+                debug!("sending notification: {notification:?}");
+                if let Err(e) = notifier.notify(notification) {
+                    error!("failed to send notification: {e:?}");
+                }
+            }
+        });
+    }
+}
+
+/// Anything which can fail the pipeline.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum Failable {
+    Stage(Stage),
+    Fmt,
+    Custom(String),
+}
+
+impl Display for Failable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Failable::Stage(stage) => write!(f, "{stage}"),
+            Failable::Fmt => write!(f, "fmt"),
+            Failable::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// What finished with the event and whether it passed.
+fn result(event: BusEvent) -> Option<(Failable, bool)> {
+    match event {
+        BusEvent::CheckPassed | BusEvent::TestsPassed | BusEvent::CoveragePassed => {
+            Some((Failable::Stage(event.finished_stage()?), true))
+        }
+        BusEvent::CheckFailed
+        | BusEvent::CheckWarned
+        | BusEvent::TestsFailed
+        | BusEvent::CoverageFailed => Some((Failable::Stage(event.finished_stage()?), false)),
+        BusEvent::FmtPassed => Some((Failable::Fmt, true)),
+        BusEvent::FmtFailed => Some((Failable::Fmt, false)),
+        BusEvent::StagePassed(name) => Some((Failable::Custom(name), true)),
+        BusEvent::StageFailed(name) => Some((Failable::Custom(name), false)),
+        _ => None,
+    }
+}
+
+fn failure(stage: &Failable, st: &State) -> Result<Notification> {
+    let summary = format!("Chester: {stage} failed");
+    let body = match (stage, st.reader().failed_test()?) {
+        (Failable::Stage(Stage::Tests), Some(test)) => format!("First failing test: {test}"),
+        _ => format!("The {stage} stage failed."),
+    };
+    Ok(Notification::new(summary, body))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::config::ConfigBuilder;
+    use crate::configuration::tracing::init_tracing;
+    use crate::testingtools::notifier::tracked;
+    use crate::testingtools::state::{self, StateValues};
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+
+    #[test]
+    fn failed_tests_are_notified_with_first_failing_test() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, notifier) = tracked();
+        let st = state::working_with(StateValues {
+            failed_test: Some("it_works".into()),
+            ..StateValues::default()
+        });
+        let shell = NotifierShell::new(shim.bus(), cfg(NotifyPolicy::Transitions)?);
+        shell.run(notifier, st);
+
+        // when
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.notify_called_with_matching(|n| {
+            n.summary == "Chester: tests failed" && n.body == "First failing test: it_works"
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn only_transitions_are_notified_by_default() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, notifier) = tracked();
+        let shell = NotifierShell::new(shim.bus(), Config::default());
+        shell.run(notifier, state::working());
+
+        // when
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_passed()?;
+        shim.simulate_check_failed()?;
+        shim.simulate_check_failed()?;
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: check failed"));
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: pipeline passed"));
+        assert!(spy.notify_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pipeline_passes_again_when_all_failed_stages_passed() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, notifier) = tracked();
+        let shell = NotifierShell::new(shim.bus(), cfg(NotifyPolicy::Failures)?);
+        shell.run(notifier, state::working());
+
+        // when
+        shim.simulate_tests_failed()?;
+        shim.simulate_coverage_failed()?;
+        shim.simulate_tests_passed()?;
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: tests failed"));
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: coverage failed"));
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: pipeline passed"));
+        assert!(spy.notify_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_fmt_and_custom_stages_fail_the_pipeline() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, notifier) = tracked();
+        let shell = NotifierShell::new(shim.bus(), cfg(NotifyPolicy::Failures)?);
+        shell.run(notifier, state::working());
+
+        // when
+        shim.simulate_fmt_failed()?;
+        shim.simulate_stage_failed("clippy")?;
+        shim.simulate_fmt_passed()?;
+        shim.simulate_stage_passed("clippy")?;
+
+        // then
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: fmt failed"));
+        assert!(spy.notify_called_with_matching(|n| {
+            n.summary == "Chester: clippy failed" && n.body == "The clippy stage failed."
+        }));
+        assert!(spy.notify_called_with_matching(|n| n.summary == "Chester: pipeline passed"));
+        assert!(spy.notify_not_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_is_notified_when_notifications_are_off() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let (spy, notifier) = tracked();
+        let shell = NotifierShell::new(shim.bus(), cfg(NotifyPolicy::Off)?);
+        shell.run(notifier, state::working());
+
+        // when
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.notify_not_called());
+
+        Ok(())
+    }
+
+    fn cfg(policy: NotifyPolicy) -> Result<Config> {
+        Ok(ConfigBuilder::default().notify_policy(policy).build()?)
+    }
+}
@@ -0,0 +1,407 @@
+use crate::configuration::config::Config;
+use crate::entities::custom_stage::{CustomStage, BUILT_IN_STAGES};
+use crate::entities::fmt::FmtMode;
+use crate::entities::stage::Stage;
+use crate::entities::state_snapshot::Verdict;
+use crate::result::SetupErr;
+use crate::use_cases::bus::{BusEvent, EventBus, EventSubscriber};
+use crate::use_cases::pipeline::Pipeline;
+use crate::use_cases::state::State;
+
+use std::collections::HashSet;
+use tracing::{debug, instrument, trace};
+
+type Result<T> = std::result::Result<T, SetupErr>;
+
+/// Runs the whole pipeline once, eg. in the CI, and returns its verdict.
+///
+/// Unlike other shells, it doesn't spawn a thread: `OneShotShell::run` blocks until every enabled
+/// stage of the run is done:
+/// - the built-in stages - until `BusEvent::CoveragePassed`, `BusEvent::CoverageFailed` or
+///   `BusEvent::CoverageSkipped`, or until the failed stage stops the run (see
+///   `Pipeline::stopped_by`), or until `BusEvent::IndexFailed`
+/// - the formatting check in the informational mode - `BusEvent::FmtPassed` or `BusEvent::FmtFailed`
+/// - the quality - `BusEvent::QualityCalculated`
+/// - the custom stages - `BusEvent::StagePassed` or `BusEvent::StageFailed`, unless the stage is
+///   not run because a stage it depends on failed
+///
+/// Coverage below the minimum or regressed to the level of a failure makes the verdict red.
+pub struct OneShotShell {
+    sub: EventSubscriber,
+    pipeline: Pipeline,
+    stages: Vec<CustomStage>,
+    fmt_awaited: bool,
+}
+
+impl OneShotShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        // NOTE: subscribed before the run is requested, so no event of the run is missed
+        let sub = bus.subscriber();
+        let pipeline = Pipeline::new(&cfg);
+        // NOTE: Blocking formatting check is a part of the built-in stages chain.
+        let fmt_awaited = cfg.fmt_mode == FmtMode::Informational;
+        Self {
+            sub,
+            pipeline,
+            stages: cfg.custom_stages,
+            fmt_awaited,
+        }
+    }
+
+    #[instrument(skip(self, st))]
+    pub fn run(self, st: State) -> Result<Verdict> {
+        let run_id = st.writer().trigger(Stage::Check)?;
+        debug!("waiting for run {run_id} to finish");
+        let mut run = RunProgress {
+            fmt_done: !self.fmt_awaited,
+            ..RunProgress::default()
+        };
+        loop {
+            let event = self.sub.recv()?;
+            run.update(&event, &self.pipeline, &self.stages);
+            if run.is_finished(&self.stages) {
+                break;
+            }
+            trace!("pipeline not finished");
+        }
+        let verdict = st.reader().snapshot()?.verdict();
+        debug!("run {run_id} finished: {verdict:?}");
+        Ok(verdict)
+    }
+}
+
+/// Stages of the run which are done, and which of them passed as far as their dependents are
+/// concerned.
+#[derive(Debug, Default)]
+struct RunProgress {
+    chain_done: bool,
+    fmt_done: bool,
+    quality_done: bool,
+    passed: HashSet<String>,
+    finished: HashSet<String>,
+}
+
+impl RunProgress {
+    fn update(&mut self, event: &BusEvent, pipeline: &Pipeline, stages: &[CustomStage]) {
+        if pipeline.stopped_by(event).is_some() {
+            self.chain_done = true;
+        }
+        match event {
+            BusEvent::CheckPassed | BusEvent::CheckWarned | BusEvent::TestsPassed => {
+                self.pass_built_in(event);
+            }
+            BusEvent::CheckFailed | BusEvent::TestsFailed => {
+                if let Some(stage) = event.finished_stage() {
+                    if pipeline.continues_after(stage) {
+                        self.passed.insert(stage.to_string());
+                    }
+                }
+            }
+            BusEvent::CoveragePassed | BusEvent::CoverageSkipped => {
+                self.pass_built_in(&BusEvent::CoveragePassed);
+                self.chain_done = true;
+            }
+            BusEvent::CoverageFailed => {
+                if pipeline.continues_after(Stage::Coverage) {
+                    self.passed.insert(Stage::Coverage.to_string());
+                }
+                self.chain_done = true;
+            }
+            BusEvent::IndexFailed => self.chain_done = true,
+            BusEvent::FmtPassed | BusEvent::FmtFailed => self.fmt_done = true,
+            BusEvent::QualityCalculated(_) => self.quality_done = true,
+            BusEvent::StagePassed(name) => {
+                self.passed.insert(name.clone());
+                self.finished.insert(name.clone());
+            }
+            BusEvent::StageFailed(name) => {
+                let continues = stages
+                    .iter()
+                    .any(|stage| stage.name == *name && stage.continue_on_failure);
+                if continues {
+                    self.passed.insert(name.clone());
+                }
+                self.finished.insert(name.clone());
+            }
+            _ => {}
+        }
+    }
+
+    fn pass_built_in(&mut self, event: &BusEvent) {
+        if let Some(stage) = event.finished_stage() {
+            self.passed.insert(stage.to_string());
+        }
+    }
+
+    fn is_finished(&self, stages: &[CustomStage]) -> bool {
+        self.chain_done
+            && self.fmt_done
+            && self.quality_done
+            && !stages.iter().any(|stage| self.is_awaited(stage, stages))
+    }
+
+    /// Custom stage is awaited until it finished, unless some stage it depends on can't pass
+    /// anymore, so it's skipped. Dependencies have no cycles, see `PipelineDef::validate`.
+    fn is_awaited(&self, stage: &CustomStage, stages: &[CustomStage]) -> bool {
+        if self.finished.contains(&stage.name) {
+            return false;
+        }
+        stage.depends_on.iter().all(|dep| {
+            if self.passed.contains(dep) {
+                true
+            } else if BUILT_IN_STAGES.contains(&dep.as_str()) {
+                !self.chain_done
+            } else {
+                stages
+                    .iter()
+                    .find(|other| other.name == *dep)
+                    .is_some_and(|other| self.is_awaited(other, stages))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::check::CheckState;
+    use crate::entities::coverage::CoverageState;
+    use crate::entities::custom_stage::{CustomStageState, SuccessCriterion};
+    use crate::testingtools::state::{self, StateValues};
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn when_coverage_passed_verdict_of_pipeline_is_returned() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let verdict = run_in_background(
+            OneShotShell::new(shim.bus(), Config::default()),
+            state::noop(),
+        );
+
+        // when
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert_eq!(
+            verdict.recv_timeout(Duration::from_secs(3))?,
+            Verdict::Green
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_coverage_is_below_minimum_verdict_is_red() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let st = state::working_with(StateValues {
+            cov_state: CoverageState::BelowThreshold {
+                actual: 40.0,
+                required: 80.0,
+                file: None,
+            },
+            ..StateValues::default()
+        });
+        let verdict = run_in_background(OneShotShell::new(shim.bus(), Config::default()), st);
+
+        // when
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_coverage_failed()?;
+
+        // then
+        assert_eq!(verdict.recv_timeout(Duration::from_secs(3))?, Verdict::Red);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_check_failed_verdict_is_returned_without_waiting_for_coverage() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let st = state::working_with(StateValues {
+            check_state: CheckState::Failure,
+            ..StateValues::default()
+        });
+        let verdict = run_in_background(OneShotShell::new(shim.bus(), Config::default()), st);
+
+        // when
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert_eq!(verdict.recv_timeout(Duration::from_secs(3))?, Verdict::Red);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_pipeline_continues_past_failed_check_run_is_not_finished() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            continue_on_failure: vec![Stage::Check],
+            ..Config::default()
+        };
+        let verdict = run_in_background(OneShotShell::new(shim.bus(), cfg), state::noop());
+
+        // when
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(verdict.recv_timeout(Duration::from_millis(200)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_not_finished_before_coverage() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let verdict = run_in_background(
+            OneShotShell::new(shim.bus(), Config::default()),
+            state::noop(),
+        );
+
+        // when
+        shim.simulate_tests_passed()?;
+
+        // then
+        assert!(verdict.recv_timeout(Duration::from_millis(200)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_not_finished_before_quality_is_calculated() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let verdict = run_in_background(
+            OneShotShell::new(shim.bus(), Config::default()),
+            state::noop(),
+        );
+
+        // when
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(verdict.recv_timeout(Duration::from_millis(200)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_not_finished_before_informational_fmt_check() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            fmt_mode: FmtMode::Informational,
+            ..Config::default()
+        };
+        let verdict = run_in_background(OneShotShell::new(shim.bus(), cfg), state::noop());
+
+        // when
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(verdict.recv_timeout(Duration::from_millis(200)).is_err());
+        shim.simulate_fmt_failed()?;
+        assert_eq!(
+            verdict.recv_timeout(Duration::from_secs(3))?,
+            Verdict::Green
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_is_not_finished_before_custom_stages() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            custom_stages: vec![stage("deny", &[]), stage("doc", &["tests"])],
+            ..Config::default()
+        };
+        let st = state::working_with(StateValues {
+            custom_stages: [(
+                "doc".to_string(),
+                CustomStageState::Failure { summary: None },
+            )]
+            .into(),
+            ..StateValues::default()
+        });
+        let verdict = run_in_background(OneShotShell::new(shim.bus(), cfg), st);
+
+        // when
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_stage_passed("deny")?;
+        shim.simulate_tests_passed()?;
+        shim.simulate_coverage_passed()?;
+
+        // then
+        assert!(verdict.recv_timeout(Duration::from_millis(200)).is_err());
+        shim.simulate_stage_failed("doc")?;
+        assert_eq!(verdict.recv_timeout(Duration::from_secs(3))?, Verdict::Red);
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_stages_skipped_after_failure_are_not_awaited() -> Result<()> {
+        // given
+        init_tracing();
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            custom_stages: vec![stage("doc", &["tests"]), stage("deploy", &["doc"])],
+            ..Config::default()
+        };
+        let verdict = run_in_background(OneShotShell::new(shim.bus(), cfg), state::noop());
+
+        // when
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(verdict.recv_timeout(Duration::from_secs(3)).is_ok());
+
+        Ok(())
+    }
+
+    fn stage(name: &str, depends_on: &[&str]) -> CustomStage {
+        CustomStage {
+            name: name.into(),
+            cmd: vec!["true".into()],
+            depends_on: depends_on.iter().map(ToString::to_string).collect(),
+            success: SuccessCriterion::ExitCode,
+            parser: None,
+            own_target_dir: false,
+            continue_on_failure: false,
+        }
+    }
+
+    fn run_in_background(shell: OneShotShell, st: State) -> Receiver<Verdict> {
+        let (tx, rx) = channel();
+        thread::spawn(move || -> super::Result<()> {
+            let _ = tx.send(shell.run(st)?);
+            Ok(())
+        });
+        rx
+    }
+}
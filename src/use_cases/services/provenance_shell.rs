@@ -0,0 +1,218 @@
+use crate::configuration::config::Config;
+use crate::entities::provenance::{StageProvenance, TrackedResult, TreeSnapshot};
+use crate::result::RunnerErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::pipeline::Pipeline;
+use crate::use_cases::state::State;
+use crate::use_cases::vcs::VersionControl;
+
+use std::collections::HashMap;
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, RunnerErr>;
+
+/// Keeps track of the source tree on which every result was produced: of the built-in stages,
+/// the formatting check, the quality metrics and the custom stages.
+///
+/// When a change is detected or a run is requested, it takes a snapshot of the source tree and
+/// updates the source tree state. The snapshot current when a stage starts is written as the
+/// provenance of the stage once it finishes. Results of the custom stages started before the
+/// latest change are dropped, so the current snapshot is the provenance of a finished custom
+/// stage. If the fingerprint can't be read from the version control system, the snapshot has
+/// only the time when it was taken.
+pub struct ProvenanceShell {
+    bus: EventBus,
+    pipeline: Pipeline,
+}
+
+impl ProvenanceShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let pipeline = Pipeline::new(&cfg);
+        Self { bus, pipeline }
+    }
+
+    #[instrument(skip(self, vcs, st))]
+    pub fn run(self, vcs: VersionControl, st: State) {
+        let sub = self.bus.subscriber();
+        let pipeline = self.pipeline;
+        thread::spawn(move || -> Result<()> {
+            let mut current = TreeSnapshot::default();
+            let mut started: HashMap<TrackedResult, TreeSnapshot> = HashMap::new();
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                if let BusEvent::ChangeDetected | BusEvent::RunRequested(_) = event {
+                    let repo_root = st.reader().repo_root()?;
+                    let fingerprint = vcs.fingerprint(repo_root).unwrap_or_else(|e| {
+                        error!("failed to read source tree fingerprint: {e:?}");
+                        Default::default()
+                    });
+                    current = TreeSnapshot::new(fingerprint);
+                    debug!("source tree changed: {current:?}");
+                    st.writer().source_tree(current.clone())?;
+                }
+                if let Some(name) = event.custom_stage() {
+                    finished(
+                        &st,
+                        TrackedResult::Custom(name.to_string()),
+                        current.clone(),
+                    )?;
+                }
+                if let Some(result) = finished_result(&event) {
+                    let tree = started.get(&result).cloned().unwrap_or_default();
+                    finished(&st, result, tree)?;
+                }
+                for result in started_results(&event, &pipeline) {
+                    started.insert(result, current.clone());
+                }
+            }
+        });
+    }
+}
+
+/// Built-in result which finished with the event.
+fn finished_result(event: &BusEvent) -> Option<TrackedResult> {
+    match event {
+        BusEvent::FmtPassed | BusEvent::FmtFailed => Some(TrackedResult::Fmt),
+        BusEvent::QualityCalculated(_) => Some(TrackedResult::Quality),
+        _ => event.finished_stage().map(TrackedResult::Stage),
+    }
+}
+
+/// Built-in results which started with the event.
+fn started_results(event: &BusEvent, pipeline: &Pipeline) -> Vec<TrackedResult> {
+    let mut results: Vec<TrackedResult> = event
+        .started_stage()
+        .map(TrackedResult::Stage)
+        .into_iter()
+        .collect();
+    if pipeline.starts_fmt(event) {
+        results.push(TrackedResult::Fmt);
+    }
+    if *event == BusEvent::ChangeDetected {
+        results.push(TrackedResult::Quality);
+    }
+    results
+}
+
+fn finished(st: &State, result: TrackedResult, tree: TreeSnapshot) -> Result<()> {
+    debug!("{result} finished on {tree:?}");
+    st.writer().provenance(result, StageProvenance::new(tree))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::fmt::FmtMode;
+    use crate::entities::provenance::Fingerprint;
+    use crate::entities::stage::Stage;
+    use crate::testingtools::state;
+    use crate::testingtools::unit::create_test_shim;
+    use crate::testingtools::vcs::{failing, working};
+
+    use anyhow::Result;
+
+    #[test]
+    fn when_change_is_detected_source_tree_is_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        ProvenanceShell::new(shim.bus(), Config::default()).run(working(Some("abc".into())), state);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.source_tree_called_with_matching(|tree| {
+            tree.fingerprint == Fingerprint::new(Some("abc".into()), None)
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_stage_finishes_tree_on_which_it_started_is_its_provenance() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        ProvenanceShell::new(shim.bus(), Config::default()).run(working(Some("abc".into())), state);
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_check_passed()?;
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.provenance_called_with_matching(|(result, provenance)| {
+            *result == TrackedResult::Stage(Stage::Check)
+                && provenance.tree.fingerprint.commit == Some("abc".into())
+        }));
+        assert!(spy.provenance_called_with_matching(|(result, provenance)| {
+            *result == TrackedResult::Stage(Stage::Tests)
+                && provenance.tree.fingerprint.commit == Some("abc".into())
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn provenance_of_fmt_quality_and_custom_stages_is_recorded() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let cfg = Config {
+            fmt_mode: FmtMode::Informational,
+            ..Config::default()
+        };
+        let shim = create_test_shim()?;
+        ProvenanceShell::new(shim.bus(), cfg).run(working(Some("abc".into())), state);
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_fmt_passed()?;
+        shim.simulate_quality_calculated(None)?;
+        shim.simulate_stage_passed("clippy")?;
+
+        // then
+        let from_abc =
+            |provenance: &StageProvenance| provenance.tree.fingerprint.commit == Some("abc".into());
+        assert!(spy.provenance_called_with_matching(|(result, provenance)| {
+            *result == TrackedResult::Fmt && from_abc(provenance)
+        }));
+        assert!(spy.provenance_called_with_matching(|(result, provenance)| {
+            *result == TrackedResult::Quality && from_abc(provenance)
+        }));
+        assert!(spy.provenance_called_with_matching(|(result, provenance)| {
+            *result == TrackedResult::Custom("clippy".into()) && from_abc(provenance)
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_fingerprint_can_not_be_read_source_tree_is_still_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        ProvenanceShell::new(shim.bus(), Config::default()).run(failing(), state);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.source_tree_called_with_matching(|tree| {
+            tree.fingerprint == Fingerprint::default()
+        }));
+
+        Ok(())
+    }
+}
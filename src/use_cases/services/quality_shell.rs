@@ -0,0 +1,127 @@
+use crate::result::QualityErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::quality_analyzer::{CodeAnalysis, QualityAnalyzer};
+use crate::use_cases::state::State;
+
+use std::thread;
+use tracing::{debug, error, instrument, trace};
+
+type Result<T> = std::result::Result<T, QualityErr>;
+
+/// When change is detected, it calculates the quality metrics of the code (cyclomatic complexity
+/// and maintainability index of every function and file) and updates quality state. The
+/// statistics of the code (lines of code, comments, `unsafe` blocks and TODOs) are collected in
+/// the same pass and update stats state.
+///
+/// The metrics don't depend on the result of other stages, so they're calculated next to the
+/// check stage. If the code can't be analyzed, neither quality nor stats state is updated.
+///
+/// It publishes following events:
+/// - `BusEvent::QualityCalculated` - when the code was analyzed after the change, also when it
+///   couldn't be analyzed, so the result always follows the change
+pub struct QualityShell {
+    bus: EventBus,
+}
+
+impl QualityShell {
+    pub fn new(bus: EventBus) -> Self {
+        Self { bus }
+    }
+
+    #[instrument(skip(self, qa, st))]
+    pub fn run(self, qa: QualityAnalyzer, st: State) {
+        let sub = self.bus.subscriber();
+        let publ = self.bus.publisher();
+        thread::spawn(move || -> Result<()> {
+            loop {
+                let Ok(BusEvent::ChangeDetected) = sub.recv() else {
+                    trace!("no change detected");
+                    continue;
+                };
+
+                let CodeAnalysis { quality, stats } = match qa.analyze(st.reader().repo_root()?) {
+                    Ok(analysis) => analysis,
+                    Err(e) => {
+                        error!("failed to analyze quality of the code: {e:?}");
+                        publ.send(BusEvent::QualityCalculated(None))?;
+                        continue;
+                    }
+                };
+                debug!("quality of {} files calculated", quality.files.len());
+                let halstead = quality.halstead();
+                st.writer().quality(quality)?;
+                st.writer().stats(stats)?;
+                publ.send(BusEvent::QualityCalculated(Some(halstead)))?;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::quality::{FileQuality, QualityReport};
+    use crate::entities::stats::ProjectStats;
+    use crate::testingtools::quality_analyzer::{failing, tracked, working};
+    use crate::testingtools::state;
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+
+    #[test]
+    fn when_change_is_detected_quality_and_stats_are_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let report = QualityReport::new(vec![FileQuality {
+            path: "src/main.rs".into(),
+            ..FileQuality::default()
+        }]);
+        let stats = ProjectStats {
+            files: 1,
+            sloc: 120,
+            unsafe_blocks: 2,
+            ..ProjectStats::default()
+        };
+        let (analyzer_spy, analyzer) = tracked(working(CodeAnalysis {
+            quality: report.clone(),
+            stats,
+        }));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        QualityShell::new(shim.bus()).run(analyzer, state);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(analyzer_spy.analyze_called());
+        assert!(spy.quality_called_with_matching(|r| *r == report));
+        assert!(spy.stats_called_with_val(&stats));
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        assert!(shim.event_on_bus(&BusEvent::QualityCalculated(Some(report.halstead())))?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_analysis_fails_quality_and_stats_are_not_updated() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        QualityShell::new(shim.bus()).run(failing(), state);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(!spy.quality_called_with_matching(|_| true));
+        assert!(!spy.stats_called());
+        shim.ignore_event()?; // ignore BusEvent::ChangeDetected
+        assert!(shim.event_on_bus(&BusEvent::QualityCalculated(None))?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use crate::configuration::config::Config;
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::StageTiming;
+use crate::result::RunnerErr;
+use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::pipeline::Pipeline;
+use crate::use_cases::state::State;
+
+use std::collections::HashMap;
+use std::thread;
+use tracing::{debug, instrument, trace};
+
+type Result<T> = std::result::Result<T, RunnerErr>;
+
+/// Records when every stage started and finished.
+///
+/// Stage starts with the event which triggers it and finishes with its result. The same as the
+/// stage shells, it takes `Config::fmt_mode` and `Config::continue_on_failure` into account, so
+/// eg. tests start after the blocking formatting check passed. A stage which finished without
+/// being started (for example when chester was started in the middle of a run) is not recorded.
+pub struct StageTimingShell {
+    bus: EventBus,
+    pipeline: Pipeline,
+}
+
+impl StageTimingShell {
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let pipeline = Pipeline::new(&cfg);
+        Self { bus, pipeline }
+    }
+
+    #[instrument(skip(self, st))]
+    pub fn run(self, st: State) {
+        let sub = self.bus.subscriber();
+        thread::spawn(move || -> Result<()> {
+            let mut timings: HashMap<Stage, StageTiming> = HashMap::new();
+            loop {
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
+                    continue;
+                };
+                if let Some((stage, passed)) = finished_stage(&event) {
+                    if let Some(mut timing) = timings.remove(&stage) {
+                        timing.finish(passed);
+                        debug!("{stage} finished in {:?}", timing.duration());
+                        st.writer().timing(stage, timing)?;
+                    }
+                }
+                if let Some(stage) = self.started_stage(&event) {
+                    debug!("{stage} started");
+                    let timing = StageTiming::start();
+                    timings.insert(stage, timing);
+                    st.writer().timing(stage, timing)?;
+                }
+            }
+        });
+    }
+
+    fn started_stage(&self, event: &BusEvent) -> Option<Stage> {
+        match event {
+            BusEvent::ChangeDetected => Some(Stage::Check),
+            _ if self.pipeline.starts_tests(event) => Some(Stage::Tests),
+            BusEvent::TestsPassed => Some(Stage::Index),
+            BusEvent::TestsFailed if self.pipeline.continues_after(Stage::Tests) => {
+                Some(Stage::Index)
+            }
+            BusEvent::CoverageRequested => Some(Stage::Coverage),
+            _ => None,
+        }
+    }
+}
+
+/// Stage which finished with the event and whether it passed.
+fn finished_stage(event: &BusEvent) -> Option<(Stage, bool)> {
+    match event {
+        BusEvent::CheckPassed => Some((Stage::Check, true)),
+        BusEvent::CheckFailed | BusEvent::CheckWarned => Some((Stage::Check, false)),
+        BusEvent::TestsPassed => Some((Stage::Tests, true)),
+        BusEvent::TestsFailed => Some((Stage::Tests, false)),
+        BusEvent::TestsSetChanged | BusEvent::TestsSetNotChanged => Some((Stage::Index, true)),
+        BusEvent::IndexFailed => Some((Stage::Index, false)),
+        BusEvent::CoveragePassed => Some((Stage::Coverage, true)),
+        BusEvent::CoverageFailed => Some((Stage::Coverage, false)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::configuration::tracing::init_tracing;
+    use crate::entities::fmt::FmtMode;
+    use crate::testingtools::state;
+    use crate::testingtools::unit::create_test_shim;
+
+    use anyhow::Result;
+
+    #[test]
+    fn when_stage_is_triggered_it_is_running() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        StageTimingShell::new(shim.bus(), Config::default()).run(state);
+
+        // when
+        shim.simulate_change()?;
+
+        // then
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Check && timing.is_running()
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_stage_passes_it_is_finished_and_next_stage_is_running() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        StageTimingShell::new(shim.bus(), Config::default()).run(state);
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Check && timing.is_running()
+        }));
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Check && timing.duration().is_some() && !timing.failed
+        }));
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Tests && timing.is_running()
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn when_stage_fails_it_is_finished_as_failed() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        StageTimingShell::new(shim.bus(), Config::default()).run(state);
+
+        // when
+        shim.simulate_change()?;
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Check && timing.is_running()
+        }));
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Check && timing.duration().is_some() && timing.failed
+        }));
+        assert!(!spy.timing_called_with_matching(|(stage, _)| *stage == Stage::Tests));
+
+        Ok(())
+    }
+
+    #[test]
+    fn index_refresh_runs_from_tests_passed_until_tests_set_is_checked() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        StageTimingShell::new(shim.bus(), Config::default()).run(state);
+
+        // when
+        shim.simulate_tests_passed()?;
+        shim.simulate_tests_changed()?;
+
+        // then
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Index && timing.is_running()
+        }));
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Index && timing.duration().is_some()
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn failed_index_refresh_finishes_index_stage_as_failed() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        StageTimingShell::new(shim.bus(), Config::default()).run(state);
+
+        // when
+        shim.simulate_tests_passed()?;
+        shim.simulate_index_failed()?;
+
+        // then
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Index && timing.is_running()
+        }));
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Index && timing.duration().is_some() && timing.failed
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn with_blocking_fmt_tests_start_after_fmt_passed() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        let cfg = Config {
+            fmt_mode: FmtMode::Blocking,
+            ..Config::default()
+        };
+        StageTimingShell::new(shim.bus(), cfg).run(state);
+
+        // when
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(!spy.timing_called_with_matching(|(stage, _)| *stage == Stage::Tests));
+
+        // when
+        shim.simulate_fmt_passed()?;
+
+        // then
+        assert!(spy.timing_called_with_matching(|(stage, timing)| {
+            *stage == Stage::Tests && timing.is_running()
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stage_finished_without_start_is_not_recorded() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, state) = state::tracked(&state::working());
+        let shim = create_test_shim()?;
+        StageTimingShell::new(shim.bus(), Config::default()).run(state);
+
+        // when
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(!spy.timing_called_with_matching(|(stage, _)| *stage == Stage::Tests));
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::configuration::config::Config;
+use crate::entities::stage::Stage;
 use crate::result::IndexErr;
 use crate::use_cases::bus::{BusEvent, EventBus};
 use crate::use_cases::state::State;
@@ -11,34 +13,57 @@ type Result<T> = std::result::Result<T, IndexErr>;
 /// When tests passed, it refreshes tests index and triggers code coverage if tests set changed.
 ///
 /// When the tests are run, `TestsIndexShell` checks tests status.
-/// If the tests failed, then nothing more happens.
-/// If the tests finished with success, then it refreshes tests index.
+/// If the tests failed, then nothing more happens, unless the tests are listed in
+/// `Config::continue_on_failure`.
+/// If the tests finished with success, then it refreshes tests index and updates the number of
+/// the tests in the stats state.
 ///
 /// It publishes following events:
-/// - `BusEvent::TestsSetChanged` - when tests passed and tests set is changed
-/// - `BusEvent::TestsSetNotChanged` - when tests passed, but tests set is not changed
+/// - `BusEvent::TestsSetChanged` - when index was refreshed and tests set is changed
+/// - `BusEvent::TestsSetNotChanged` - when index was refreshed, but tests set is not changed
+/// - `BusEvent::IndexFailed` - when index couldn't be refreshed
 pub struct TestsIndexShell {
     bus: EventBus,
+    after_failed_tests: bool,
 }
 
 impl TestsIndexShell {
-    pub fn new(bus: EventBus) -> Self {
-        Self { bus }
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let after_failed_tests = cfg.continue_on_failure.contains(&Stage::Tests);
+        Self {
+            bus,
+            after_failed_tests,
+        }
     }
 
     #[instrument(skip(self, index, st))]
     pub fn run(self, index: TestsIndex, st: State) {
         let sub = self.bus.subscriber();
         let publ = self.bus.publisher();
+        let after_failed_tests = self.after_failed_tests;
         thread::spawn(move || -> Result<()> {
             loop {
-                let Ok(BusEvent::TestsPassed) = sub.recv() else {
-                    trace!("tests failed, skipping index refresh");
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
                     continue;
                 };
+                match event {
+                    BusEvent::TestsPassed => {}
+                    BusEvent::TestsFailed if after_failed_tests => {
+                        debug!("tests failed, but index is refreshed anyway");
+                    }
+                    _ => {
+                        trace!("tests failed, skipping index refresh");
+                        continue;
+                    }
+                }
 
                 debug!("checking if tests changed");
-                match index.refresh(st.reader().repo_root()?) {
+                let status = index.refresh(st.reader().repo_root()?);
+                if let Some(count) = index.tests_count() {
+                    st.writer().tests_count(count)?;
+                }
+                match status {
                     Ok(IndexStatus::TestsSetChanged) => {
                         debug!("tests change detected");
                         publ.send(BusEvent::TestsSetChanged)?;
@@ -47,8 +72,14 @@ impl TestsIndexShell {
                         debug!("tests not changed");
                         publ.send(BusEvent::TestsSetNotChanged)?;
                     }
-                    Ok(IndexStatus::Failure) => error!("index refresh failed"),
-                    Err(e) => error!("error while running index refresh: {e:?}"),
+                    Ok(IndexStatus::Failure) => {
+                        error!("index refresh failed");
+                        publ.send(BusEvent::IndexFailed)?;
+                    }
+                    Err(e) => {
+                        error!("error while running index refresh: {e:?}");
+                        publ.send(BusEvent::IndexFailed)?;
+                    }
                 }
             }
         });
@@ -59,9 +90,10 @@ impl TestsIndexShell {
 mod test {
     use super::*;
 
+    use crate::configuration::config::ConfigBuilder;
     use crate::configuration::tracing::init_tracing;
     use crate::testingtools::state;
-    use crate::testingtools::tests_index::{failing, tracked, working};
+    use crate::testingtools::tests_index::{failing, tracked, working, working_with_tests};
     use crate::testingtools::unit::create_test_shim;
 
     use anyhow::Result;
@@ -73,7 +105,7 @@ mod test {
         let (spy, index) = tracked(working(IndexStatus::TestsSetChanged));
         let state = state::noop();
         let shim = create_test_shim()?;
-        TestsIndexShell::new(shim.bus()).run(index, state);
+        TestsIndexShell::new(shim.bus(), Config::default()).run(index, state);
 
         // when
         shim.simulate_tests_passed()?;
@@ -84,6 +116,44 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn index_is_started_after_failed_tests_when_tests_continue_on_failure() -> Result<()> {
+        // given
+        init_tracing();
+        let (spy, index) = tracked(working(IndexStatus::TestsSetChanged));
+        let cfg = ConfigBuilder::default()
+            .continue_on_failure(vec![Stage::Tests])
+            .build()?;
+        let shim = create_test_shim()?;
+        TestsIndexShell::new(shim.bus(), cfg).run(index, state::noop());
+
+        // when
+        shim.simulate_tests_failed()?;
+
+        // then
+        assert!(spy.refresh_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tests_counted_by_index_are_written_to_state() -> Result<()> {
+        // given
+        init_tracing();
+        let index = working_with_tests(IndexStatus::TestsSetNotChanged, 42);
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        TestsIndexShell::new(shim.bus(), Config::default()).run(index, state);
+
+        // when
+        shim.simulate_tests_passed()?;
+
+        // then
+        assert!(spy.tests_count_called_with_val(42));
+
+        Ok(())
+    }
+
     #[test]
     fn when_tests_changed_there_is_correct_event_on_the_bus() -> Result<()> {
         // given
@@ -91,7 +161,7 @@ mod test {
         let index = working(IndexStatus::TestsSetChanged);
         let state = state::noop();
         let shim = create_test_shim()?;
-        TestsIndexShell::new(shim.bus()).run(index, state);
+        TestsIndexShell::new(shim.bus(), Config::default()).run(index, state);
 
         // when
         shim.simulate_tests_passed()?;
@@ -110,7 +180,7 @@ mod test {
         let index = working(IndexStatus::TestsSetNotChanged);
         let state = state::noop();
         let shim = create_test_shim()?;
-        TestsIndexShell::new(shim.bus()).run(index, state);
+        TestsIndexShell::new(shim.bus(), Config::default()).run(index, state);
 
         // when
         shim.simulate_tests_passed()?;
@@ -123,39 +193,39 @@ mod test {
     }
 
     #[test]
-    fn when_index_fails_to_refresh_nothing_is_on_the_bus() -> Result<()> {
+    fn when_index_fails_to_refresh_index_failed_is_on_the_bus() -> Result<()> {
         // given
         init_tracing();
         let index = working(IndexStatus::Failure);
         let state = state::noop();
         let shim = create_test_shim()?;
-        TestsIndexShell::new(shim.bus()).run(index, state);
+        TestsIndexShell::new(shim.bus(), Config::default()).run(index, state);
 
         // when
         shim.simulate_tests_passed()?;
         shim.ignore_event()?;
 
         // then
-        assert!(shim.no_event_on_bus()?);
+        assert!(shim.event_on_bus(&BusEvent::IndexFailed)?);
 
         Ok(())
     }
 
     #[test]
-    fn when_indexing_command_fails_there_is_no_event_on_the_bus() -> Result<()> {
+    fn when_indexing_command_fails_index_failed_is_on_the_bus() -> Result<()> {
         // given
         init_tracing();
         let index = failing();
         let state = state::noop();
         let shim = create_test_shim()?;
-        TestsIndexShell::new(shim.bus()).run(index, state);
+        TestsIndexShell::new(shim.bus(), Config::default()).run(index, state);
 
         // when
         shim.simulate_tests_passed()?;
         shim.ignore_event()?;
 
         // then
-        assert!(shim.no_event_on_bus()?);
+        assert!(shim.event_on_bus(&BusEvent::IndexFailed)?);
 
         Ok(())
     }
@@ -1,6 +1,8 @@
+use crate::configuration::config::Config;
 use crate::entities::tests::TestsState;
 use crate::result::RunnerErr;
 use crate::use_cases::bus::{BusEvent, EventBus};
+use crate::use_cases::pipeline::Pipeline;
 use crate::use_cases::state::State;
 use crate::use_cases::test_runner::{TestRunner, TestsRunStatus};
 
@@ -12,45 +14,76 @@ type Result<T> = std::result::Result<T, RunnerErr>;
 /// When check passed, it runs tests, updates tests state and publishes result of the tests.
 ///
 /// `TestsShell` first waits for the event describing the result of the check stage.
-/// If check stage failed, nothing happens.
+/// If check stage failed, the tests state is set as `TestsState::Skipped`, unless the check is
+/// listed in `Config::continue_on_failure` (eg. when the check fails on warnings only).
 /// If check stage succeeds, `TestsShell` sets the tests state as `TestsState::Pending`, then
-/// runs the tests.
+/// runs the tests. Check which failed only on warnings (`BusEvent::CheckWarned`) doesn't stop
+/// them either.
+/// When `Config::fmt_mode` is `FmtMode::Blocking`, the tests are run only after the formatting
+/// check passed, otherwise they are skipped as well.
+/// Tests requested via the API (`BusEvent::TestsRequested`) are run right away.
 /// Tests state is updated accordingly to the result of the tests.
 ///
 /// It publishes following events:
-/// - `BusEvent::TestsPassed` - when check passed and tests passed as well
-/// - `BusEvent::TestsFailed` - when check passed, but tests failed
+/// - `BusEvent::TestsPassed` - when tests were run and passed
+/// - `BusEvent::TestsFailed` - when tests were run, but failed
 pub struct TestsShell {
     bus: EventBus,
+    pipeline: Pipeline,
 }
 
 impl TestsShell {
-    pub fn new(bus: EventBus) -> Self {
-        Self { bus }
+    pub fn new(bus: EventBus, cfg: Config) -> Self {
+        let pipeline = Pipeline::new(&cfg);
+        Self { bus, pipeline }
     }
 
     #[instrument(skip(self, tr, st))]
     pub fn run(self, tr: TestRunner, st: State) {
         let sub = self.bus.subscriber();
         let publ = self.bus.publisher();
+        let pipeline = self.pipeline;
         thread::spawn(move || -> Result<()> {
             let sw = st.writer();
             loop {
-                let Ok(BusEvent::CheckPassed) = sub.recv() else {
-                    trace!("check failed, skipping tests");
+                let Ok(event) = sub.recv() else {
+                    trace!("no event received");
                     continue;
                 };
+                match event {
+                    _ if pipeline.starts_tests(&event) => {}
+                    BusEvent::CheckFailed | BusEvent::FmtFailed => {
+                        if let Some(failed) = pipeline.stopped_by(&event) {
+                            debug!("{failed} failed, skipping tests");
+                            sw.tests(TestsState::Skipped {
+                                reason: format!("{failed} failed"),
+                            })?;
+                        }
+                        continue;
+                    }
+                    _ => {
+                        trace!("check not finished");
+                        continue;
+                    }
+                }
 
                 debug!("running tests");
                 sw.tests(TestsState::Pending)?;
-                let Ok(TestsRunStatus::Success) = tr.run(st.reader().repo_root()?) else {
+                let status = tr.run(st.reader().repo_root()?);
+                let Ok(TestsRunStatus::Success) = status else {
                     debug!("tests failed");
+                    let failed_test = match status {
+                        Ok(TestsRunStatus::Failure(name)) => name,
+                        _ => None,
+                    };
+                    sw.failed_test(failed_test)?;
                     sw.tests(TestsState::Failure)?;
                     publ.send(BusEvent::TestsFailed)?;
                     continue;
                 };
 
                 debug!("tests passed");
+                sw.failed_test(None)?;
                 sw.tests(TestsState::Success)?;
                 publ.send(BusEvent::TestsPassed)?;
             }
@@ -62,7 +95,10 @@ impl TestsShell {
 mod test {
     use super::*;
 
+    use crate::configuration::config::ConfigBuilder;
     use crate::configuration::tracing::init_tracing;
+    use crate::entities::fmt::FmtMode;
+    use crate::entities::stage::Stage;
     use crate::testingtools::state;
     use crate::testingtools::test_runner::{failing, tracked, working};
     use crate::testingtools::unit::create_test_shim;
@@ -76,7 +112,7 @@ mod test {
         let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, noop_state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, noop_state);
 
         // when
         shim.simulate_check_passed()?;
@@ -87,6 +123,134 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn requested_tests_are_started_without_waiting_for_blocking_fmt() -> Result<()> {
+        // given
+        init_tracing();
+        let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
+        let cfg = Config {
+            fmt_mode: FmtMode::Blocking,
+            ..Config::default()
+        };
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), cfg).run(test_runner, state::noop());
+
+        // when
+        shim.simulate_tests_requested()?;
+
+        // then
+        assert!(test_runner_spy.run_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tests_are_started_after_failed_check_when_check_continues_on_failure() -> Result<()> {
+        // given
+        init_tracing();
+        let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
+        let cfg = ConfigBuilder::default()
+            .continue_on_failure(vec![Stage::Check])
+            .build()?;
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), cfg).run(test_runner, state::noop());
+
+        // when
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(test_runner_spy.run_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tests_are_started_when_check_failed_on_warnings_only() -> Result<()> {
+        // given
+        init_tracing();
+        let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, state::noop());
+
+        // when
+        shim.simulate_check_warned()?;
+
+        // then
+        assert!(test_runner_spy.run_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tests_are_skipped_when_check_failed() -> Result<()> {
+        // given
+        init_tracing();
+        let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, state);
+
+        // when
+        shim.simulate_check_failed()?;
+
+        // then
+        assert!(spy.tests_state_called_with_val(&TestsState::Skipped {
+            reason: "check failed".into()
+        }));
+        assert!(!test_runner_spy.run_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn blocking_formatting_check_must_pass_before_tests_are_started() -> Result<()> {
+        // given
+        init_tracing();
+        let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
+        let cfg = ConfigBuilder::default()
+            .fmt_mode(FmtMode::Blocking)
+            .build()?;
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), cfg).run(test_runner, state::noop());
+
+        shim.simulate_check_passed()?;
+        assert!(!test_runner_spy.run_called());
+
+        // when
+        shim.simulate_fmt_passed()?;
+
+        // then
+        assert!(test_runner_spy.run_called());
+        assert!(!test_runner_spy.run_called());
+
+        Ok(())
+    }
+
+    #[test]
+    fn tests_are_skipped_when_blocking_formatting_check_failed() -> Result<()> {
+        // given
+        init_tracing();
+        let (test_runner_spy, test_runner) = tracked(working(TestsRunStatus::Success));
+        let (spy, state) = state::tracked(&state::noop());
+        let cfg = ConfigBuilder::default()
+            .fmt_mode(FmtMode::Blocking)
+            .build()?;
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), cfg).run(test_runner, state);
+
+        // when
+        shim.simulate_check_passed()?;
+        shim.simulate_fmt_failed()?;
+
+        // then
+        assert!(spy.tests_state_called_with_val(&TestsState::Skipped {
+            reason: "fmt failed".into()
+        }));
+        assert!(!test_runner_spy.run_called());
+
+        Ok(())
+    }
+
     #[test]
     fn when_tests_pass_there_is_correct_event_on_the_bus() -> Result<()> {
         // given
@@ -94,7 +258,7 @@ mod test {
         let test_runner = working(TestsRunStatus::Success);
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, noop_state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, noop_state);
 
         // when
         shim.simulate_check_passed()?;
@@ -113,7 +277,7 @@ mod test {
         let test_runner = working(TestsRunStatus::Success);
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, state);
 
         // when
         shim.simulate_check_passed()?;
@@ -130,10 +294,10 @@ mod test {
     fn when_tests_fail_there_is_correct_event_on_the_bus() -> Result<()> {
         // given
         init_tracing();
-        let test_runner = working(TestsRunStatus::Failure);
+        let test_runner = working(TestsRunStatus::Failure(None));
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, noop_state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, noop_state);
 
         // when
         shim.simulate_check_passed()?;
@@ -149,10 +313,10 @@ mod test {
     fn when_tests_fail_state_is_set_to_pending_then_failure() -> Result<()> {
         // given
         init_tracing();
-        let test_runner = working(TestsRunStatus::Failure);
+        let test_runner = working(TestsRunStatus::Failure(None));
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, state);
 
         // when
         shim.simulate_check_passed()?;
@@ -172,7 +336,7 @@ mod test {
         let test_runner = failing();
         let noop_state = state::noop();
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, noop_state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, noop_state);
 
         // when
         shim.simulate_check_passed()?;
@@ -191,7 +355,7 @@ mod test {
         let test_runner = failing();
         let (spy, state) = state::tracked(&state::noop());
         let shim = create_test_shim()?;
-        TestsShell::new(shim.bus()).run(test_runner, state);
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, state);
 
         // when
         shim.simulate_check_passed()?;
@@ -203,4 +367,22 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn when_tests_fail_first_failed_test_is_saved() -> Result<()> {
+        // given
+        init_tracing();
+        let test_runner = working(TestsRunStatus::Failure(Some("some::test".into())));
+        let (spy, state) = state::tracked(&state::noop());
+        let shim = create_test_shim()?;
+        TestsShell::new(shim.bus(), Config::default()).run(test_runner, state);
+
+        // when
+        shim.simulate_check_passed()?;
+
+        // then
+        assert!(spy.failed_test_called_with_val(&Some("some::test".into())));
+
+        Ok(())
+    }
 }
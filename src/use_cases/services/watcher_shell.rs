@@ -1,33 +1,83 @@
+use crate::entities::watcher::WatcherState;
 use crate::result::WatcherErr;
 use crate::use_cases::bus::{BusEvent, EventBus};
 use crate::use_cases::change_watcher::ChangeWatcher;
-use crate::use_cases::state::StateReader;
+use crate::use_cases::state::State;
 
+use std::sync::{Arc, Mutex};
 use std::thread;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, trace};
 
 type Result<T> = std::result::Result<T, WatcherErr>;
 
 /// Watches for the change in the watched directory.
 ///
-/// When change appears, it publishes `BusEvent::ChangeDetected`.
+/// When change appears, it publishes `BusEvent::ChangeDetected`. Filesystem events are counted in
+/// the state metrics as soon as they are received, also the ones in the ignored paths.
+///
+/// Watching is paused and resumed by updating the watcher state, which publishes
+/// `BusEvent::WatcherPaused` and `BusEvent::WatcherResumed`. Changes detected during the pause are
+/// not published. Instead, a single `BusEvent::ChangeDetected` is published on resume if anything
+/// changed in the meantime.
 pub struct ChangeWatcherShell {
     bus: EventBus,
+    missed_change: Arc<Mutex<bool>>,
 }
 
 impl ChangeWatcherShell {
     pub fn new(bus: EventBus) -> Self {
-        Self { bus }
+        let missed_change = Arc::new(Mutex::new(false));
+        Self { bus, missed_change }
+    }
+
+    #[instrument(skip(self, change_watcher, state))]
+    pub fn run(self, change_watcher: ChangeWatcher, state: State) {
+        self.control_pause();
+        let publ = self.bus.publisher();
+        let missed_change = self.missed_change;
+        thread::spawn(move || -> Result<()> {
+            loop {
+                let watched = change_watcher.wait_for_events(state.reader().repo_root()?)?;
+                if !watched.has_change() {
+                    trace!("only ignored paths changed");
+                    state.writer().watched(watched)?;
+                    continue;
+                }
+                {
+                    // NOTE: The lock is held while reading the watcher state, so the change can't
+                    // be missed when the watcher is resumed at the same time.
+                    let mut missed_change = missed_change.lock().expect("poisoned mutex");
+                    if state.reader().watcher()? == WatcherState::Paused {
+                        debug!("detected change, but watcher is paused");
+                        *missed_change = true;
+                    } else {
+                        debug!("detected change");
+                        publ.send(BusEvent::ChangeDetected)?;
+                    }
+                }
+                // NOTE: Events are counted once the change is handled, so the watcher state
+                // can't be changed in between by whoever waits for them.
+                state.writer().watched(watched)?;
+            }
+        });
     }
 
-    #[instrument(skip(self, change_watcher))]
-    pub fn run(self, change_watcher: ChangeWatcher, state: StateReader) {
+    fn control_pause(&self) {
+        let sub = self.bus.subscriber();
         let publ = self.bus.publisher();
+        let missed_change = self.missed_change.clone();
         thread::spawn(move || -> Result<()> {
             loop {
-                change_watcher.wait_for_change(state.repo_root()?)?;
-                debug!("detected change");
-                publ.send(BusEvent::ChangeDetected)?;
+                let Ok(BusEvent::WatcherResumed) = sub.recv() else {
+                    trace!("not a resume event");
+                    continue;
+                };
+                let mut missed_change = missed_change.lock().expect("poisoned mutex");
+                if *missed_change {
+                    debug!("changes detected during the pause");
+                    *missed_change = false;
+                    publ.send(BusEvent::ChangeDetected)?;
+                }
             }
         });
     }
@@ -40,6 +90,8 @@ mod test {
     use crate::configuration::factories::{event_bus, state};
     use crate::configuration::tracing::init_tracing;
     use crate::entities::repo_root::RepoRoot;
+    use crate::entities::watcher::WatchedEvents;
+    use crate::testingtools;
     use crate::testingtools::unit::create_test_shim;
     use crate::use_cases::change_watcher::Watcher;
 
@@ -54,30 +106,155 @@ mod test {
         let change_watcher = MockChangeWatcher::make(shim.rx());
         let bus = event_bus()?;
         let state = state(bus.publisher());
-        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state.reader());
+        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state);
+
+        // when
+        shim.trigger_watcher()?;
+
+        // then
+        assert!(shim.event_on_bus(&BusEvent::ChangeDetected)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn watched_events_are_counted_in_state() -> Result<()> {
+        // given
+        init_tracing();
+        let mut shim = create_test_shim()?;
+        let change_watcher = MockChangeWatcher::make(shim.rx());
+        let (spy, state) = testingtools::state::tracked(&testingtools::state::noop());
+        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state);
+
+        // when
+        shim.trigger_watcher()?;
+
+        // then
+        assert!(spy.watched_called_with_val(&WatchedEvents {
+            received: 1,
+            ignored: 0,
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn events_in_ignored_paths_are_counted_without_publishing_change() -> Result<()> {
+        // given
+        init_tracing();
+        let mut shim = create_test_shim()?;
+        let events = WatchedEvents {
+            received: 2,
+            ignored: 2,
+        };
+        let change_watcher = MockChangeWatcher::make_with(shim.rx(), events);
+        let (spy, state) = testingtools::state::tracked(&testingtools::state::noop());
+        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state);
+
+        // when
+        shim.trigger_watcher()?;
+
+        // then
+        assert!(spy.watched_called_with_val(&events));
+        assert!(shim.no_event_on_bus()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn change_is_not_published_when_watcher_is_paused() -> Result<()> {
+        // given
+        init_tracing();
+        let mut shim = create_test_shim()?;
+        let change_watcher = MockChangeWatcher::make(shim.rx());
+        let (spy, state) = testingtools::state::tracked(&state(shim.bus().publisher()));
+        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state.clone());
+        state.writer().watcher(WatcherState::Paused)?;
+        shim.ignore_event()?; // ignore BusEvent::WatcherPaused
 
         // when
         shim.trigger_watcher()?;
 
+        // then
+        assert!(spy.watched_called_with_val(&WatchedEvents {
+            received: 1,
+            ignored: 0,
+        }));
+        assert!(shim.no_event_on_bus()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn changes_detected_during_pause_trigger_single_run_on_resume() -> Result<()> {
+        // given
+        init_tracing();
+        let mut shim = create_test_shim()?;
+        let change_watcher = MockChangeWatcher::make(shim.rx());
+        let (spy, state) = testingtools::state::tracked(&state(shim.bus().publisher()));
+        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state.clone());
+        state.writer().watcher(WatcherState::Paused)?;
+        shim.ignore_event()?; // ignore BusEvent::WatcherPaused
+        shim.trigger_watcher()?;
+        shim.trigger_watcher()?;
+        assert!(spy.watched_called()); // wait for the first change
+        assert!(spy.watched_called()); // wait for the second change
+
+        // when
+        state.writer().watcher(WatcherState::Watching)?;
+        shim.ignore_event()?; // ignore BusEvent::WatcherResumed
+
         // then
         assert!(shim.event_on_bus(&BusEvent::ChangeDetected)?);
+        assert!(shim.no_event_on_bus()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resume_without_changes_during_pause_does_not_trigger_run() -> Result<()> {
+        // given
+        init_tracing();
+        let mut shim = create_test_shim()?;
+        let change_watcher = MockChangeWatcher::make(shim.rx());
+        let state = state(shim.bus().publisher());
+        ChangeWatcherShell::new(shim.bus()).run(change_watcher, state.clone());
+        state.writer().watcher(WatcherState::Paused)?;
+        shim.ignore_event()?; // ignore BusEvent::WatcherPaused
+
+        // when
+        state.writer().watcher(WatcherState::Watching)?;
+        shim.ignore_event()?; // ignore BusEvent::WatcherResumed
+
+        // then
+        assert!(shim.no_event_on_bus()?);
 
         Ok(())
     }
 
     pub struct MockChangeWatcher {
         rx: Receiver<()>,
+        events: WatchedEvents,
     }
 
     impl MockChangeWatcher {
         fn make(rx: Receiver<()>) -> ChangeWatcher {
-            Box::new(Self { rx })
+            let events = WatchedEvents {
+                received: 1,
+                ignored: 0,
+            };
+            Self::make_with(rx, events)
+        }
+
+        fn make_with(rx: Receiver<()>, events: WatchedEvents) -> ChangeWatcher {
+            Box::new(Self { rx, events })
         }
     }
 
     impl Watcher for MockChangeWatcher {
-        fn wait_for_change(&self, _repo_root: RepoRoot) -> Result<(), WatcherErr> {
-            Ok(self.rx.recv()?)
+        fn wait_for_events(&self, _repo_root: RepoRoot) -> Result<WatchedEvents, WatcherErr> {
+            self.rx.recv()?;
+            Ok(self.events)
         }
     }
 }
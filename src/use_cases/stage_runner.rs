@@ -0,0 +1,12 @@
+use crate::entities::custom_stage::{CustomStage, CustomStageState};
+use crate::entities::repo_root::RepoRoot;
+use crate::result::StageErr;
+
+use std::sync::Arc;
+
+/// Shared, so the stages can be run in parallel.
+pub type StageRunner = Arc<dyn SRunner>;
+
+pub trait SRunner: Send + Sync {
+    fn run(&self, stage: &CustomStage, repo_root: RepoRoot) -> Result<CustomStageState, StageErr>;
+}
@@ -1,9 +1,23 @@
 use crate::entities::check::CheckState;
 use crate::entities::coverage::CoverageState;
+use crate::entities::coverage_summary::CoverageSummary;
+use crate::entities::custom_stage::CustomStageState;
+use crate::entities::diff_coverage::DiffCoverage;
+use crate::entities::file_coverage::FileCoverage;
+use crate::entities::fmt::FmtState;
+use crate::entities::provenance::{StageProvenance, TrackedResult, TreeSnapshot};
+use crate::entities::quality::QualityReport;
 use crate::entities::repo_root::RepoRoot;
+use crate::entities::run_id::RunId;
+use crate::entities::stage::Stage;
+use crate::entities::stage_timing::StageTiming;
+use crate::entities::state_snapshot::StateSnapshot;
+use crate::entities::stats::ProjectStats;
 use crate::entities::tests::TestsState;
+use crate::entities::watcher::{WatchedEvents, WatcherState};
 use crate::result::{StateReaderErr, StateWriterErr};
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -18,9 +32,19 @@ pub trait AppState: Sync + Send {
 
 pub trait AppStateReader: Sync + Send {
     fn tests(&self) -> Result<TestsState, StateReaderErr>;
+    fn failed_test(&self) -> Result<Option<String>, StateReaderErr>;
     fn check(&self) -> Result<CheckState, StateReaderErr>;
+    fn fmt(&self) -> Result<FmtState, StateReaderErr>;
     fn coverage(&self) -> Result<CoverageState, StateReaderErr>;
+    fn coverage_summary(&self) -> Result<Option<CoverageSummary>, StateReaderErr>;
+    fn coverage_files(&self) -> Result<Vec<FileCoverage>, StateReaderErr>;
+    fn diff_coverage(&self) -> Result<Option<DiffCoverage>, StateReaderErr>;
+    fn quality(&self) -> Result<Option<QualityReport>, StateReaderErr>;
+    fn stats(&self) -> Result<Option<ProjectStats>, StateReaderErr>;
+    fn custom_stages(&self) -> Result<HashMap<String, CustomStageState>, StateReaderErr>;
     fn repo_root(&self) -> Result<RepoRoot, StateReaderErr>;
+    fn watcher(&self) -> Result<WatcherState, StateReaderErr>;
+    fn snapshot(&self) -> Result<StateSnapshot, StateReaderErr>;
 }
 
 impl Debug for dyn AppStateReader {
@@ -33,9 +57,28 @@ impl Debug for dyn AppStateReader {
 
 pub trait AppStateWriter: Sync + Send {
     fn tests(&self, status: TestsState) -> Result<(), StateWriterErr>;
+    fn failed_test(&self, name: Option<String>) -> Result<(), StateWriterErr>;
     fn check(&self, status: CheckState) -> Result<(), StateWriterErr>;
+    fn fmt(&self, status: FmtState) -> Result<(), StateWriterErr>;
     fn coverage(&self, coverage: CoverageState) -> Result<(), StateWriterErr>;
+    fn coverage_summary(&self, summary: CoverageSummary) -> Result<(), StateWriterErr>;
+    fn coverage_files(&self, files: Vec<FileCoverage>) -> Result<(), StateWriterErr>;
+    fn diff_coverage(&self, diff: DiffCoverage) -> Result<(), StateWriterErr>;
+    fn quality(&self, report: QualityReport) -> Result<(), StateWriterErr>;
+    fn stats(&self, stats: ProjectStats) -> Result<(), StateWriterErr>;
+    fn tests_count(&self, count: u32) -> Result<(), StateWriterErr>;
+    fn source_tree(&self, tree: TreeSnapshot) -> Result<(), StateWriterErr>;
+    fn provenance(
+        &self,
+        result: TrackedResult,
+        provenance: StageProvenance,
+    ) -> Result<(), StateWriterErr>;
+    fn timing(&self, stage: Stage, timing: StageTiming) -> Result<(), StateWriterErr>;
+    fn custom_stage(&self, name: String, state: CustomStageState) -> Result<(), StateWriterErr>;
     fn repo_root(&self, repo_root: RepoRoot) -> Result<(), StateWriterErr>;
+    fn trigger(&self, stage: Stage) -> Result<RunId, StateWriterErr>;
+    fn watcher(&self, watcher: WatcherState) -> Result<(), StateWriterErr>;
+    fn watched(&self, events: WatchedEvents) -> Result<(), StateWriterErr>;
 }
 
 #[cfg(test)]
@@ -69,16 +112,56 @@ mod test {
             Ok(TestsState::Pending)
         }
 
+        fn failed_test(&self) -> Result<Option<String>, StateReaderErr> {
+            Ok(None)
+        }
+
         fn check(&self) -> Result<CheckState, StateReaderErr> {
             Ok(CheckState::Pending)
         }
 
+        fn fmt(&self) -> Result<FmtState, StateReaderErr> {
+            Ok(FmtState::Pending)
+        }
+
         fn coverage(&self) -> Result<CoverageState, StateReaderErr> {
             Ok(CoverageState::Pending)
         }
 
+        fn coverage_summary(&self) -> Result<Option<CoverageSummary>, StateReaderErr> {
+            Ok(None)
+        }
+
+        fn coverage_files(&self) -> Result<Vec<FileCoverage>, StateReaderErr> {
+            Ok(Vec::new())
+        }
+
+        fn diff_coverage(&self) -> Result<Option<DiffCoverage>, StateReaderErr> {
+            Ok(None)
+        }
+
+        fn quality(&self) -> Result<Option<QualityReport>, StateReaderErr> {
+            Ok(None)
+        }
+
+        fn stats(&self) -> Result<Option<ProjectStats>, StateReaderErr> {
+            Ok(None)
+        }
+
+        fn custom_stages(&self) -> Result<HashMap<String, CustomStageState>, StateReaderErr> {
+            Ok(HashMap::new())
+        }
+
         fn repo_root(&self) -> Result<RepoRoot, StateReaderErr> {
             Ok(RepoRoot::new("/some/path"))
         }
+
+        fn watcher(&self) -> Result<WatcherState, StateReaderErr> {
+            Ok(WatcherState::Watching)
+        }
+
+        fn snapshot(&self) -> Result<StateSnapshot, StateReaderErr> {
+            Ok(StateSnapshot::default())
+        }
     }
 }
@@ -11,5 +11,6 @@ pub trait TRunner: Send {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TestsRunStatus {
     Success,
-    Failure,
+    /// Tests failed, with the name of the first failing test if it's known.
+    Failure(Option<String>),
 }
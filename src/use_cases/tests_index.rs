@@ -5,6 +5,8 @@ pub type TestsIndex = Box<dyn TIndex>;
 
 pub trait TIndex: Send {
     fn refresh(&self, repo_root: RepoRoot) -> Result<IndexStatus, IndexErr>;
+    /// Number of the tests listed by the last refresh, none until the tests were listed.
+    fn tests_count(&self) -> Option<u32>;
 }
 
 #[allow(unused)]
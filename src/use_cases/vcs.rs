@@ -0,0 +1,22 @@
+use crate::entities::diff_coverage::ChangedLines;
+use crate::entities::provenance::Fingerprint;
+use crate::entities::repo_root::RepoRoot;
+use crate::result::VcsErr;
+
+use std::sync::Arc;
+
+pub type VersionControl = Arc<dyn Vcs>;
+
+pub trait Vcs: Send + Sync {
+    /// Returns identifier of the currently checked out commit or `None` if there is no such
+    /// commit (for example the directory is not a repository).
+    fn head(&self, repo_root: RepoRoot) -> Result<Option<String>, VcsErr>;
+
+    /// Returns lines added or modified compared to the base commit, including uncommitted
+    /// changes.
+    fn changes(&self, repo_root: RepoRoot) -> Result<Vec<ChangedLines>, VcsErr>;
+
+    /// Returns the fingerprint of the current content of the repository, including uncommitted
+    /// and untracked files.
+    fn fingerprint(&self, repo_root: RepoRoot) -> Result<Fingerprint, VcsErr>;
+}